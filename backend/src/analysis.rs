@@ -0,0 +1,6 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+pub mod duplicates;
+pub mod repro;
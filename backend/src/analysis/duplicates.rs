@@ -0,0 +1,326 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Detection of submissions that are likely duplicates of each other.
+//!
+//! Duplicates happen when a unit is resold and contributed again, or when a submission directory
+//! is accidentally copied. Each pair of submissions gets a list of signals, and none of them is
+//! conclusive on its own.
+
+use anyhow::Error;
+use image::imageops::FilterType;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    fmt, fs,
+    hash::Hasher,
+    path::Path,
+};
+
+use crate::db::{Category, Submission};
+
+/// Largest perceptual hash distance at which two photos are considered near-identical
+pub const DEFAULT_MAX_PHOTO_DISTANCE: u32 = 4;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DuplicateSignal {
+    /// Both consoles have the same shell serial number
+    SameSerial { serial: String },
+    /// The boards have the same labels and dates, all chips have the same labels, and the
+    /// submissions have the same game or console code
+    SameBoard,
+    /// A photo file in one submission is byte-identical to a photo in the other
+    IdenticalPhoto { a: &'static str, b: &'static str },
+    /// Photos look nearly identical after scaling them down
+    SimilarPhoto {
+        a: &'static str,
+        b: &'static str,
+        distance: u32,
+    },
+}
+
+impl fmt::Display for DuplicateSignal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DuplicateSignal::SameSerial { serial } => write!(f, "Same serial number {serial}"),
+            DuplicateSignal::SameBoard => write!(f, "Same board and chip labels"),
+            DuplicateSignal::IdenticalPhoto { a, b } => write!(f, "Identical photos {a} and {b}"),
+            DuplicateSignal::SimilarPhoto { a, b, distance } => {
+                write!(f, "Similar photos {a} and {b} (distance {distance})")
+            }
+        }
+    }
+}
+
+/// A pair of submissions that are likely duplicates
+#[derive(Clone, Debug)]
+pub struct Duplicate<'a> {
+    pub a: &'a Submission,
+    pub b: &'a Submission,
+    pub signals: Vec<DuplicateSignal>,
+}
+
+/// Content hashes of one photo file
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PhotoFingerprint {
+    pub len: u64,
+    /// Hash of the file contents
+    pub content: u64,
+    /// Difference hash of the decoded image, or None if the file can't be decoded
+    pub perceptual: Option<u64>,
+}
+
+impl PhotoFingerprint {
+    pub fn compute(path: &Path) -> Result<PhotoFingerprint, Error> {
+        let bytes = fs::read(path)?;
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&bytes);
+        let perceptual = image::load_from_memory(&bytes)
+            .ok()
+            .map(|img| difference_hash(&img));
+        Ok(PhotoFingerprint {
+            len: bytes.len() as u64,
+            content: hasher.finish(),
+            perceptual,
+        })
+    }
+}
+
+/// Computes a 64-bit difference hash, which has one bit for each pair of horizontally adjacent
+/// pixels in a 9x8 grayscale version of the image
+fn difference_hash(img: &image::DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(brighter);
+        }
+    }
+    hash
+}
+
+/// Photo of a submission, identified by the submission index and the photo name
+#[derive(Copy, Clone, Debug)]
+pub struct PhotoEntry {
+    pub submission: usize,
+    pub name: &'static str,
+    pub fingerprint: PhotoFingerprint,
+}
+
+/// Fingerprints all photos of the given submissions.
+///
+/// Photos that can't be read are returned separately with their errors.
+pub fn fingerprint_photos(submissions: &[Submission]) -> (Vec<PhotoEntry>, Vec<(&Path, Error)>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (index, submission) in submissions.iter().enumerate() {
+        for photo in &submission.photos {
+            match PhotoFingerprint::compute(&photo.path) {
+                Ok(fingerprint) => entries.push(PhotoEntry {
+                    submission: index,
+                    name: photo.name,
+                    fingerprint,
+                }),
+                Err(err) => errors.push((photo.path.as_path(), err)),
+            }
+        }
+    }
+    (entries, errors)
+}
+
+/// Normalizes a serial number so that differences in case and whitespace don't matter
+fn normalize_serial(serial: &str) -> String {
+    serial
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Returns the board metadata as a string if at least one chip on the board has a label
+fn labeled_board(submission: &Submission) -> Option<String> {
+    if !submission
+        .chips
+        .iter()
+        .any(|chip| chip.chip.label.is_some())
+    {
+        return None;
+    }
+    let pointer = match submission.category {
+        Category::Cartridge => "/board",
+        Category::Console(_) => "/mainboard",
+    };
+    Some(submission.metadata.pointer(pointer)?.to_string())
+}
+
+/// Finds likely duplicates among the submissions.
+///
+/// Photos are only compared if their fingerprints are given. Photos whose perceptual hashes
+/// differ by at most `max_photo_distance` bits are considered near-identical.
+pub fn find_duplicates<'a>(
+    submissions: &'a [Submission],
+    photos: &[PhotoEntry],
+    max_photo_distance: u32,
+) -> Vec<Duplicate<'a>> {
+    let mut pairs: BTreeMap<(usize, usize), Vec<DuplicateSignal>> = BTreeMap::new();
+    let mut add_pairs = |indices: &[usize], signal: &dyn Fn() -> DuplicateSignal| {
+        for (i, &a) in indices.iter().enumerate() {
+            for &b in &indices[i + 1..] {
+                pairs
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push(signal());
+            }
+        }
+    };
+
+    let mut serials: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut boards: BTreeMap<(&str, &str, String), Vec<usize>> = BTreeMap::new();
+    for (index, submission) in submissions.iter().enumerate() {
+        let serial = submission
+            .metadata
+            .pointer("/shell/serial")
+            .and_then(|serial| serial.as_str())
+            .map(normalize_serial)
+            .filter(|serial| !serial.is_empty());
+        if let (Category::Console(_), Some(serial)) = (submission.category, serial) {
+            serials.entry(serial).or_default().push(index);
+        }
+        if let Some(board) = labeled_board(submission) {
+            boards
+                .entry((submission.category.id(), &submission.code, board))
+                .or_default()
+                .push(index);
+        }
+    }
+    for (serial, indices) in &serials {
+        add_pairs(indices, &|| DuplicateSignal::SameSerial {
+            serial: serial.clone(),
+        });
+    }
+    for indices in boards.values() {
+        add_pairs(indices, &|| DuplicateSignal::SameBoard);
+    }
+
+    for (i, a) in photos.iter().enumerate() {
+        for b in &photos[i + 1..] {
+            if a.submission == b.submission {
+                continue;
+            }
+            // Photo names are listed in the same order as the submissions
+            let (a, b) = if a.submission < b.submission {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            let (fa, fb) = (a.fingerprint, b.fingerprint);
+            let signal = if fa.len == fb.len && fa.content == fb.content {
+                DuplicateSignal::IdenticalPhoto {
+                    a: a.name,
+                    b: b.name,
+                }
+            } else {
+                match (fa.perceptual, fb.perceptual) {
+                    (Some(ha), Some(hb)) if (ha ^ hb).count_ones() <= max_photo_distance => {
+                        DuplicateSignal::SimilarPhoto {
+                            a: a.name,
+                            b: b.name,
+                            distance: (ha ^ hb).count_ones(),
+                        }
+                    }
+                    _ => continue,
+                }
+            };
+            pairs
+                .entry((a.submission, b.submission))
+                .or_default()
+                .push(signal);
+        }
+    }
+
+    pairs
+        .into_iter()
+        .map(|((a, b), signals)| Duplicate {
+            a: &submissions[a],
+            b: &submissions[b],
+            signals,
+        })
+        .collect()
+}
+
+#[test]
+fn test_find_duplicates() {
+    use crate::{
+        db::{chip::ChipData, SubmissionChip},
+        Console,
+    };
+    use serde_json::json;
+
+    let submission = |category, slug: &str, serial: &str, label: &str| Submission {
+        category,
+        code: "DMG".to_owned(),
+        slug: slug.to_owned(),
+        contributor: "test".to_owned(),
+        title: slug.to_owned(),
+        board: "DMG-CPU-06".to_owned(),
+        outlier: false,
+        chips: vec![SubmissionChip {
+            designator: "U1",
+            role: "CPU",
+            chip: ChipData::from_label(label),
+        }],
+        photos: Vec::new(),
+        metadata: json!({
+            "shell": { "serial": serial },
+            "mainboard": { "label": "DMG-CPU-06", "u1": { "label": label } },
+        }),
+        data: std::sync::Arc::new(()),
+    };
+    let dmg = Category::Console(Console::Dmg);
+    let submissions = [
+        submission(dmg, "G10000000", "G10000000", "DMG-CPU B 8905 D"),
+        submission(dmg, "G10000001", "g1000 0000", "DMG-CPU B 8905 D"),
+        submission(dmg, "G10000002", "G10000002", "DMG-CPU C 9402 D"),
+    ];
+    let photo = |submission, content, perceptual| PhotoEntry {
+        submission,
+        name: "01_front.jpg",
+        fingerprint: PhotoFingerprint {
+            len: 100,
+            content,
+            perceptual: Some(perceptual),
+        },
+    };
+    let photos = [photo(0, 1, 0xff00), photo(2, 2, 0xff01), photo(1, 3, 0)];
+
+    let duplicates = find_duplicates(&submissions, &photos, DEFAULT_MAX_PHOTO_DISTANCE);
+    let pairs = duplicates
+        .iter()
+        .map(|d| (d.a.slug.as_str(), d.b.slug.as_str(), d.signals.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        [
+            (
+                "G10000000",
+                "G10000001",
+                vec![
+                    DuplicateSignal::SameSerial {
+                        serial: "G10000000".to_owned()
+                    },
+                    DuplicateSignal::SameBoard,
+                ]
+            ),
+            (
+                "G10000000",
+                "G10000002",
+                vec![DuplicateSignal::SimilarPhoto {
+                    a: "01_front.jpg",
+                    b: "01_front.jpg",
+                    distance: 1
+                }]
+            ),
+        ]
+    );
+}
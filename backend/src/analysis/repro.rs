@@ -0,0 +1,340 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Heuristics for detecting reproduction and counterfeit cartridges.
+//!
+//! None of the checks is conclusive on its own, so each finding only adds to a score which
+//! describes how likely it is that the cartridge is not a genuine one.
+
+use regex::Regex;
+use std::{fmt, sync::OnceLock};
+
+use crate::{
+    config::cartridge::{
+        game_id, BoardLayout, ChipRole, ChipRoleConfig, GameConfig, PartDesignator,
+    },
+    input::cartridge::Cartridge,
+    parser::{self, LabelParser, Year},
+};
+
+/// Last year of Game Boy cartridge production
+const LAST_PRODUCTION_YEAR: u16 = 2009;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReproFinding {
+    /// A flash chip is used where a mask ROM is expected
+    FlashRom { designator: PartDesignator },
+    /// The board label is not in the known board catalog
+    UnknownBoard { label: String },
+    /// The mask ROM is for some other game
+    RomCodeMismatch {
+        designator: PartDesignator,
+        rom_code: String,
+    },
+    /// A chip is dated after the end of production or after the board itself
+    InconsistentDate {
+        designator: PartDesignator,
+        year: u16,
+    },
+    /// A battery-backed layout lacks the supervisor chip that protects the save RAM
+    MissingSupervisor { designator: PartDesignator },
+    /// The board (designator None) or the mask ROM is dated before the game was released
+    PredatesRelease {
+        designator: Option<PartDesignator>,
+        year: u16,
+        release_year: u16,
+    },
+}
+
+impl ReproFinding {
+    pub fn weight(&self) -> u8 {
+        match self {
+            ReproFinding::FlashRom { .. } => 50,
+            ReproFinding::RomCodeMismatch { .. } => 40,
+            ReproFinding::UnknownBoard { .. } => 30,
+            ReproFinding::MissingSupervisor { .. } => 30,
+            ReproFinding::InconsistentDate { .. } => 20,
+            ReproFinding::PredatesRelease { .. } => 20,
+        }
+    }
+}
+
+impl fmt::Display for ReproFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReproFinding::FlashRom { designator } => {
+                write!(
+                    f,
+                    "{}: flash chip instead of a mask ROM",
+                    designator.as_str()
+                )
+            }
+            ReproFinding::UnknownBoard { label } => write!(f, "Unknown board {label}"),
+            ReproFinding::RomCodeMismatch {
+                designator,
+                rom_code,
+            } => write!(
+                f,
+                "{}: ROM code {rom_code} doesn't match the game",
+                designator.as_str()
+            ),
+            ReproFinding::InconsistentDate { designator, year } => write!(
+                f,
+                "{}: chip year {year} is inconsistent with the board",
+                designator.as_str()
+            ),
+            ReproFinding::MissingSupervisor { designator } => write!(
+                f,
+                "{}: supervisor chip missing on a battery-backed board",
+                designator.as_str()
+            ),
+            ReproFinding::PredatesRelease {
+                designator,
+                year,
+                release_year,
+            } => write!(
+                f,
+                "{}: year {year} is before the release of the game ({release_year})",
+                designator.map_or("Board", |designator| designator.as_str())
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReproReport {
+    pub findings: Vec<ReproFinding>,
+}
+
+impl ReproReport {
+    /// Likelihood of the cartridge being a reproduction, from 0 to 100
+    pub fn score(&self) -> u8 {
+        self.findings
+            .iter()
+            .map(|finding| u32::from(finding.weight()))
+            .sum::<u32>()
+            .min(100) as u8
+    }
+    pub fn is_likely_repro(&self) -> bool {
+        self.score() >= 50
+    }
+}
+
+fn is_flash_label(label: &str) -> bool {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX
+        .get_or_init(|| Regex::new(r#"(29F|29LV|29DL|29GL|29W|39SF|39VF)[0-9]"#).unwrap())
+        .is_match(label)
+        || parser::flash::flash().parse(label).is_ok()
+}
+
+fn chip_year(role: ChipRole, label: &str) -> Option<Year> {
+    match role {
+        ChipRole::Rom => parser::mask_rom::mask_rom().parse(label).ok()?.year,
+        ChipRole::Mapper => parser::mapper::mapper().parse(label).ok()?.year,
+        ChipRole::Ram => parser::ram::ram().parse(label).ok()?.year,
+        ChipRole::SupervisorReset => {
+            parser::supervisor_reset::supervisor_reset()
+                .parse(label)
+                .ok()?
+                .year
+        }
+        _ => None,
+    }
+}
+
+/// Returns the board year and the mask ROM years of a cartridge.
+///
+/// The board year has no designator.
+fn release_dates(cartridge: &Cartridge, cfg: &GameConfig) -> Vec<(Option<PartDesignator>, u16)> {
+    let board = &cartridge.board;
+    let mut dates = board
+        .year
+        .map(|year| (None, year))
+        .into_iter()
+        .collect::<Vec<_>>();
+    let layout = BoardLayout::resolve(&board.label, cfg);
+    let roles = layout.map(ChipRoleConfig::from).unwrap_or_default();
+    for (designator, role) in roles.iter().filter(|&(_, role)| role == ChipRole::Rom) {
+        let year = (board[designator].as_ref())
+            .and_then(|chip| chip.label.as_deref())
+            .filter(|label| !is_flash_label(label))
+            .and_then(|label| chip_year(role, label))
+            .and_then(|year| year.full_year(board.year));
+        if let Some(year) = year {
+            dates.push((Some(designator), year));
+        }
+    }
+    dates
+}
+
+/// Returns the reference release year of a game.
+///
+/// This is the earliest board or mask ROM year among the cartridges of the game that have no
+/// findings, because a genuine board or ROM can't be older than the game itself.
+pub fn release_year<'a>(
+    cartridges: impl IntoIterator<Item = &'a Cartridge>,
+    cfg: &GameConfig,
+) -> Option<u16> {
+    cartridges
+        .into_iter()
+        .filter(|cartridge| analyze(cartridge, cfg, None).findings.is_empty())
+        .flat_map(|cartridge| release_dates(cartridge, cfg))
+        .map(|(_, year)| year)
+        .min()
+}
+
+/// Analyzes a cartridge, optionally comparing its dates to the release year of the game (see
+/// `release_year`)
+pub fn analyze(cartridge: &Cartridge, cfg: &GameConfig, release_year: Option<u16>) -> ReproReport {
+    let mut findings = Vec::new();
+    let board = &cartridge.board;
+    let layout = BoardLayout::resolve(&board.label, cfg);
+    if layout.is_none() && !cfg.is_unlicensed() {
+        findings.push(ReproFinding::UnknownBoard {
+            label: board.label.clone(),
+        });
+    }
+    let layout = layout.or_else(|| cfg.layouts.first().copied());
+    let roles = layout.map(ChipRoleConfig::from).unwrap_or_default();
+    let has_battery = roles.iter().any(|(_, role)| role == ChipRole::Battery);
+    for (designator, role) in roles.iter() {
+        let chip = &board[designator];
+        if role == ChipRole::SupervisorReset && has_battery && chip.is_none() {
+            findings.push(ReproFinding::MissingSupervisor { designator });
+        }
+        let label = match chip.as_ref().and_then(|chip| chip.label.as_deref()) {
+            Some(label) => label,
+            None => continue,
+        };
+        if role == ChipRole::Rom {
+            if is_flash_label(label) {
+                findings.push(ReproFinding::FlashRom { designator });
+            } else if let Ok(rom) = parser::mask_rom::mask_rom().parse(label) {
+                if game_id(&rom.rom_code) != game_id(&cartridge.code) {
+                    findings.push(ReproFinding::RomCodeMismatch {
+                        designator,
+                        rom_code: rom.rom_code,
+                    });
+                }
+            }
+        }
+        let year = chip_year(role, label).and_then(|year| year.full_year(board.year));
+        if let Some(year) = year {
+            let is_after_board = board.year.map(|board_year| year > board_year + 1);
+            if year > LAST_PRODUCTION_YEAR || is_after_board == Some(true) {
+                findings.push(ReproFinding::InconsistentDate { designator, year });
+            }
+        }
+    }
+    if let Some(release_year) = release_year {
+        for (designator, year) in release_dates(cartridge, cfg) {
+            if year + 1 < release_year {
+                findings.push(ReproFinding::PredatesRelease {
+                    designator,
+                    year,
+                    release_year,
+                });
+            }
+        }
+    }
+    ReproReport { findings }
+}
+
+#[cfg(test)]
+fn test_cfg() -> GameConfig {
+    GameConfig {
+        rom_id: "CGB-AFIP-0".to_owned(),
+        name: "F-1 World Grand Prix (Europe) (En,Fr,De,Es)".to_owned(),
+        rom_verified: true,
+        sha256: None,
+        platform: crate::config::cartridge::GamePlatform::Gbc,
+        layouts: vec![BoardLayout::RomMapperRam],
+        publisher: None,
+        mapper: None,
+    }
+}
+
+#[cfg(test)]
+fn test_cartridge() -> Cartridge {
+    use crate::input::{cartridge::CartridgeBoard, Chip};
+
+    Cartridge {
+        code: "CGB-AFIP-0".to_owned(),
+        board: CartridgeBoard {
+            label: "DMG-A08-01".to_owned(),
+            year: Some(1999),
+            u1: Some(Chip::from_label(Some(
+                "CGB-AFIP-0 S LH537MTJ JAPAN G2 9929 D".to_owned(),
+            ))),
+            u2: Some(Chip::from_label(Some("MBC5 LZ9GB31 9922 A".to_owned()))),
+            u3: Some(Chip::from_label(Some(
+                "HY6264A LJ-10 9814B KOREA".to_owned(),
+            ))),
+            u4: Some(Chip::from_label(Some("915 134A".to_owned()))),
+            ..CartridgeBoard::default()
+        },
+        ..Cartridge::default()
+    }
+}
+
+#[test]
+fn test_analyze() {
+    use crate::input::Chip;
+
+    let cfg = test_cfg();
+    let mut cartridge = test_cartridge();
+    assert_eq!(analyze(&cartridge, &cfg, None).score(), 0);
+
+    cartridge.board.label = "GB-FLASH-32M".to_owned();
+    cartridge.board.u1 = Some(Chip::from_label(Some("AM29F016B-90EC".to_owned())));
+    cartridge.board.u4 = None;
+    let report = analyze(&cartridge, &cfg, None);
+    assert_eq!(
+        report.findings,
+        vec![
+            ReproFinding::UnknownBoard {
+                label: "GB-FLASH-32M".to_owned()
+            },
+            ReproFinding::FlashRom {
+                designator: PartDesignator::U1
+            },
+            ReproFinding::MissingSupervisor {
+                designator: PartDesignator::U4
+            },
+        ]
+    );
+    assert!(report.is_likely_repro());
+}
+
+#[test]
+fn test_release_year() {
+    use crate::input::Chip;
+
+    let cfg = test_cfg();
+    let genuine = test_cartridge();
+    // Mask ROM from 1996 on a board without a supervisor chip
+    let mut suspicious = test_cartridge();
+    suspicious.board.u1 = Some(Chip::from_label(Some(
+        "CGB-AFIP-0 S LH537MTJ JAPAN G2 9629 D".to_owned(),
+    )));
+    suspicious.board.u4 = None;
+
+    let release_year = release_year([&genuine, &suspicious], &cfg);
+    assert_eq!(release_year, Some(1999));
+    assert_eq!(analyze(&genuine, &cfg, release_year).score(), 0);
+    assert_eq!(
+        analyze(&suspicious, &cfg, release_year).findings,
+        vec![
+            ReproFinding::MissingSupervisor {
+                designator: PartDesignator::U4
+            },
+            ReproFinding::PredatesRelease {
+                designator: Some(PartDesignator::U1),
+                year: 1996,
+                release_year: 1999
+            },
+        ]
+    );
+}
@@ -3,3 +3,5 @@
 // SPDX-License-Identifier: MIT
 
 pub mod cartridge;
+pub mod console;
+pub mod mainboard;
@@ -8,14 +8,14 @@ use serde_json;
 use std::{
     collections::{BTreeMap, HashMap},
     fmt,
-    fs::File,
-    io::{BufReader, BufWriter},
+    fs::{self, File},
+    io::BufReader,
     ops::{Index, IndexMut},
     path::Path,
     sync::OnceLock,
 };
 
-use crate::sha256::Sha256;
+use crate::{format::to_canonical_json, parser::MapperType, sha256::Sha256, ParseError};
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub struct GameConfig {
@@ -27,6 +27,85 @@ pub struct GameConfig {
     pub sha256: Option<Sha256>,
     pub platform: GamePlatform,
     pub layouts: Vec<BoardLayout>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// Mapper of an unlicensed release. Unlicensed mappers are often hidden in epoxy blobs, so
+    /// they can't be identified from the chip labels
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapper: Option<MapperType>,
+}
+
+/// Returns the game part of a code, e.g. `AFI` in `CGB-AFIP-0`.
+///
+/// The 4th character of a 4-character game id is a region code, so it's ignored.
+pub fn game_id(code: &str) -> Option<&str> {
+    let id = code
+        .split('-')
+        .find(|part| !matches!(*part, "DIS" | "DMG" | "CGB"))?;
+    Some(if id.len() == 4 { &id[..3] } else { id })
+}
+
+impl GameConfig {
+    pub fn is_unlicensed(&self) -> bool {
+        UnlicensedCode::is_unlicensed(&self.rom_id)
+    }
+}
+
+/// Release code of a game that was not released through Nintendo.
+///
+/// Unlicensed releases have no Nintendo-assigned code, so they use the scheme
+/// `UNL-<publisher>-<title>-<revision>`, e.g. `UNL-WT-BIBLE-0`.
+///
+/// ```
+/// use gbhwdb_backend::config::cartridge::UnlicensedCode;
+/// let code = UnlicensedCode::parse("UNL-SACHEN-TAIKU-0").unwrap();
+/// assert_eq!(code.publisher, "SACHEN");
+/// assert_eq!(code.title, "TAIKU");
+/// assert_eq!(code.revision, 0);
+/// assert!(UnlicensedCode::parse("DMG-TRA-1").is_err());
+/// assert!(UnlicensedCode::parse("UNL-WT-bible-0").is_err());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnlicensedCode {
+    pub publisher: String,
+    pub title: String,
+    pub revision: u8,
+}
+
+impl UnlicensedCode {
+    pub const PREFIX: &'static str = "UNL-";
+
+    pub fn is_unlicensed(code: &str) -> bool {
+        code.starts_with(UnlicensedCode::PREFIX)
+    }
+    pub fn parse(code: &str) -> Result<UnlicensedCode, ParseError> {
+        fn is_valid_part(part: &str) -> bool {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        }
+        let mut parts = code
+            .strip_prefix(UnlicensedCode::PREFIX)
+            .ok_or(ParseError("missing UNL- prefix"))?
+            .split('-');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(publisher), Some(title), Some(revision), None)
+                if is_valid_part(publisher) && is_valid_part(title) =>
+            {
+                Ok(UnlicensedCode {
+                    publisher: publisher.to_owned(),
+                    title: title.to_owned(),
+                    revision: revision
+                        .parse()
+                        .map_err(|_| ParseError("invalid revision"))?,
+                })
+            }
+            _ => Err(ParseError("invalid unlicensed code")),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
@@ -71,6 +150,12 @@ pub enum BoardLayout {
     Huc3,
     #[serde(rename = "tama")]
     Tama,
+    #[serde(rename = "unl_rom")]
+    UnlRom,
+    #[serde(rename = "unl_rom_mapper")]
+    UnlRomMapper,
+    #[serde(rename = "unl_blob")]
+    UnlBlob,
 }
 
 fn create_map() -> HashMap<&'static str, BoardLayout> {
@@ -138,6 +223,22 @@ impl BoardLayout {
             .and_then(|key| map.get(key).cloned())
             .or_else(|| map.get(label).cloned())
     }
+    /// Resolves the layout of a board used in a cartridge of the given game.
+    ///
+    /// Unlicensed boards rarely have a label that identifies the layout, so they fall back to the
+    /// layout in the game config if it's unambiguous.
+    pub fn resolve(label: &str, cfg: &GameConfig) -> Option<BoardLayout> {
+        BoardLayout::from_label(label).or_else(|| match cfg.layouts.as_slice() {
+            &[layout] if cfg.is_unlicensed() && layout.is_unlicensed() => Some(layout),
+            _ => None,
+        })
+    }
+    pub fn is_unlicensed(&self) -> bool {
+        matches!(
+            self,
+            BoardLayout::UnlRom | BoardLayout::UnlRomMapper | BoardLayout::UnlBlob
+        )
+    }
 }
 
 pub fn load_cfgs<P: AsRef<Path>>(path: P) -> Result<BTreeMap<String, GameConfig>, Error> {
@@ -154,13 +255,11 @@ pub fn write_cfgs<P: AsRef<Path>>(
     path: P,
     cfgs: &BTreeMap<String, GameConfig>,
 ) -> Result<(), Error> {
-    let file = File::create(path)?;
-    let file = BufWriter::new(file);
-    serde_json::to_writer_pretty(file, cfgs)?;
+    fs::write(path, to_canonical_json(cfgs)?)?;
     Ok(())
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum ChipRole {
     Unknown,
     Rom,
@@ -175,6 +274,7 @@ pub enum ChipRole {
     HexInverter,
     Mcu,
     Rtc,
+    Battery,
 }
 
 impl ChipRole {
@@ -193,6 +293,7 @@ impl ChipRole {
             ChipRole::HexInverter => "Hex inverter",
             ChipRole::Mcu => "Microcontroller",
             ChipRole::Rtc => "RTC",
+            ChipRole::Battery => "Battery",
         }
     }
 }
@@ -207,10 +308,11 @@ pub enum PartDesignator {
     U6,
     U7,
     X1,
+    Bt1,
 }
 
 impl PartDesignator {
-    const ALL: [PartDesignator; 8] = [
+    pub const ALL: [PartDesignator; 9] = [
         PartDesignator::U1,
         PartDesignator::U2,
         PartDesignator::U3,
@@ -219,6 +321,7 @@ impl PartDesignator {
         PartDesignator::U6,
         PartDesignator::U7,
         PartDesignator::X1,
+        PartDesignator::Bt1,
     ];
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -230,6 +333,7 @@ impl PartDesignator {
             PartDesignator::U6 => "U6",
             PartDesignator::U7 => "U7",
             PartDesignator::X1 => "X1",
+            PartDesignator::Bt1 => "BT1",
         }
     }
 }
@@ -244,6 +348,7 @@ pub struct ChipRoleConfig {
     pub u6: Option<ChipRole>,
     pub u7: Option<ChipRole>,
     pub x1: Option<ChipRole>,
+    pub bt1: Option<ChipRole>,
 }
 
 impl Index<PartDesignator> for ChipRoleConfig {
@@ -259,6 +364,7 @@ impl Index<PartDesignator> for ChipRoleConfig {
             PartDesignator::U6 => &self.u6,
             PartDesignator::U7 => &self.u7,
             PartDesignator::X1 => &self.x1,
+            PartDesignator::Bt1 => &self.bt1,
         }
     }
 }
@@ -274,6 +380,7 @@ impl IndexMut<PartDesignator> for ChipRoleConfig {
             PartDesignator::U6 => &mut self.u6,
             PartDesignator::U7 => &mut self.u7,
             PartDesignator::X1 => &mut self.x1,
+            PartDesignator::Bt1 => &mut self.bt1,
         }
     }
 }
@@ -303,6 +410,7 @@ impl From<BoardLayout> for ChipRoleConfig {
                 u2: Some(ChipRole::Mapper),
                 u3: Some(ChipRole::Ram),
                 u4: Some(ChipRole::SupervisorReset),
+                bt1: Some(ChipRole::Battery),
                 ..ChipRoleConfig::default()
             },
             BoardLayout::RomMapperRamXtal => ChipRoleConfig {
@@ -311,12 +419,14 @@ impl From<BoardLayout> for ChipRoleConfig {
                 u3: Some(ChipRole::Ram),
                 u4: Some(ChipRole::SupervisorReset),
                 x1: Some(ChipRole::Crystal),
+                bt1: Some(ChipRole::Battery),
                 ..ChipRoleConfig::default()
             },
             BoardLayout::Mbc2 => ChipRoleConfig {
                 u1: Some(ChipRole::Rom),
                 u2: Some(ChipRole::Mapper),
                 u3: Some(ChipRole::SupervisorReset),
+                bt1: Some(ChipRole::Battery),
                 ..ChipRoleConfig::default()
             },
             BoardLayout::Mbc6 => ChipRoleConfig {
@@ -325,6 +435,7 @@ impl From<BoardLayout> for ChipRoleConfig {
                 u3: Some(ChipRole::Flash),
                 u4: Some(ChipRole::Ram),
                 u5: Some(ChipRole::SupervisorReset),
+                bt1: Some(ChipRole::Battery),
                 ..ChipRoleConfig::default()
             },
             BoardLayout::Mbc7 => ChipRoleConfig {
@@ -341,6 +452,7 @@ impl From<BoardLayout> for ChipRoleConfig {
                 u4: Some(ChipRole::SupervisorReset),
                 u5: Some(ChipRole::Rom),
                 u6: Some(ChipRole::LineDecoder),
+                bt1: Some(ChipRole::Battery),
                 ..ChipRoleConfig::default()
             },
             BoardLayout::Huc3 => ChipRoleConfig {
@@ -350,6 +462,7 @@ impl From<BoardLayout> for ChipRoleConfig {
                 u4: Some(ChipRole::SupervisorReset),
                 u5: Some(ChipRole::HexInverter),
                 x1: Some(ChipRole::Crystal),
+                bt1: Some(ChipRole::Battery),
                 ..ChipRoleConfig::default()
             },
             BoardLayout::Tama => ChipRoleConfig {
@@ -359,8 +472,99 @@ impl From<BoardLayout> for ChipRoleConfig {
                 u4: Some(ChipRole::Rtc),
                 u5: Some(ChipRole::SupervisorReset),
                 x1: Some(ChipRole::Crystal),
+                bt1: Some(ChipRole::Battery),
+                ..ChipRoleConfig::default()
+            },
+            BoardLayout::UnlRom => ChipRoleConfig {
+                u1: Some(ChipRole::Rom),
+                ..ChipRoleConfig::default()
+            },
+            BoardLayout::UnlRomMapper => ChipRoleConfig {
+                u1: Some(ChipRole::Rom),
+                u2: Some(ChipRole::Mapper),
+                u3: Some(ChipRole::Unknown),
+                ..ChipRoleConfig::default()
+            },
+            BoardLayout::UnlBlob => ChipRoleConfig {
+                u1: Some(ChipRole::Unknown),
                 ..ChipRoleConfig::default()
             },
         }
     }
 }
+
+#[cfg(test)]
+fn test_cfg(rom_id: &str, layouts: &[BoardLayout]) -> GameConfig {
+    GameConfig {
+        rom_id: rom_id.to_owned(),
+        name: "Test".to_owned(),
+        rom_verified: false,
+        sha256: None,
+        platform: GamePlatform::Gb,
+        layouts: layouts.to_vec(),
+        publisher: None,
+        mapper: None,
+    }
+}
+
+#[test]
+fn test_board_layout_resolve() {
+    let licensed = test_cfg("DMG-TRA-1", &[BoardLayout::RomMapper]);
+    assert_eq!(
+        BoardLayout::resolve("DMG-BEAN-02", &licensed),
+        Some(BoardLayout::RomMapper)
+    );
+    assert_eq!(
+        BoardLayout::resolve("DMG-AAA-03", &licensed),
+        Some(BoardLayout::Rom)
+    );
+    // Licensed games never fall back to the game config
+    assert_eq!(BoardLayout::resolve("KY-01", &licensed), None);
+
+    let unlicensed = test_cfg("UNL-WT-BIBLE-0", &[BoardLayout::UnlRomMapper]);
+    assert_eq!(
+        BoardLayout::resolve("KY-01", &unlicensed),
+        Some(BoardLayout::UnlRomMapper)
+    );
+    assert_eq!(
+        BoardLayout::resolve("", &unlicensed),
+        Some(BoardLayout::UnlRomMapper)
+    );
+    assert_eq!(
+        BoardLayout::resolve("DMG-BEAN-02", &unlicensed),
+        Some(BoardLayout::RomMapper)
+    );
+
+    let ambiguous = test_cfg(
+        "UNL-WT-BIBLE-0",
+        &[BoardLayout::UnlRom, BoardLayout::UnlBlob],
+    );
+    assert_eq!(BoardLayout::resolve("KY-01", &ambiguous), None);
+    let licensed_layout = test_cfg("UNL-WT-BIBLE-0", &[BoardLayout::Rom]);
+    assert_eq!(BoardLayout::resolve("KY-01", &licensed_layout), None);
+}
+
+#[test]
+fn test_unlicensed_layouts() {
+    let roles = |layout: BoardLayout| ChipRoleConfig::from(layout).iter().collect::<Vec<_>>();
+    assert_eq!(
+        roles(BoardLayout::UnlRom),
+        [(PartDesignator::U1, ChipRole::Rom)]
+    );
+    assert_eq!(
+        roles(BoardLayout::UnlRomMapper),
+        [
+            (PartDesignator::U1, ChipRole::Rom),
+            (PartDesignator::U2, ChipRole::Mapper),
+            (PartDesignator::U3, ChipRole::Unknown),
+        ]
+    );
+    assert_eq!(
+        roles(BoardLayout::UnlBlob),
+        [(PartDesignator::U1, ChipRole::Unknown)]
+    );
+    assert!(BoardLayout::UnlRom.is_unlicensed());
+    assert!(BoardLayout::UnlRomMapper.is_unlicensed());
+    assert!(BoardLayout::UnlBlob.is_unlicensed());
+    assert!(!BoardLayout::RomMapper.is_unlicensed());
+}
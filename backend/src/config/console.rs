@@ -0,0 +1,336 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use self::{ChipParser as P, ConsoleChipRole as R, ConsoleDesignator as D};
+use crate::Console;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ConsoleDesignator {
+    U1,
+    U2,
+    U3,
+    U4,
+    U5,
+    U6,
+    X1,
+    Y1,
+    Xtal1,
+    Coil1,
+    T1,
+}
+
+impl ConsoleDesignator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsoleDesignator::U1 => "U1",
+            ConsoleDesignator::U2 => "U2",
+            ConsoleDesignator::U3 => "U3",
+            ConsoleDesignator::U4 => "U4",
+            ConsoleDesignator::U5 => "U5",
+            ConsoleDesignator::U6 => "U6",
+            ConsoleDesignator::X1 => "X1",
+            ConsoleDesignator::Y1 => "Y1",
+            ConsoleDesignator::Xtal1 => "XTAL1",
+            ConsoleDesignator::Coil1 => "COIL1",
+            ConsoleDesignator::T1 => "T1",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ConsoleChipRole {
+    Unknown,
+    Cpu,
+    Icd2,
+    WorkRam,
+    VideoRam,
+    Rom,
+    Cic,
+    Amplifier,
+    LcdBiasGenerator,
+    Regulator,
+    Pmic,
+    BatteryController,
+    Crystal,
+    Coil,
+    Transformer,
+}
+
+impl ConsoleChipRole {
+    pub fn display(&self) -> &'static str {
+        match self {
+            ConsoleChipRole::Unknown => "?",
+            ConsoleChipRole::Cpu => "CPU",
+            ConsoleChipRole::Icd2 => "ICD2",
+            ConsoleChipRole::WorkRam => "WRAM",
+            ConsoleChipRole::VideoRam => "VRAM",
+            ConsoleChipRole::Rom => "ROM",
+            ConsoleChipRole::Cic => "CIC",
+            ConsoleChipRole::Amplifier => "Audio amplifier",
+            ConsoleChipRole::LcdBiasGenerator => "LCD bias generator",
+            ConsoleChipRole::Regulator => "Regulator",
+            ConsoleChipRole::Pmic => "Power management",
+            ConsoleChipRole::BatteryController => "Battery controller",
+            ConsoleChipRole::Crystal => "Crystal",
+            ConsoleChipRole::Coil => "Coil",
+            ConsoleChipRole::Transformer => "Transformer",
+        }
+    }
+}
+
+/// Label parser used for a console chip.
+///
+/// Every variant corresponds to one top-level parser function in `crate::parser`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ChipParser {
+    Gen1Soc,
+    Gen2Soc,
+    CgbSoc,
+    AgbSocQfp128,
+    AgbSocQfp156,
+    AgbSocBga,
+    Ram,
+    SramTsop1_48,
+    Icd2,
+    SgbRom,
+    Cic,
+    DmgAmp,
+    MgbAmp,
+    AgbAmp,
+    DmgReg,
+    CgbReg,
+    AgbReg,
+    AgbPmic,
+    AgsPmicOld,
+    AgsPmicNew,
+    AgsChargeController,
+    GbsDol,
+    GbsReg,
+    OxyPmic,
+    OxyU4,
+    OxyU5,
+    Crystal4MiHz,
+    Crystal8MiHz,
+    Crystal20MiHz,
+    Crystal32MiHz,
+    Coil,
+    MglTransformer,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ConsoleChip {
+    pub designator: ConsoleDesignator,
+    pub role: ConsoleChipRole,
+    pub parser: ChipParser,
+    /// The chip is covered by an epoxy blob, so it usually has no label of its own
+    pub in_blob: bool,
+}
+
+const fn chip(
+    designator: ConsoleDesignator,
+    role: ConsoleChipRole,
+    parser: ChipParser,
+) -> ConsoleChip {
+    ConsoleChip {
+        designator,
+        role,
+        parser,
+        in_blob: false,
+    }
+}
+
+const fn blob_chip(
+    designator: ConsoleDesignator,
+    role: ConsoleChipRole,
+    parser: ChipParser,
+) -> ConsoleChip {
+    ConsoleChip {
+        in_blob: true,
+        ..chip(designator, role, parser)
+    }
+}
+
+const DMG: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::Gen1Soc),
+    chip(D::U2, R::VideoRam, P::Ram),
+    chip(D::U3, R::WorkRam, P::Ram),
+    chip(D::U4, R::Amplifier, P::DmgAmp),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+/// DMG-CPU-07 and DMG-CPU-08 have the CPU, both RAM chips and the amplifier under a single epoxy
+/// blob
+const DMG_BLOB: &[ConsoleChip] = &[
+    blob_chip(D::U1, R::Cpu, P::Gen1Soc),
+    blob_chip(D::U2, R::VideoRam, P::Ram),
+    blob_chip(D::U3, R::WorkRam, P::Ram),
+    blob_chip(D::U4, R::Amplifier, P::DmgAmp),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+const SGB: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::Gen1Soc),
+    chip(D::U2, R::Icd2, P::Icd2),
+    chip(D::U3, R::WorkRam, P::Ram),
+    chip(D::U4, R::VideoRam, P::Ram),
+    chip(D::U5, R::Rom, P::SgbRom),
+    chip(D::U6, R::Cic, P::Cic),
+];
+
+const MGB: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::Gen2Soc),
+    chip(D::U2, R::WorkRam, P::Ram),
+    chip(D::U3, R::Amplifier, P::MgbAmp),
+    chip(D::U4, R::LcdBiasGenerator, P::DmgReg),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+const MGL: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::Gen2Soc),
+    chip(D::U2, R::WorkRam, P::Ram),
+    chip(D::U3, R::Amplifier, P::MgbAmp),
+    chip(D::U4, R::LcdBiasGenerator, P::DmgReg),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+    chip(D::T1, R::Transformer, P::MglTransformer),
+];
+
+const SGB2: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::Gen2Soc),
+    chip(D::U2, R::Icd2, P::Icd2),
+    chip(D::U3, R::WorkRam, P::Ram),
+    chip(D::U4, R::Rom, P::SgbRom),
+    chip(D::U5, R::Cic, P::Cic),
+    chip(D::Xtal1, R::Crystal, P::Crystal20MiHz),
+    chip(D::Coil1, R::Coil, P::Coil),
+];
+
+const CGB: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::CgbSoc),
+    chip(D::U2, R::WorkRam, P::Ram),
+    chip(D::U3, R::Amplifier, P::MgbAmp),
+    chip(D::U4, R::LcdBiasGenerator, P::CgbReg),
+    chip(D::X1, R::Crystal, P::Crystal8MiHz),
+];
+
+/// CGB-CPU-06 has a CPU CGB E with built-in work RAM, so there's no U2
+const CGB_CPU_06: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::CgbSoc),
+    chip(D::U3, R::Amplifier, P::MgbAmp),
+    chip(D::U4, R::LcdBiasGenerator, P::CgbReg),
+    chip(D::X1, R::Crystal, P::Crystal8MiHz),
+];
+
+const AGB: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::AgbSocQfp128),
+    chip(D::U2, R::WorkRam, P::SramTsop1_48),
+    chip(D::U3, R::LcdBiasGenerator, P::AgbReg),
+    chip(D::U4, R::Unknown, P::AgbPmic),
+    chip(D::U6, R::Amplifier, P::AgbAmp),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+/// AGB-CPU-10 has no separate LCD regulator at U3
+const AGB_CPU_10: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::AgbSocQfp128),
+    chip(D::U2, R::WorkRam, P::SramTsop1_48),
+    chip(D::U4, R::Unknown, P::AgbPmic),
+    chip(D::U6, R::Amplifier, P::AgbAmp),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+const AGS: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::AgbSocQfp156),
+    chip(D::U2, R::WorkRam, P::SramTsop1_48),
+    chip(D::U3, R::Amplifier, P::AgbAmp),
+    chip(D::U4, R::Unknown, P::AgsPmicOld),
+    chip(D::U5, R::BatteryController, P::AgsChargeController),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+/// Later AGS/AGT boards have a new power management chip at U3 instead of the amplifier
+const AGS_NEW_PMIC: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::AgbSocQfp156),
+    chip(D::U2, R::WorkRam, P::SramTsop1_48),
+    chip(D::U3, R::Pmic, P::AgsPmicNew),
+    chip(D::U4, R::Unknown, P::AgsPmicOld),
+    chip(D::U5, R::BatteryController, P::AgsChargeController),
+    chip(D::X1, R::Crystal, P::Crystal4MiHz),
+];
+
+const GBS: &[ConsoleChip] = &[
+    chip(D::U2, R::Cpu, P::AgbSocQfp128),
+    chip(D::U3, R::WorkRam, P::SramTsop1_48),
+    chip(D::U4, R::Unknown, P::GbsDol),
+    chip(D::U5, R::Regulator, P::GbsReg),
+    chip(D::U6, R::Regulator, P::GbsReg),
+    chip(D::Y1, R::Crystal, P::Crystal32MiHz),
+];
+
+const OXY: &[ConsoleChip] = &[
+    chip(D::U1, R::Cpu, P::AgbSocBga),
+    chip(D::U2, R::Pmic, P::OxyPmic),
+    chip(D::U4, R::Unknown, P::OxyU4),
+    chip(D::U5, R::Unknown, P::OxyU5),
+];
+
+/// Returns the chips of a console mainboard.
+///
+/// The mainboard label is used to pick the right configuration for boards where some revisions
+/// have different chips.
+pub fn console_board_chips(console: Console, mainboard_label: &str) -> &'static [ConsoleChip] {
+    match console {
+        Console::Dmg => match mainboard_label {
+            "DMG-CPU-07" | "DMG-CPU-08" => DMG_BLOB,
+            _ => DMG,
+        },
+        Console::Sgb => SGB,
+        Console::Mgb => MGB,
+        Console::Mgl => MGL,
+        Console::Sgb2 => SGB2,
+        Console::Cgb => match mainboard_label {
+            "CGB-CPU-06" => CGB_CPU_06,
+            // CGB-CPU-01 to CGB-CPU-05 have the same chips in the same places
+            _ => CGB,
+        },
+        Console::Agb => match mainboard_label {
+            "AGB-CPU-10" => AGB_CPU_10,
+            _ => AGB,
+        },
+        Console::Ags => match mainboard_label {
+            "C/AGS-CPU-30" | "C/AGT-CPU-01" => AGS_NEW_PMIC,
+            _ => AGS,
+        },
+        Console::Gbs => GBS,
+        Console::Oxy => OXY,
+    }
+}
+
+/// Returns the chips of the most common mainboard revision of a console
+pub fn default_console_board_chips(console: Console) -> &'static [ConsoleChip] {
+    console_board_chips(console, "")
+}
+
+#[test]
+fn test_console_board_chips() {
+    for console in Console::ALL {
+        let chips = default_console_board_chips(console);
+        assert!(chips.iter().any(|chip| chip.role == ConsoleChipRole::Cpu));
+        for (idx, chip) in chips.iter().enumerate() {
+            assert!(chips[idx + 1..]
+                .iter()
+                .all(|other| other.designator != chip.designator));
+        }
+    }
+    assert_eq!(
+        console_board_chips(Console::Ags, "C/AGS-CPU-30")[2].parser,
+        ChipParser::AgsPmicNew
+    );
+    let has_work_ram = |label| {
+        console_board_chips(Console::Cgb, label)
+            .iter()
+            .any(|chip| chip.role == ConsoleChipRole::WorkRam)
+    };
+    assert!(has_work_ram("CGB-CPU-01"));
+    assert!(!has_work_ram("CGB-CPU-06"));
+}
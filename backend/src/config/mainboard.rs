@@ -0,0 +1,358 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+use super::console::{
+    console_board_chips, ConsoleChipRole,
+    ConsoleChipRole::{Cpu, Icd2, LcdBiasGenerator, Pmic, Regulator, VideoRam, WorkRam},
+    ConsoleDesignator,
+};
+use crate::Console;
+
+/// Chip kinds that are expected for one role on a mainboard revision
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ChipRule {
+    pub role: ConsoleChipRole,
+    /// Accepted chip kinds, as returned by the label parser of the role.
+    ///
+    /// An empty list means the role is not populated on this revision.
+    pub kinds: &'static [&'static str],
+}
+
+/// A known mainboard revision of a console
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MainboardRevision {
+    pub console: Console,
+    pub label: &'static str,
+    pub rules: &'static [ChipRule],
+}
+
+/// A chip that doesn't match the rules of its mainboard revision
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deviation {
+    pub designator: ConsoleDesignator,
+    pub role: ConsoleChipRole,
+    pub kind: String,
+    pub expected: &'static [&'static str],
+}
+
+impl fmt::Display for Deviation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let designator = self.designator.as_str();
+        let role = self.role.display();
+        let kind = &self.kind;
+        if self.expected.is_empty() {
+            write!(f, "{designator} ({role}): unexpected chip {kind}")
+        } else {
+            let expected = self.expected.join(", ");
+            write!(
+                f,
+                "{designator} ({role}): {kind} is not one of the expected kinds ({expected})"
+            )
+        }
+    }
+}
+
+impl MainboardRevision {
+    /// Returns an URL-friendly id, e.g. `c-ags-cpu-01` for `C/AGS-CPU-01`
+    pub fn slug(&self) -> String {
+        self.label.to_ascii_lowercase().replace('/', "-")
+    }
+    pub fn rule(&self, role: ConsoleChipRole) -> Option<&'static ChipRule> {
+        self.rules.iter().find(|rule| rule.role == role)
+    }
+    /// Checks the parsed chip kinds of a mainboard against the rules of this revision.
+    ///
+    /// Chips that are missing or whose kind is unknown are not reported.
+    pub fn check<'a, F>(&self, kind_of: F) -> Vec<Deviation>
+    where
+        F: Fn(ConsoleDesignator) -> Option<&'a str>,
+    {
+        console_board_chips(self.console, self.label)
+            .iter()
+            .filter_map(|chip| {
+                let rule = self.rule(chip.role)?;
+                let kind = kind_of(chip.designator)?;
+                (!rule.kinds.contains(&kind)).then(|| Deviation {
+                    designator: chip.designator,
+                    role: chip.role,
+                    kind: kind.to_owned(),
+                    expected: rule.kinds,
+                })
+            })
+            .collect()
+    }
+}
+
+const fn rule(role: ConsoleChipRole, kinds: &'static [&'static str]) -> ChipRule {
+    ChipRule { role, kinds }
+}
+
+const fn revision(
+    console: Console,
+    label: &'static str,
+    rules: &'static [ChipRule],
+) -> MainboardRevision {
+    MainboardRevision {
+        console,
+        label,
+        rules,
+    }
+}
+
+const DMG_RAM: &[&str] = &[
+    "LH5160N-10L",
+    "LH5164LN-10",
+    "LH5164N-10L",
+    "LH5264N",
+    "LH5264N4",
+    "LH5264N4T",
+    "LH5264TN-L",
+    "LH5264TN-TL",
+    "LH52A64N-L",
+    "LH52A64N-TL",
+];
+
+const MGB_RAM: &[&str] = &["LH5164AN-10L", "LH52A64N-L", "LH52A64N-PL", "LH52A64N-YL"];
+
+const CGB_RAM: &[&str] = &[
+    "LH51D256T-Z5",
+    "LH51D256T-Z7",
+    "LH52256CVT",
+    "LH52CV256JT-10LL",
+];
+
+const AGB_RAM: &[&str] = &[
+    "BS616LV2018TC-70",
+    "BS616LV2019TC-70",
+    "HY62LF16206A-LT12C",
+    "LP62S16128BW-70LLTF",
+    "M68AS128DL70N6",
+    "MB82D12160-10FN",
+    "TC55V200FT-70",
+    "μPD442012AGY-BB85X-MJH",
+    "μPD442012AGY-BC85X-MJH",
+    "μPD442012LGY-B85X-MJH",
+];
+
+macro_rules! dmg {
+    ($label:literal, $cpu:expr) => {
+        revision(
+            Console::Dmg,
+            $label,
+            &[
+                rule(Cpu, $cpu),
+                rule(WorkRam, DMG_RAM),
+                rule(VideoRam, DMG_RAM),
+            ],
+        )
+    };
+}
+
+macro_rules! mgb {
+    ($console:ident, $label:literal) => {
+        revision(
+            Console::$console,
+            $label,
+            &[
+                rule(Cpu, &["CPU MGB"]),
+                rule(WorkRam, MGB_RAM),
+                rule(LcdBiasGenerator, &["IR3E02"]),
+            ],
+        )
+    };
+}
+
+macro_rules! cgb {
+    ($label:literal, $cpu:expr) => {
+        revision(
+            Console::Cgb,
+            $label,
+            &[
+                rule(Cpu, $cpu),
+                rule(WorkRam, CGB_RAM),
+                rule(LcdBiasGenerator, &["IR3E06N"]),
+            ],
+        )
+    };
+}
+
+macro_rules! agb {
+    ($console:ident, $label:literal, $cpu:expr) => {
+        revision(
+            Console::$console,
+            $label,
+            &[rule(Cpu, $cpu), rule(WorkRam, AGB_RAM)],
+        )
+    };
+    ($console:ident, $label:literal, $cpu:expr, $regulator_role:ident: $regulator:expr) => {
+        revision(
+            Console::$console,
+            $label,
+            &[
+                rule(Cpu, $cpu),
+                rule(WorkRam, AGB_RAM),
+                rule($regulator_role, $regulator),
+            ],
+        )
+    };
+}
+
+/// Known mainboard revisions.
+///
+/// Only GBS boards have chips with the regulator role. The LCD regulators of MGB, MGL, CGB and
+/// AGB boards are checked as LCD bias generators, and the power management chips of later AGS/AGT
+/// boards and OXY boards as PMICs. The remaining power chips (e.g. U4 on AGB and AGS boards) have
+/// an unknown role, so nothing is expected of them.
+const REVISIONS: &[MainboardRevision] = &[
+    dmg!("DMG-CPU-01", &["DMG-CPU", "DMG-CPU A"]),
+    dmg!("DMG-CPU-02", &["DMG-CPU A"]),
+    dmg!("DMG-CPU-03", &["DMG-CPU A", "DMG-CPU B"]),
+    dmg!("DMG-CPU-04", &["DMG-CPU B"]),
+    dmg!("DMG-CPU-05", &["DMG-CPU B"]),
+    dmg!("DMG-CPU-06", &["DMG-CPU B", "DMG-CPU C"]),
+    // The CPU and both RAM chips are in a single epoxy blob
+    revision(
+        Console::Dmg,
+        "DMG-CPU-07",
+        &[rule(Cpu, &["DMG-CPU B (blob)"])],
+    ),
+    // Early DMG-CPU-08 boards (e.g. stamp 502) still have a DMG-CPU B in the blob
+    revision(
+        Console::Dmg,
+        "DMG-CPU-08",
+        &[rule(Cpu, &["DMG-CPU B (blob)", "DMG-CPU C (blob)"])],
+    ),
+    revision(
+        Console::Sgb,
+        "SGB-N-01",
+        &[
+            rule(Cpu, &["SGB-CPU 01"]),
+            rule(Icd2, &["ICD2-N"]),
+            rule(WorkRam, DMG_RAM),
+            rule(VideoRam, DMG_RAM),
+        ],
+    ),
+    revision(
+        Console::Sgb,
+        "SGB-N-10",
+        &[
+            rule(Cpu, &["SGB-CPU 01"]),
+            rule(Icd2, &["ICD2-N"]),
+            rule(WorkRam, DMG_RAM),
+            rule(VideoRam, DMG_RAM),
+        ],
+    ),
+    revision(
+        Console::Sgb,
+        "SGB-R-10",
+        &[
+            rule(Cpu, &["SGB-CPU 01"]),
+            rule(Icd2, &["ICD2-R"]),
+            rule(WorkRam, &["LH52A64N-YL"]),
+            rule(VideoRam, &["LH52A64N-YL"]),
+        ],
+    ),
+    mgb!(Mgb, "MGB-CPU-01"),
+    mgb!(Mgb, "MGB-ECPU-01"),
+    mgb!(Mgb, "MGB-LCPU-01"),
+    mgb!(Mgb, "MGB-LCPU-02"),
+    mgb!(Mgl, "MGL-CPU-01"),
+    revision(
+        Console::Sgb2,
+        "SHVC-SGB2-01",
+        &[
+            rule(Cpu, &["CPU SGB2"]),
+            rule(Icd2, &["ICD2-R"]),
+            rule(WorkRam, MGB_RAM),
+        ],
+    ),
+    cgb!("CGB-CPU-01", &["CPU CGB", "CPU CGB A", "CPU CGB B"]),
+    cgb!("CGB-CPU-02", &["CPU CGB B"]),
+    cgb!("CGB-CPU-03", &["CPU CGB B", "CPU CGB C"]),
+    cgb!("CGB-CPU-04", &["CPU CGB C", "CPU CGB D"]),
+    cgb!("CGB-CPU-05", &["CPU CGB D"]),
+    cgb!("CGB-CPU-06", &["CPU CGB E"]),
+    agb!(Agb, "AGB-CPU-01", &["CPU AGB"], LcdBiasGenerator: &["IR3E09N"]),
+    agb!(Agb, "AGB-CPU-02", &["CPU AGB"], LcdBiasGenerator: &["IR3E09N"]),
+    agb!(Agb, "AGB-CPU-03", &["CPU AGB A"], LcdBiasGenerator: &["IR3E09N"]),
+    agb!(Agb, "AGB-CPU-04", &["CPU AGB A"], LcdBiasGenerator: &["IR3E09N"]),
+    agb!(Agb, "AGB-CPU-10", &["CPU AGB A", "CPU AGB A E"]),
+    agb!(Ags, "C/AGS-CPU-01", &["CPU AGB B"]),
+    agb!(Ags, "C/AGS-CPU-10", &["CPU AGB B"]),
+    agb!(Ags, "C/AGS-CPU-11", &["CPU AGB B"]),
+    agb!(Ags, "C/AGS-CPU-21", &["CPU AGB B E"]),
+    agb!(Ags, "C/AGS-CPU-30", &["CPU AGB B E"], Pmic: &["PM B3", "PM B4"]),
+    agb!(Ags, "C/AGT-CPU-01", &["CPU AGB B E"], Pmic: &["PM B3", "PM B4"]),
+    agb!(Gbs, "DOL-GBS-01", &["CPU AGB A"], Regulator: &["MM1592F"]),
+    agb!(Gbs, "DOL-GBS-10", &["CPU AGB A"], Regulator: &["MM1592F"]),
+    agb!(Gbs, "DOL-GBS-20", &["CPU AGB A", "CPU AGB A E"], Regulator: &["MM1592F"]),
+    revision(
+        Console::Oxy,
+        "OXY-CPU-01",
+        &[rule(Cpu, &["CPU AGB E"]), rule(Pmic, &["PM C"])],
+    ),
+    revision(
+        Console::Oxy,
+        "OXY-CPU-02",
+        &[rule(Cpu, &["CPU AGB E"]), rule(Pmic, &["PM C"])],
+    ),
+];
+
+/// Returns all known mainboard revisions of a console
+pub fn mainboard_revisions(console: Console) -> impl Iterator<Item = &'static MainboardRevision> {
+    REVISIONS
+        .iter()
+        .filter(move |revision| revision.console == console)
+}
+
+pub fn mainboard_revision(console: Console, label: &str) -> Option<&'static MainboardRevision> {
+    mainboard_revisions(console).find(|revision| revision.label == label)
+}
+
+#[test]
+fn test_mainboard_revision_check() {
+    let revision = mainboard_revision(Console::Cgb, "CGB-CPU-06").unwrap();
+    assert_eq!(revision.slug(), "cgb-cpu-06");
+    let deviations = revision.check(|designator| match designator {
+        ConsoleDesignator::U1 => Some("CPU CGB D"),
+        ConsoleDesignator::U2 => Some("LH51D256T-Z7"),
+        _ => None,
+    });
+    assert_eq!(deviations.len(), 1);
+    assert_eq!(deviations[0].designator, ConsoleDesignator::U1);
+    assert_eq!(deviations[0].expected, &["CPU CGB E"]);
+
+    let revision = mainboard_revision(Console::Agb, "AGB-CPU-04").unwrap();
+    let deviations = revision.check(|designator| match designator {
+        ConsoleDesignator::U3 => Some("IR3E02"),
+        _ => None,
+    });
+    assert_eq!(deviations.len(), 1);
+    assert_eq!(deviations[0].role, ConsoleChipRole::LcdBiasGenerator);
+
+    let revision = mainboard_revision(Console::Dmg, "DMG-CPU-08").unwrap();
+    let deviations = revision.check(|designator| match designator {
+        ConsoleDesignator::U1 => Some("DMG-CPU B (blob)"),
+        _ => None,
+    });
+    assert!(deviations.is_empty());
+
+    let revision = mainboard_revision(Console::Ags, "C/AGS-CPU-30").unwrap();
+    let deviations = revision.check(|designator| match designator {
+        ConsoleDesignator::U3 => Some("PM B3"),
+        ConsoleDesignator::U4 => Some("S6403"),
+        _ => None,
+    });
+    assert!(deviations.is_empty());
+
+    assert_eq!(
+        mainboard_revision(Console::Ags, "C/AGS-CPU-01")
+            .unwrap()
+            .slug(),
+        "c-ags-cpu-01"
+    );
+    assert!(mainboard_revision(Console::Cgb, "AGB-CPU-01").is_none());
+}
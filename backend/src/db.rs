@@ -0,0 +1,484 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::{anyhow, bail, Context, Error};
+use log::warn;
+use serde::de::DeserializeOwned;
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use self::chip::{cartridge_chips, parse_console_chip, ChipData};
+use crate::{
+    config::cartridge::{load_cfgs, BoardLayout, GameConfig},
+    input::{
+        agb::AgbConsole, ags::AgsConsole, cartridge::Cartridge, cgb::CgbConsole,
+        console::ConsoleSubmission, dmg::DmgConsole, gbs::GbsConsole, mgb::MgbConsole,
+        mgl::MglConsole, oxy::OxyConsole, sgb::SgbConsole, sgb2::Sgb2Console,
+    },
+    migration::{read_metadata, MetadataKind},
+    parser::Manufacturer,
+    Console,
+};
+
+pub mod chip;
+pub mod export;
+pub mod expr;
+
+/// Photo files that a cartridge submission directory may contain
+pub const CARTRIDGE_PHOTOS: &[&str] = &["01_front.jpg", "02_pcb_front.jpg", "03_pcb_back.jpg"];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Photo {
+    pub name: &'static str,
+    pub path: PathBuf,
+}
+
+/// Submission data loaded from a `metadata.json` file, and the photos next to it
+#[derive(Clone, Debug)]
+pub struct Loaded<T> {
+    pub data: T,
+    pub dir: PathBuf,
+    pub photos: Vec<Photo>,
+}
+
+impl<T> Loaded<T> {
+    pub fn photo(&self, name: &str) -> Option<&Photo> {
+        self.photos.iter().find(|photo| photo.name == name)
+    }
+}
+
+fn load_dir<T: DeserializeOwned>(
+    kind: MetadataKind,
+    dir: &Path,
+    photo_files: &'static [&'static str],
+) -> Result<Loaded<T>, Error> {
+    let path = dir.join("metadata.json");
+    let (data, _) = read_metadata(kind, &path)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+        if name != "metadata.json" && !photo_files.contains(&name.as_ref()) {
+            warn!("{}: unexpected file {name}", dir.display());
+        }
+    }
+    Ok(Loaded {
+        data,
+        dir: dir.to_owned(),
+        photos: photos(dir, photo_files),
+    })
+}
+
+/// Returns the photos that exist in the given submission directory
+pub(crate) fn photos(dir: &Path, photo_files: &'static [&'static str]) -> Vec<Photo> {
+    photo_files
+        .iter()
+        .map(|&name| Photo {
+            name,
+            path: dir.join(name),
+        })
+        .filter(|photo| photo.path.is_file())
+        .collect()
+}
+
+/// Returns the subdirectories that contain a `metadata.json` file, in sorted order
+pub(crate) fn submission_dirs(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.join("metadata.json").is_file() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn check_slug(dir: &Path, slug: &str) -> Result<(), Error> {
+    if dir.file_name().and_then(|name| name.to_str()) != Some(slug) {
+        bail!("{}: slug {slug} doesn't match the directory", dir.display());
+    }
+    Ok(())
+}
+
+/// Loads all cartridge submissions from `data/cartridges` in the given root directory
+pub fn load_cartridge_submissions(root: &Path) -> Result<Vec<Loaded<Cartridge>>, Error> {
+    load_cartridge_submissions_filtered(root, |_| true)
+}
+
+/// Loads the cartridge submissions whose directory (relative to the root) is accepted by the
+/// filter
+pub fn load_cartridge_submissions_filtered(
+    root: &Path,
+    filter: impl Fn(&Path) -> bool,
+) -> Result<Vec<Loaded<Cartridge>>, Error> {
+    let mut submissions = Vec::new();
+    let games_dir = root.join("data/cartridges");
+    for entry in fs::read_dir(&games_dir)
+        .with_context(|| format!("Failed to read {}", games_dir.display()))?
+    {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        for dir in submission_dirs(&entry.path())? {
+            if !filter(dir.strip_prefix(root)?) {
+                continue;
+            }
+            let loaded: Loaded<Cartridge> =
+                load_dir(MetadataKind::Cartridge, &dir, CARTRIDGE_PHOTOS)?;
+            check_slug(&dir, &loaded.data.slug)?;
+            submissions.push(loaded);
+        }
+    }
+    Ok(submissions)
+}
+
+/// Loads all submissions of one console from `data/consoles` in the given root directory
+pub fn load_console_submissions<C: ConsoleSubmission>(
+    root: &Path,
+) -> Result<Vec<Loaded<C>>, Error> {
+    load_console_submissions_filtered(root, |_| true)
+}
+
+/// Loads the submissions of one console whose directory (relative to the root) is accepted by
+/// the filter
+pub fn load_console_submissions_filtered<C: ConsoleSubmission>(
+    root: &Path,
+    filter: impl Fn(&Path) -> bool,
+) -> Result<Vec<Loaded<C>>, Error> {
+    let mut submissions = Vec::new();
+    for dir in submission_dirs(&root.join("data/consoles").join(C::CONSOLE.code()))? {
+        if !filter(dir.strip_prefix(root)?) {
+            continue;
+        }
+        let loaded: Loaded<C> =
+            load_dir(MetadataKind::Console(C::CONSOLE), &dir, C::PHOTOS.files())?;
+        let slug = loaded.data.slug();
+        check_slug(&dir, slug)?;
+        if let Some(serial) = loaded.data.serial().filter(|&serial| serial != slug) {
+            bail!(
+                "{}: serial {serial} doesn't match the slug {slug}",
+                dir.display()
+            );
+        }
+        submissions.push(loaded);
+    }
+    Ok(submissions)
+}
+
+/// Category of a submission. Cartridges sort before consoles, and consoles sort in the order of
+/// `Console`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    Cartridge,
+    Console(Console),
+}
+
+impl Category {
+    /// Returns `cartridge` or the console id
+    pub fn id(&self) -> &'static str {
+        match self {
+            Category::Cartridge => "cartridge",
+            Category::Console(console) => console.id(),
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Cartridge => "Cartridges",
+            Category::Console(console) => console.name(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmissionChip {
+    pub designator: &'static str,
+    pub role: &'static str,
+    pub chip: ChipData,
+}
+
+/// A cartridge or console submission with its chips parsed
+#[derive(Clone, Debug)]
+pub struct Submission {
+    pub category: Category,
+    /// Game code for cartridges, console code for consoles
+    pub code: String,
+    pub slug: String,
+    pub contributor: String,
+    pub title: String,
+    /// Label of the cartridge board or console mainboard
+    pub board: String,
+    /// True if any part of the submission has been marked as an outlier
+    pub outlier: bool,
+    pub chips: Vec<SubmissionChip>,
+    pub photos: Vec<Photo>,
+    /// The original `metadata.json` contents
+    pub metadata: serde_json::Value,
+    pub(crate) data: Arc<dyn Any + Send + Sync>,
+}
+
+impl Submission {
+    /// Returns the parsed `metadata.json` if it is of type `T` (`Cartridge` or one of the
+    /// console submission types)
+    pub fn data<T: Any>(&self) -> Option<&T> {
+        self.data.downcast_ref()
+    }
+    pub fn photo(&self, name: &str) -> Option<&Photo> {
+        self.photos.iter().find(|photo| photo.name == name)
+    }
+    pub(crate) fn from_cartridge(
+        cfgs: &BTreeMap<String, GameConfig>,
+        loaded: Loaded<Cartridge>,
+    ) -> Result<Submission, Error> {
+        let dir = loaded.dir.display();
+        let cartridge = &loaded.data;
+        let cfg = cfgs
+            .get(&cartridge.code)
+            .ok_or_else(|| anyhow!("{dir}: unknown game code {}", cartridge.code))?;
+        let layout = BoardLayout::resolve(&cartridge.board.label, cfg)
+            .ok_or_else(|| anyhow!("{dir}: unknown board {}", cartridge.board.label))?;
+        let chips = cartridge_chips(cfg, layout, &cartridge.board)
+            .into_iter()
+            .map(|(designator, role, chip)| SubmissionChip {
+                designator: designator.as_str(),
+                role: role.display(),
+                // Only labels can fail to parse
+                chip: chip.unwrap_or_else(|_| {
+                    let chip = cartridge.board[designator].as_ref();
+                    ChipData {
+                        label: chip.and_then(|chip| chip.label.clone()),
+                        outlier: chip.is_some_and(|chip| chip.outlier),
+                        ..ChipData::default()
+                    }
+                }),
+            })
+            .collect();
+        Ok(Submission {
+            category: Category::Cartridge,
+            code: cartridge.code.clone(),
+            slug: cartridge.slug.clone(),
+            contributor: cartridge.contributor.clone(),
+            title: format!("Entry #{}", cartridge.index),
+            board: cartridge.board.label.clone(),
+            outlier: cartridge.shell.outlier || cartridge.board.outlier,
+            chips,
+            metadata: serde_json::to_value(cartridge)?,
+            photos: loaded.photos,
+            data: Arc::new(loaded.data),
+        })
+    }
+    pub(crate) fn from_console<C: ConsoleSubmission>(
+        loaded: Loaded<C>,
+    ) -> Result<Submission, Error> {
+        let console = &loaded.data;
+        let year_hint = console.year_hint();
+        let chips = console
+            .mainboard_chips()
+            .into_iter()
+            .map(|(config, chip)| {
+                let data = match chip.label.as_deref() {
+                    Some(label) => parse_console_chip(config.parser, year_hint, label)
+                        .unwrap_or_else(|_| ChipData::from_label(label)),
+                    None => ChipData::default(),
+                };
+                let data = ChipData {
+                    outlier: chip.outlier,
+                    ..data
+                };
+                SubmissionChip {
+                    designator: config.designator.as_str(),
+                    role: config.role.display(),
+                    chip: data,
+                }
+            })
+            .collect();
+        Ok(Submission {
+            category: Category::Console(C::CONSOLE),
+            code: C::CONSOLE.code().to_owned(),
+            slug: console.slug().to_owned(),
+            contributor: console.contributor().to_owned(),
+            title: console.title(),
+            board: console.mainboard_label().to_owned(),
+            outlier: console.has_outliers(),
+            chips,
+            metadata: serde_json::to_value(console)?,
+            photos: loaded.photos,
+            data: Arc::new(loaded.data),
+        })
+    }
+}
+
+/// Filters for querying submissions.
+///
+/// Console, board and contributor filters apply to the submission. Chip filters apply to
+/// individual chips, and a submission matches if at least one of its chips matches all of them.
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    pub category: Option<Category>,
+    pub board: Option<String>,
+    pub contributor: Option<String>,
+    pub chip_kind: Option<String>,
+    pub manufacturer: Option<Manufacturer>,
+    pub years: Option<RangeInclusive<u16>>,
+}
+
+impl Query {
+    fn has_chip_filters(&self) -> bool {
+        self.chip_kind.is_some() || self.manufacturer.is_some() || self.years.is_some()
+    }
+    pub fn matches_chip(&self, chip: &ChipData) -> bool {
+        self.chip_kind
+            .iter()
+            .all(|kind| chip.kind.as_ref() == Some(kind))
+            && self
+                .manufacturer
+                .iter()
+                .all(|&manufacturer| chip.manufacturer == Some(manufacturer))
+            && self
+                .years
+                .iter()
+                .all(|years| chip.year.is_some_and(|year| years.contains(&year)))
+    }
+    pub fn matches(&self, submission: &Submission) -> bool {
+        self.category
+            .iter()
+            .all(|&category| submission.category == category)
+            && self.board.iter().all(|board| &submission.board == board)
+            && self
+                .contributor
+                .iter()
+                .all(|contributor| &submission.contributor == contributor)
+            && (!self.has_chip_filters()
+                || submission
+                    .chips
+                    .iter()
+                    .any(|chip| self.matches_chip(&chip.chip)))
+    }
+}
+
+/// All submissions in the database
+#[derive(Clone, Debug, Default)]
+pub struct Database {
+    pub cfgs: BTreeMap<String, GameConfig>,
+    pub submissions: Vec<Submission>,
+}
+
+impl Database {
+    /// Loads the game configs and all submissions from the given root directory.
+    ///
+    /// Submissions are sorted by category, then code and slug. Chips whose labels can't be
+    /// parsed only have the label.
+    pub fn load<P: AsRef<Path>>(root: P) -> Result<Database, Error> {
+        Database::load_filtered(root, |_| true)
+    }
+    /// Like `load`, but only loads the submissions whose directory (relative to the root) is
+    /// accepted by the filter
+    pub fn load_filtered<P: AsRef<Path>>(
+        root: P,
+        filter: impl Fn(&Path) -> bool + Copy,
+    ) -> Result<Database, Error> {
+        let root = root.as_ref();
+        let cfgs = load_cfgs(root.join("config/games.json"))?;
+        let mut submissions = load_cartridge_submissions_filtered(root, filter)?
+            .into_iter()
+            .map(|loaded| Submission::from_cartridge(&cfgs, loaded))
+            .collect::<Result<Vec<_>, Error>>()?;
+        submissions.extend(load_consoles::<DmgConsole>(root, filter)?);
+        submissions.extend(load_consoles::<SgbConsole>(root, filter)?);
+        submissions.extend(load_consoles::<MgbConsole>(root, filter)?);
+        submissions.extend(load_consoles::<MglConsole>(root, filter)?);
+        submissions.extend(load_consoles::<Sgb2Console>(root, filter)?);
+        submissions.extend(load_consoles::<CgbConsole>(root, filter)?);
+        submissions.extend(load_consoles::<AgbConsole>(root, filter)?);
+        submissions.extend(load_consoles::<AgsConsole>(root, filter)?);
+        submissions.extend(load_consoles::<GbsConsole>(root, filter)?);
+        submissions.extend(load_consoles::<OxyConsole>(root, filter)?);
+        Ok(Database::new(cfgs, submissions))
+    }
+    /// Creates a database of the given submissions, sorting them like `load` does
+    pub(crate) fn new(
+        cfgs: BTreeMap<String, GameConfig>,
+        mut submissions: Vec<Submission>,
+    ) -> Database {
+        submissions
+            .sort_by(|a, b| (a.category, &a.code, &a.slug).cmp(&(b.category, &b.code, &b.slug)));
+        Database { cfgs, submissions }
+    }
+    pub fn query<'a>(&'a self, query: &'a Query) -> impl Iterator<Item = &'a Submission> + 'a {
+        self.submissions
+            .iter()
+            .filter(move |submission| query.matches(submission))
+    }
+}
+
+fn load_consoles<C: ConsoleSubmission>(
+    root: &Path,
+    filter: impl Fn(&Path) -> bool,
+) -> Result<Vec<Submission>, Error> {
+    load_console_submissions_filtered::<C>(root, filter)?
+        .into_iter()
+        .map(Submission::from_console)
+        .collect()
+}
+
+#[test]
+fn test_query() {
+    let chip = |kind: &str, year| SubmissionChip {
+        designator: "U1",
+        role: "CPU",
+        chip: ChipData {
+            kind: Some(kind.to_owned()),
+            manufacturer: Some(Manufacturer::Sharp),
+            year: Some(year),
+            ..ChipData::default()
+        },
+    };
+    let submission = Submission {
+        category: Category::Console(Console::Dmg),
+        code: "DMG".to_owned(),
+        slug: "G10000000".to_owned(),
+        contributor: "test".to_owned(),
+        title: "G10000000".to_owned(),
+        board: "DMG-CPU-06".to_owned(),
+        outlier: false,
+        chips: vec![chip("DMG-CPU C", 1996), chip("LH5164AN", 1995)],
+        photos: Vec::new(),
+        metadata: serde_json::Value::Null,
+        data: Arc::new(()),
+    };
+    assert!(Query::default().matches(&submission));
+    assert!(Query {
+        category: Some(Category::Console(Console::Dmg)),
+        board: Some("DMG-CPU-06".to_owned()),
+        chip_kind: Some("DMG-CPU C".to_owned()),
+        years: Some(1996..=1997),
+        ..Query::default()
+    }
+    .matches(&submission));
+    assert!(!Query {
+        category: Some(Category::Cartridge),
+        ..Query::default()
+    }
+    .matches(&submission));
+    assert!(!Query {
+        chip_kind: Some("DMG-CPU C".to_owned()),
+        years: Some(1995..=1995),
+        ..Query::default()
+    }
+    .matches(&submission));
+}
+
+#[test]
+fn test_category_order() {
+    assert!(Category::Cartridge < Category::Console(Console::Dmg));
+    assert!(Category::Console(Console::Dmg) < Category::Console(Console::Sgb));
+    assert!(Category::Console(Console::Gbs) < Category::Console(Console::Oxy));
+}
@@ -0,0 +1,479 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    config::{
+        cartridge::{BoardLayout, ChipRole, ChipRoleConfig, GameConfig, PartDesignator},
+        console::ChipParser,
+    },
+    input::cartridge::CartridgeBoard,
+    parser::{self, LabelParser, Manufacturer, Year},
+    time::{Month, Week},
+};
+
+/// Information that can be extracted from any parsed chip label
+pub trait ParsedChip {
+    fn kind(&self) -> Option<String> {
+        None
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        None
+    }
+    fn year(&self) -> Option<Year> {
+        None
+    }
+    fn week(&self) -> Option<Week> {
+        None
+    }
+    fn month(&self) -> Option<Month> {
+        None
+    }
+    fn rom_code(&self) -> Option<String> {
+        None
+    }
+}
+
+/// A chip label and the information parsed from it
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ChipData {
+    pub label: Option<String>,
+    pub kind: Option<String>,
+    pub manufacturer: Option<Manufacturer>,
+    pub year: Option<u16>,
+    pub month: Option<Month>,
+    pub week: Option<Week>,
+    pub rom_code: Option<String>,
+    /// True if the chip has been marked as an outlier in the submission
+    pub outlier: bool,
+}
+
+impl ChipData {
+    /// Returns chip data that contains only the label
+    pub fn from_label(label: &str) -> ChipData {
+        ChipData {
+            label: Some(label.to_owned()),
+            ..ChipData::default()
+        }
+    }
+    /// Parses a chip label with the given parser.
+    ///
+    /// Partial years are completed using the year hint.
+    pub fn parse<T: ParsedChip, F: LabelParser<T>>(
+        year_hint: Option<u16>,
+        label: &str,
+        f: &F,
+    ) -> Result<ChipData, String> {
+        let chip = f
+            .parse(label)
+            .map_err(|err| format!("Failed to parse chip label {label}: {err}"))?;
+        Ok(ChipData {
+            label: Some(label.to_owned()),
+            kind: chip.kind(),
+            manufacturer: chip.manufacturer(),
+            year: chip.year().and_then(|year| year.full_year(year_hint)),
+            month: chip.month(),
+            week: chip.week(),
+            rom_code: chip.rom_code(),
+            outlier: false,
+        })
+    }
+}
+
+/// Parses a console chip label with the parser configured for the chip
+///
+/// ```
+/// # use gbhwdb_backend::{config::console::ChipParser, db::chip::parse_console_chip};
+/// let chip = parse_console_chip(ChipParser::Gen1Soc, None, "DMG-CPU B © 1989 Nintendo JAPAN 9207 D").unwrap();
+/// assert_eq!(chip.year, Some(1992));
+/// assert_eq!(chip.kind.as_deref(), Some("DMG-CPU B"));
+/// ```
+pub fn parse_console_chip(
+    parser: ChipParser,
+    year_hint: Option<u16>,
+    label: &str,
+) -> Result<ChipData, String> {
+    use parser::*;
+    match parser {
+        ChipParser::Gen1Soc => ChipData::parse(year_hint, label, gen1_soc::gen1_soc()),
+        ChipParser::Gen2Soc => ChipData::parse(year_hint, label, gen2_soc::gen2_soc()),
+        ChipParser::CgbSoc => ChipData::parse(year_hint, label, cgb_soc::cgb_soc()),
+        ChipParser::AgbSocQfp128 => {
+            ChipData::parse(year_hint, label, agb_soc_qfp_128::agb_soc_qfp_128())
+        }
+        ChipParser::AgbSocQfp156 => {
+            ChipData::parse(year_hint, label, agb_soc_qfp_156::agb_soc_qfp_156())
+        }
+        ChipParser::AgbSocBga => ChipData::parse(year_hint, label, agb_soc_bga::agb_soc_bga()),
+        ChipParser::Ram => ChipData::parse(year_hint, label, ram::ram()),
+        ChipParser::SramTsop1_48 => {
+            ChipData::parse(year_hint, label, sram_tsop1_48::sram_tsop1_48())
+        }
+        ChipParser::Icd2 => ChipData::parse(year_hint, label, icd2::icd2()),
+        ChipParser::SgbRom => ChipData::parse(year_hint, label, sgb_rom::sgb_rom()),
+        ChipParser::Cic => ChipData::parse(year_hint, label, cic::cic()),
+        ChipParser::DmgAmp => ChipData::parse(year_hint, label, dmg_amp::dmg_amp()),
+        ChipParser::MgbAmp => ChipData::parse(year_hint, label, mgb_amp::mgb_amp()),
+        ChipParser::AgbAmp => ChipData::parse(year_hint, label, agb_amp::agb_amp()),
+        ChipParser::DmgReg => ChipData::parse(year_hint, label, dmg_reg::dmg_reg()),
+        ChipParser::CgbReg => ChipData::parse(year_hint, label, cgb_reg::cgb_reg()),
+        ChipParser::AgbReg => ChipData::parse(year_hint, label, agb_reg::agb_reg()),
+        ChipParser::AgbPmic => ChipData::parse(year_hint, label, agb_pmic::agb_pmic()),
+        ChipParser::AgsPmicOld => ChipData::parse(year_hint, label, ags_pmic_old::ags_pmic_old()),
+        ChipParser::AgsPmicNew => ChipData::parse(year_hint, label, ags_pmic_new::ags_pmic_new()),
+        ChipParser::AgsChargeController => {
+            ChipData::parse(year_hint, label, ags_charge_ctrl::ags_charge_ctrl())
+        }
+        ChipParser::GbsDol => ChipData::parse(year_hint, label, gbs_dol::gbs_dol()),
+        ChipParser::GbsReg => ChipData::parse(year_hint, label, gbs_reg::gbs_reg()),
+        ChipParser::OxyPmic => ChipData::parse(year_hint, label, oxy_pmic::oxy_pmic()),
+        ChipParser::OxyU4 => ChipData::parse(year_hint, label, oxy_u4::oxy_u4()),
+        ChipParser::OxyU5 => ChipData::parse(year_hint, label, oxy_u5::oxy_u5()),
+        ChipParser::Crystal4MiHz => {
+            ChipData::parse(year_hint, label, crystal_4mihz::crystal_4mihz())
+        }
+        ChipParser::Crystal8MiHz => {
+            ChipData::parse(year_hint, label, crystal_8mihz::crystal_8mihz())
+        }
+        ChipParser::Crystal20MiHz => {
+            ChipData::parse(year_hint, label, crystal_20mihz::crystal_20mihz())
+        }
+        ChipParser::Crystal32MiHz => {
+            ChipData::parse(year_hint, label, crystal_32mihz::crystal_32mihz())
+        }
+        ChipParser::Coil => ChipData::parse(year_hint, label, coil::coil()),
+        ChipParser::MglTransformer => {
+            ChipData::parse(year_hint, label, mgl_transformer::mgl_transformer())
+        }
+    }
+}
+
+/// Parses a cartridge chip label based on the board layout and the role of the chip.
+///
+/// Chips on unlicensed boards are often unmarked or use unknown parts, so only ROM and mapper
+/// labels are parsed on those boards.
+///
+/// ```
+/// # use gbhwdb_backend::{config::cartridge::{BoardLayout, ChipRole}, db::chip::parse_cartridge_chip};
+/// let chip = parse_cartridge_chip(BoardLayout::RomMapper, Some(1998), ChipRole::Mapper, "DMG MBC1B Nintendo S 9107 5 A").unwrap();
+/// assert_eq!(chip.kind.as_deref(), Some("MBC1B"));
+/// assert_eq!(chip.year, Some(1991));
+/// ```
+pub fn parse_cartridge_chip(
+    layout: BoardLayout,
+    board_year: Option<u16>,
+    role: ChipRole,
+    label: &str,
+) -> Result<ChipData, String> {
+    use parser::*;
+    let tama = |kind: &str| {
+        ChipData::parse(board_year, label, tama::tama()).map(|chip| ChipData {
+            kind: Some(kind.to_owned()),
+            ..chip
+        })
+    };
+    if layout.is_unlicensed() {
+        return match role {
+            ChipRole::Rom => ChipData::parse(board_year, label, mask_rom::mask_rom()),
+            ChipRole::Mapper => ChipData::parse(board_year, label, mapper::mapper()),
+            _ => Ok(ChipData::from_label(label)),
+        };
+    }
+    match role {
+        ChipRole::Rom if layout == BoardLayout::Tama => tama("TAMA7"),
+        ChipRole::Rom => ChipData::parse(board_year, label, mask_rom::mask_rom()),
+        ChipRole::Mapper if layout == BoardLayout::Tama => tama("TAMA5"),
+        ChipRole::Mapper => ChipData::parse(board_year, label, mapper::mapper()),
+        ChipRole::Mcu => tama("TAMA6"),
+        ChipRole::Ram => ChipData::parse(board_year, label, ram::ram()),
+        ChipRole::SupervisorReset => {
+            ChipData::parse(board_year, label, supervisor_reset::supervisor_reset())
+        }
+        ChipRole::Crystal => ChipData::parse(board_year, label, crystal_32kihz::crystal_32kihz()),
+        ChipRole::Flash => ChipData::parse(board_year, label, flash::flash()),
+        ChipRole::Eeprom => ChipData::parse(board_year, label, eeprom::eeprom()),
+        ChipRole::Accelerometer => {
+            ChipData::parse(board_year, label, accelerometer::accelerometer())
+        }
+        ChipRole::LineDecoder => ChipData::parse(board_year, label, line_decoder::line_decoder()),
+        ChipRole::HexInverter => ChipData::parse(board_year, label, hex_inverter::hex_inverter()),
+        ChipRole::Rtc => ChipData::parse(board_year, label, rtc::rtc()),
+        ChipRole::Battery => ChipData::parse(board_year, label, battery::battery_cr2025()),
+        ChipRole::Unknown => Ok(ChipData::from_label(label)),
+    }
+}
+
+/// Returns all populated chips of a cartridge board.
+///
+/// A chip is returned as an error if its label can't be parsed. Unlicensed chips fall back to
+/// the plain label instead.
+pub fn cartridge_chips(
+    cfg: &GameConfig,
+    layout: BoardLayout,
+    board: &CartridgeBoard,
+) -> Vec<(PartDesignator, ChipRole, Result<ChipData, String>)> {
+    let mut chips = Vec::new();
+    for (designator, role) in ChipRoleConfig::from(layout).iter() {
+        if let Some(chip) = &board[designator] {
+            let data = match chip.label.as_deref() {
+                Some(label) if layout.is_unlicensed() => {
+                    Ok(parse_cartridge_chip(layout, board.year, role, label)
+                        .unwrap_or_else(|_| ChipData::from_label(label)))
+                }
+                Some(label) => parse_cartridge_chip(layout, board.year, role, label),
+                None if layout.is_unlicensed() => Ok(ChipData {
+                    kind: Some("Blob".to_owned()),
+                    ..ChipData::default()
+                }),
+                None => Ok(ChipData::default()),
+            };
+            // Unlicensed mappers are usually unmarked, so the game config is used if the label
+            // can't be parsed. A parsed mapper is kept, and lint reports any mismatch
+            let mapper_designator = match layout {
+                BoardLayout::UnlRomMapper => Some(PartDesignator::U2),
+                BoardLayout::UnlBlob => Some(PartDesignator::U1),
+                _ => None,
+            };
+            let data = match cfg.mapper.filter(|_| mapper_designator == Some(designator)) {
+                Some(mapper) => data.map(|data| match data.kind.as_deref() {
+                    None | Some("Blob") => ChipData {
+                        kind: Some(mapper.display_name().to_owned()),
+                        ..data
+                    },
+                    Some(_) => data,
+                }),
+                None => data,
+            };
+            let data = data.map(|data| ChipData {
+                outlier: chip.outlier,
+                ..data
+            });
+            chips.push((designator, role, data));
+        }
+    }
+    chips
+}
+
+impl ParsedChip for parser::Gen1Soc {
+    fn kind(&self) -> Option<String> {
+        use parser::Gen1SocKind::*;
+        Some(
+            (match self.kind {
+                Dmg0 => "DMG-CPU",
+                DmgA => "DMG-CPU A",
+                DmgB => "DMG-CPU B",
+                DmgC => "DMG-CPU C",
+                DmgBlobB => "DMG-CPU B (blob)",
+                DmgBlobC => "DMG-CPU C (blob)",
+                Sgb => "SGB-CPU 01",
+            })
+            .to_owned(),
+        )
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        Some(Manufacturer::Sharp)
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::Gen2Soc {
+    fn kind(&self) -> Option<String> {
+        use parser::Gen2SocKind::*;
+        Some(
+            (match self.kind {
+                Mgb => "CPU MGB",
+                Sgb2 => "CPU SGB2",
+            })
+            .to_owned(),
+        )
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        Some(Manufacturer::Sharp)
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::StaticRam {
+    fn kind(&self) -> Option<String> {
+        self.part.clone()
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::Crystal {
+    fn kind(&self) -> Option<String> {
+        Some(self.format_frequency())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+    fn month(&self) -> Option<Month> {
+        self.month
+    }
+}
+
+impl ParsedChip for parser::Coil {
+    fn kind(&self) -> Option<String> {
+        Some(self.kind.clone())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+}
+
+impl ParsedChip for parser::Transformer {
+    fn kind(&self) -> Option<String> {
+        Some(self.kind.clone())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+}
+
+impl ParsedChip for parser::SgbRom {
+    fn kind(&self) -> Option<String> {
+        self.chip_type.clone()
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+    fn rom_code(&self) -> Option<String> {
+        Some(self.rom_code.clone())
+    }
+}
+
+impl ParsedChip for parser::ChipYearWeek {
+    fn kind(&self) -> Option<String> {
+        Some(self.kind.clone())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::MaskRom {
+    fn kind(&self) -> Option<String> {
+        self.chip_type.clone()
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::Mapper {
+    fn kind(&self) -> Option<String> {
+        Some(self.mbc_type.display_name().to_owned())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::tama::Tama {
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::SupervisorReset {
+    fn kind(&self) -> Option<String> {
+        Some(self.chip_type.clone())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn week(&self) -> Option<Week> {
+        self.week
+    }
+}
+
+impl ParsedChip for parser::Battery {
+    fn kind(&self) -> Option<String> {
+        Some(self.kind.clone())
+    }
+    fn manufacturer(&self) -> Option<Manufacturer> {
+        self.manufacturer
+    }
+    fn year(&self) -> Option<Year> {
+        self.year
+    }
+    fn month(&self) -> Option<Month> {
+        self.month
+    }
+}
+
+#[test]
+fn test_cartridge_chips_outlier() {
+    let cfg: GameConfig = serde_json::from_str(
+        r#"{"name": "Test", "rom_verified": false, "platform": "gb", "layouts": ["rom_mapper"]}"#,
+    )
+    .unwrap();
+    let board: CartridgeBoard = serde_json::from_str(
+        r#"{"label": "DMG-BEAN-02", "u1": {"label": "DMG-TRA-1"},
+            "u2": {"label": "DMG MBC1B Nintendo S 9107 5 A", "outlier": true}}"#,
+    )
+    .unwrap();
+    let chips = cartridge_chips(&cfg, BoardLayout::RomMapper, &board);
+    let outliers = chips
+        .iter()
+        .map(|(designator, _, chip)| (*designator, chip.as_ref().is_ok_and(|chip| chip.outlier)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outliers,
+        [(PartDesignator::U1, false), (PartDesignator::U2, true)]
+    );
+}
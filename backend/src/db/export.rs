@@ -0,0 +1,253 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Versioned JSON export of all submissions.
+//!
+//! The export consists of `index.json`, `cartridges.json` and one `consoles/{id}.json` file per
+//! console. Every file has a top-level `schema_version`:
+//!
+//! - `index.json`: `files` lists every data file with its `category`, `name`, relative `path` and
+//!   number of `submissions`
+//! - data files: `category`, `name` and `submissions`. Every submission has `code`, `slug`,
+//!   `title`, `contributor`, `url`, `board`, `outlier`, `photos` (`name`, `url`) and `chips`
+//! - chips: `designator`, `role`, `label` (as written on the chip), `outlier`, and the fields
+//!   parsed from the label: `kind`, `manufacturer`, `year`, `month`, `week`, `rom_code` and
+//!   `date` (`from` and `to` as YYYY-MM-DD, covering the most precise known date)
+//!
+//! Missing values are `null`. The schema version is incremented whenever a field is removed or
+//! its meaning changes. New fields may be added without changing the version. Version 1 includes
+//! the chip `outlier` field.
+
+use serde::Serialize;
+
+use super::{Category, Database, Submission, SubmissionChip};
+use crate::{
+    time::{date_range, Month, Week},
+    Console,
+};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportIndex {
+    pub schema_version: u32,
+    pub files: Vec<ExportIndexEntry>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportIndexEntry {
+    pub category: &'static str,
+    pub name: &'static str,
+    /// Path relative to the index file
+    pub path: String,
+    pub submissions: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportFile<'a> {
+    pub schema_version: u32,
+    pub category: &'static str,
+    pub name: &'static str,
+    pub submissions: Vec<ExportSubmission<'a>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportSubmission<'a> {
+    pub code: &'a str,
+    pub slug: &'a str,
+    pub title: &'a str,
+    pub contributor: &'a str,
+    pub url: String,
+    pub board: &'a str,
+    pub outlier: bool,
+    pub photos: Vec<ExportPhoto>,
+    pub chips: Vec<ExportChip<'a>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportPhoto {
+    pub name: &'static str,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportChip<'a> {
+    pub designator: &'static str,
+    pub role: &'static str,
+    pub label: Option<&'a str>,
+    pub outlier: bool,
+    pub kind: Option<&'a str>,
+    pub manufacturer: Option<&'static str>,
+    pub year: Option<u16>,
+    pub month: Option<Month>,
+    pub week: Option<Week>,
+    pub rom_code: Option<&'a str>,
+    pub date: Option<ExportDateRange>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ExportDateRange {
+    pub from: String,
+    pub to: String,
+}
+
+/// All export files, keyed by their path relative to the index
+#[derive(Clone, Debug)]
+pub struct Export<'a> {
+    pub index: ExportIndex,
+    pub files: Vec<(String, ExportFile<'a>)>,
+}
+
+/// Builds the export from the database. Site and photo URLs are prefixed with `base_url`.
+pub fn export<'a>(db: &'a Database, base_url: &str) -> Export<'a> {
+    let categories = [Category::Cartridge]
+        .into_iter()
+        .chain(Console::ALL.into_iter().map(Category::Console));
+    let mut index = ExportIndex {
+        schema_version: SCHEMA_VERSION,
+        files: Vec::new(),
+    };
+    let mut files = Vec::new();
+    for category in categories {
+        let path = match category {
+            Category::Cartridge => "cartridges.json".to_owned(),
+            Category::Console(console) => format!("consoles/{}.json", console.id()),
+        };
+        let submissions = db
+            .submissions
+            .iter()
+            .filter(|submission| submission.category == category)
+            .map(|submission| export_submission(submission, base_url))
+            .collect::<Vec<_>>();
+        index.files.push(ExportIndexEntry {
+            category: category.id(),
+            name: category.name(),
+            path: path.clone(),
+            submissions: submissions.len(),
+        });
+        files.push((
+            path,
+            ExportFile {
+                schema_version: SCHEMA_VERSION,
+                category: category.id(),
+                name: category.name(),
+                submissions,
+            },
+        ));
+    }
+    Export { index, files }
+}
+
+fn export_submission<'a>(submission: &'a Submission, base_url: &str) -> ExportSubmission<'a> {
+    let (page_dir, photo_dir) = match submission.category {
+        Category::Cartridge => ("cartridges", submission.code.as_str()),
+        Category::Console(console) => ("consoles", console.id()),
+    };
+    let slug = &submission.slug;
+    ExportSubmission {
+        code: &submission.code,
+        slug,
+        title: &submission.title,
+        contributor: &submission.contributor,
+        url: format!("{base_url}/{page_dir}/{photo_dir}/{slug}.html"),
+        board: &submission.board,
+        outlier: submission.outlier,
+        photos: submission
+            .photos
+            .iter()
+            .map(|photo| ExportPhoto {
+                name: photo.name,
+                url: format!("{base_url}/static/{photo_dir}/{slug}_{}", photo.name),
+            })
+            .collect(),
+        chips: submission.chips.iter().map(export_chip).collect(),
+    }
+}
+
+fn export_chip(chip: &SubmissionChip) -> ExportChip<'_> {
+    let data = &chip.chip;
+    ExportChip {
+        designator: chip.designator,
+        role: chip.role,
+        label: data.label.as_deref(),
+        outlier: data.outlier,
+        kind: data.kind.as_deref(),
+        manufacturer: data.manufacturer.map(|manufacturer| manufacturer.name()),
+        year: data.year,
+        month: data.month,
+        week: data.week,
+        rom_code: data.rom_code.as_deref(),
+        date: data
+            .year
+            .and_then(|year| date_range(year, data.month, data.week))
+            .map(|range| ExportDateRange {
+                from: range.start().to_string(),
+                to: range.end().to_string(),
+            }),
+    }
+}
+
+#[test]
+fn test_export() {
+    use super::{chip::ChipData, Photo};
+    use crate::parser::Manufacturer;
+
+    let db = Database {
+        cfgs: Default::default(),
+        submissions: vec![Submission {
+            category: Category::Console(Console::Dmg),
+            code: "DMG".to_owned(),
+            slug: "G10000000".to_owned(),
+            contributor: "test".to_owned(),
+            title: "G10000000".to_owned(),
+            board: "DMG-CPU-06".to_owned(),
+            outlier: false,
+            chips: vec![SubmissionChip {
+                designator: "U1",
+                role: "CPU",
+                chip: ChipData {
+                    label: Some("DMG-CPU C".to_owned()),
+                    kind: Some("DMG-CPU C".to_owned()),
+                    manufacturer: Some(Manufacturer::Sharp),
+                    year: Some(1996),
+                    month: Some(Month::March),
+                    outlier: true,
+                    ..ChipData::default()
+                },
+            }],
+            photos: vec![Photo {
+                name: "01_front.jpg",
+                path: "01_front.jpg".into(),
+            }],
+            metadata: serde_json::Value::Null,
+            data: std::sync::Arc::new(()),
+        }],
+    };
+    let export = export(&db, "https://example.com");
+    assert_eq!(export.files.len(), 11);
+    let dmg = &export.index.files[1];
+    assert_eq!(
+        (dmg.path.as_str(), dmg.submissions),
+        ("consoles/dmg.json", 1)
+    );
+
+    let (_, file) = &export.files[1];
+    let submission = &file.submissions[0];
+    assert_eq!(
+        submission.url,
+        "https://example.com/consoles/dmg/G10000000.html"
+    );
+    assert_eq!(
+        submission.photos[0].url,
+        "https://example.com/static/dmg/G10000000_01_front.jpg"
+    );
+    assert!(submission.chips[0].outlier);
+    assert_eq!(
+        submission.chips[0].date,
+        Some(ExportDateRange {
+            from: "1996-03-01".to_owned(),
+            to: "1996-03-31".to_owned()
+        })
+    );
+}
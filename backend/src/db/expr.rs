@@ -0,0 +1,580 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Filter expressions for querying submissions.
+//!
+//! An expression is made of comparisons combined with `and`, `or`, `not` and parentheses:
+//!
+//! ```text
+//! console = DMG and chip.role = WRAM and chip.kind ~ LH5164 and chip.year < 1991
+//! ```
+//!
+//! Comparison operators are `=`, `!=`, `<`, `<=`, `>`, `>=` and `~` (contains). Text comparisons
+//! are case-insensitive, and values containing spaces or operator characters must be quoted.
+//! A field without an operator is true if its value is present and not `false`.
+//! Comparisons against a missing value are always false.
+//!
+//! Chip fields (`chip.*`) are evaluated against one chip at a time, so all chip comparisons in an
+//! expression must match the same chip.
+
+use serde::Serialize;
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use super::{Category, Submission, SubmissionChip};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Field {
+    Console,
+    Code,
+    Slug,
+    Title,
+    Contributor,
+    Board,
+    Outlier,
+    ChipDesignator,
+    ChipRole,
+    ChipKind,
+    ChipManufacturer,
+    ChipYear,
+    ChipMonth,
+    ChipWeek,
+    ChipLabel,
+    ChipRomCode,
+}
+
+impl Field {
+    pub const ALL: [Field; 16] = [
+        Field::Console,
+        Field::Code,
+        Field::Slug,
+        Field::Title,
+        Field::Contributor,
+        Field::Board,
+        Field::Outlier,
+        Field::ChipDesignator,
+        Field::ChipRole,
+        Field::ChipKind,
+        Field::ChipManufacturer,
+        Field::ChipYear,
+        Field::ChipMonth,
+        Field::ChipWeek,
+        Field::ChipLabel,
+        Field::ChipRomCode,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Console => "console",
+            Field::Code => "code",
+            Field::Slug => "slug",
+            Field::Title => "title",
+            Field::Contributor => "contributor",
+            Field::Board => "board",
+            Field::Outlier => "outlier",
+            Field::ChipDesignator => "chip.designator",
+            Field::ChipRole => "chip.role",
+            Field::ChipKind => "chip.kind",
+            Field::ChipManufacturer => "chip.manufacturer",
+            Field::ChipYear => "chip.year",
+            Field::ChipMonth => "chip.month",
+            Field::ChipWeek => "chip.week",
+            Field::ChipLabel => "chip.label",
+            Field::ChipRomCode => "chip.rom_code",
+        }
+    }
+    pub fn is_chip_field(&self) -> bool {
+        self.name().starts_with("chip.")
+    }
+    /// Returns the value of this field in a submission, or in one of its chips
+    pub fn value(&self, submission: &Submission, chip: Option<&SubmissionChip>) -> Value {
+        let text = |value: &str| Value::Text(value.to_owned());
+        match self {
+            Field::Console => match submission.category {
+                Category::Console(console) => text(console.id()),
+                Category::Cartridge => Value::Null,
+            },
+            Field::Code => text(&submission.code),
+            Field::Slug => text(&submission.slug),
+            Field::Title => text(&submission.title),
+            Field::Contributor => text(&submission.contributor),
+            Field::Board => text(&submission.board),
+            Field::Outlier => Value::Bool(submission.outlier),
+            _ => {
+                let Some(chip) = chip else {
+                    return Value::Null;
+                };
+                let data = &chip.chip;
+                let value = match self {
+                    Field::ChipDesignator => Some(text(chip.designator)),
+                    Field::ChipRole => Some(text(chip.role)),
+                    Field::ChipKind => data.kind.as_deref().map(text),
+                    Field::ChipManufacturer => data
+                        .manufacturer
+                        .map(|manufacturer| text(manufacturer.name())),
+                    Field::ChipYear => data.year.map(|year| Value::Number(year.into())),
+                    Field::ChipMonth => data
+                        .month
+                        .map(|month| Value::Number(u8::from(month).into())),
+                    Field::ChipWeek => data.week.map(|week| Value::Number(u8::from(week).into())),
+                    Field::ChipLabel => data.label.as_deref().map(text),
+                    Field::ChipRomCode => data.rom_code.as_deref().map(text),
+                    _ => unreachable!(),
+                };
+                value.unwrap_or(Value::Null)
+            }
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    /// Parses a field name. The `chip.` prefix of chip fields is optional.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.to_ascii_lowercase();
+        Field::ALL
+            .into_iter()
+            .find(|field| {
+                field.name() == name
+                    || (field.is_chip_field() && field.name().strip_prefix("chip.") == Some(&name))
+            })
+            .ok_or_else(|| format!("Unknown field {name}"))
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(u32),
+    Text(String),
+}
+
+impl Value {
+    fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (Value::Number(a), Value::Number(b)) => Some(a.cmp(b)),
+            (Value::Text(a), Value::Text(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+            // Bare values that look like numbers, e.g. `code = 1234`
+            (Value::Text(a), Value::Number(b)) => Some(a.as_str().cmp(b.to_string().as_str())),
+            _ => None,
+        }
+    }
+    fn contains(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Text(a), Value::Text(b)) => a.to_lowercase().contains(&b.to_lowercase()),
+            (Value::Text(a), Value::Number(b)) => a.contains(&b.to_string()),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Bool(value) => fmt::Display::fmt(value, f),
+            Value::Number(value) => fmt::Display::fmt(value, f),
+            Value::Text(value) => f.write_str(value),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+impl Op {
+    fn apply(&self, lhs: &Value, rhs: &Value) -> bool {
+        if *self == Op::Contains {
+            return lhs.contains(rhs);
+        }
+        match lhs.compare(rhs) {
+            Some(ordering) => match self {
+                Op::Eq => ordering.is_eq(),
+                Op::Ne => ordering.is_ne(),
+                Op::Lt => ordering.is_lt(),
+                Op::Le => ordering.is_le(),
+                Op::Gt => ordering.is_gt(),
+                Op::Ge => ordering.is_ge(),
+                Op::Contains => unreachable!(),
+            },
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expr {
+    Field(Field),
+    Compare(Field, Op, Value),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parses a filter expression
+    ///
+    /// ```
+    /// # use gbhwdb_backend::db::expr::{Expr, Field, Op, Value};
+    /// let expr = Expr::parse("chip.year < 1991 and not outlier").unwrap();
+    /// assert_eq!(
+    ///     expr,
+    ///     Expr::And(
+    ///         Box::new(Expr::Compare(Field::ChipYear, Op::Lt, Value::Number(1991))),
+    ///         Box::new(Expr::Not(Box::new(Expr::Field(Field::Outlier)))),
+    ///     )
+    /// );
+    /// assert!(Expr::parse("chip.year <").is_err());
+    /// ```
+    pub fn parse(input: &str) -> Result<Expr, ExprError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            end: input.len(),
+        };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            Some((position, token)) => Err(ExprError {
+                position,
+                message: format!("Unexpected {token}"),
+            }),
+            None => Ok(expr),
+        }
+    }
+    pub fn uses_chip_fields(&self) -> bool {
+        match self {
+            Expr::Field(field) | Expr::Compare(field, _, _) => field.is_chip_field(),
+            Expr::Not(expr) => expr.uses_chip_fields(),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.uses_chip_fields() || rhs.uses_chip_fields()
+            }
+        }
+    }
+    /// Evaluates the expression against a submission and optionally one of its chips
+    pub fn eval(&self, submission: &Submission, chip: Option<&SubmissionChip>) -> bool {
+        match self {
+            Expr::Field(field) => !matches!(
+                field.value(submission, chip),
+                Value::Null | Value::Bool(false)
+            ),
+            Expr::Compare(field, op, value) => op.apply(&field.value(submission, chip), value),
+            Expr::Not(expr) => !expr.eval(submission, chip),
+            Expr::And(lhs, rhs) => lhs.eval(submission, chip) && rhs.eval(submission, chip),
+            Expr::Or(lhs, rhs) => lhs.eval(submission, chip) || rhs.eval(submission, chip),
+        }
+    }
+    /// Returns true if the submission, or at least one of its chips, matches the expression
+    pub fn matches(&self, submission: &Submission) -> bool {
+        if self.uses_chip_fields() {
+            submission
+                .chips
+                .iter()
+                .any(|chip| self.eval(submission, Some(chip)))
+        } else {
+            self.eval(submission, None)
+        }
+    }
+    /// Returns the chips of the submission that match the expression
+    pub fn matching_chips<'a>(
+        &'a self,
+        submission: &'a Submission,
+    ) -> impl Iterator<Item = &'a SubmissionChip> + 'a {
+        submission
+            .chips
+            .iter()
+            .filter(move |chip| self.eval(submission, Some(chip)))
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Expr::parse(input)
+    }
+}
+
+/// Expression syntax error
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExprError {
+    /// Byte offset in the input
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Op(Op),
+    Word(String),
+    Quoted(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::And => f.write_str("'and'"),
+            Token::Or => f.write_str("'or'"),
+            Token::Not => f.write_str("'not'"),
+            Token::Op(_) => f.write_str("operator"),
+            Token::Word(word) => write!(f, "'{word}'"),
+            Token::Quoted(text) => write!(f, "\"{text}\""),
+        }
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    !ch.is_whitespace() && !"()=!<>~\"'&|".contains(ch)
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((position, ch)) = chars.next() {
+        let mut next_is = |expected: char| chars.next_if(|&(_, ch)| ch == expected).is_some();
+        let token = match ch {
+            _ if ch.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '=' => {
+                next_is('=');
+                Token::Op(Op::Eq)
+            }
+            '~' => Token::Op(Op::Contains),
+            '!' if next_is('=') => Token::Op(Op::Ne),
+            '!' => Token::Not,
+            '<' if next_is('=') => Token::Op(Op::Le),
+            '<' => Token::Op(Op::Lt),
+            '>' if next_is('=') => Token::Op(Op::Ge),
+            '>' => Token::Op(Op::Gt),
+            '&' if next_is('&') => Token::And,
+            '|' if next_is('|') => Token::Or,
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, end)) if end == ch => break,
+                        Some((_, ch)) => text.push(ch),
+                        None => {
+                            return Err(ExprError {
+                                position,
+                                message: "Unterminated string".to_owned(),
+                            })
+                        }
+                    }
+                }
+                Token::Quoted(text)
+            }
+            _ if is_word_char(ch) => {
+                let mut word = String::from(ch);
+                while let Some((_, ch)) = chars.next_if(|&(_, ch)| is_word_char(ch)) {
+                    word.push(ch);
+                }
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                }
+            }
+            _ => {
+                return Err(ExprError {
+                    position,
+                    message: format!("Unexpected character '{ch}'"),
+                })
+            }
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<(usize, &'a Token)> {
+        self.tokens
+            .get(self.pos)
+            .map(|(position, token)| (*position, token))
+    }
+    fn next(&mut self) -> Result<(usize, &'a Token), ExprError> {
+        let next = self.peek().ok_or_else(|| ExprError {
+            position: self.end,
+            message: "Unexpected end of expression".to_owned(),
+        })?;
+        self.pos += 1;
+        Ok(next)
+    }
+    fn eat(&mut self, expected: &Token) -> bool {
+        let found = self.peek().is_some_and(|(_, token)| token == expected);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+    fn parse_or(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+    fn parse_and(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.parse_not()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+    fn parse_not(&mut self) -> Result<Expr, ExprError> {
+        if self.eat(&Token::Not) {
+            Ok(Expr::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_primary()
+        }
+    }
+    fn parse_primary(&mut self) -> Result<Expr, ExprError> {
+        match self.next()? {
+            (_, Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next()? {
+                    (_, Token::RParen) => Ok(expr),
+                    (position, token) => Err(ExprError {
+                        position,
+                        message: format!("Expected ')', found {token}"),
+                    }),
+                }
+            }
+            (position, Token::Word(name)) => {
+                let field = name
+                    .parse::<Field>()
+                    .map_err(|message| ExprError { position, message })?;
+                match self.peek() {
+                    Some((_, &Token::Op(op))) => {
+                        self.pos += 1;
+                        Ok(Expr::Compare(field, op, self.parse_value()?))
+                    }
+                    _ => Ok(Expr::Field(field)),
+                }
+            }
+            (position, token) => Err(ExprError {
+                position,
+                message: format!("Expected a field, found {token}"),
+            }),
+        }
+    }
+    fn parse_value(&mut self) -> Result<Value, ExprError> {
+        match self.next()? {
+            (_, Token::Quoted(text)) => Ok(Value::Text(text.clone())),
+            (_, Token::Word(word)) => Ok(match word.to_ascii_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => match word.parse::<u32>() {
+                    Ok(number) => Value::Number(number),
+                    Err(_) => Value::Text(word.clone()),
+                },
+            }),
+            (position, token) => Err(ExprError {
+                position,
+                message: format!("Expected a value, found {token}"),
+            }),
+        }
+    }
+}
+
+#[test]
+fn test_expr() {
+    use crate::{db::chip::ChipData, parser::Manufacturer, Console};
+
+    let chip = |role, kind: &str, year| SubmissionChip {
+        designator: "U1",
+        role,
+        chip: ChipData {
+            kind: Some(kind.to_owned()),
+            manufacturer: Some(Manufacturer::Sharp),
+            year: Some(year),
+            ..ChipData::default()
+        },
+    };
+    let submission = Submission {
+        category: Category::Console(Console::Dmg),
+        code: "dmg".to_owned(),
+        slug: "G10000000".to_owned(),
+        contributor: "test".to_owned(),
+        title: "G10000000".to_owned(),
+        board: "DMG-CPU-06".to_owned(),
+        outlier: false,
+        chips: vec![
+            chip("CPU", "DMG-CPU B", 1990),
+            chip("WRAM", "LH5164LN", 1992),
+        ],
+        photos: Vec::new(),
+        metadata: serde_json::Value::Null,
+        data: std::sync::Arc::new(()),
+    };
+    let matches = |input: &str| Expr::parse(input).unwrap().matches(&submission);
+    assert!(matches("console = DMG"));
+    assert!(matches("console == dmg && !outlier"));
+    assert!(matches(
+        "board = 'DMG-CPU-06' and chip.manufacturer = sharp"
+    ));
+    assert!(matches(
+        "chip.role = WRAM and chip.kind ~ lh5164 and chip.year >= 1992"
+    ));
+    assert!(!matches("chip.role = WRAM and chip.year < 1991"));
+    assert!(matches(
+        "(chip.role = CPU or chip.role = WRAM) and year < 1991"
+    ));
+    assert!(!matches("console = CGB or outlier"));
+    assert!(!matches("chip.week > 0"));
+    assert!(!matches("chip.week != 1"));
+
+    let expr = Expr::parse("kind ~ LH").unwrap();
+    assert_eq!(expr.matching_chips(&submission).count(), 1);
+
+    assert_eq!(
+        Expr::parse("foo = 1").unwrap_err(),
+        ExprError {
+            position: 0,
+            message: "Unknown field foo".to_owned()
+        }
+    );
+    assert_eq!(Expr::parse("(console = DMG").unwrap_err().position, 14);
+    assert_eq!(Expr::parse("board = \"DMG").unwrap_err().position, 8);
+}
@@ -0,0 +1,237 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Canonical formatting of data files.
+//!
+//! A file is canonical if it's exactly what the backend types serialize to. Formatting migrates
+//! the file to the current version, trims all strings, collapses whitespace in labels, and checks
+//! that the round trip through the backend types doesn't lose any data.
+
+use anyhow::{anyhow, bail, Context, Error};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::cartridge::GameConfig,
+    db::submission_dirs,
+    input::{
+        agb::AgbConsole, ags::AgsConsole, cartridge::Cartridge, cgb::CgbConsole,
+        console::ConsoleSubmission, dmg::DmgConsole, gbs::GbsConsole, mgb::MgbConsole,
+        mgl::MglConsole, oxy::OxyConsole, sgb::SgbConsole, sgb2::Sgb2Console,
+    },
+    migration::{migrate, MetadataKind},
+};
+
+/// Serializes data in the canonical format of all JSON data files
+pub fn to_canonical_json<T: Serialize>(data: &T) -> Result<String, serde_json::Error> {
+    let mut json = serde_json::to_string_pretty(data)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Trims all strings, and collapses whitespace in labels to single spaces.
+///
+/// ```
+/// # use gbhwdb_backend::format::normalize_strings;
+/// let mut value = serde_json::json!({
+///     "contributor": " test ",
+///     "mainboard": { "u1": { "label": "DMG-CPU  B\t8912 " } }
+/// });
+/// normalize_strings(&mut value);
+/// assert_eq!(value, serde_json::json!({
+///     "contributor": "test",
+///     "mainboard": { "u1": { "label": "DMG-CPU B 8912" } }
+/// }));
+/// ```
+pub fn normalize_strings(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = text.trim().to_owned(),
+        Value::Array(values) => values.iter_mut().for_each(normalize_strings),
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(text) if key.ends_with("label") => {
+                        *text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    }
+                    _ => normalize_strings(value),
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Returns JSON pointers to values of `input` that are missing or different in `output`.
+///
+/// Missing `false` and `null` values are not lost, because they are the defaults of omitted
+/// fields.
+fn lost_values(input: &Value, output: Option<&Value>, pointer: &str, lost: &mut Vec<String>) {
+    match (input, output) {
+        (Value::Bool(false) | Value::Null, None) => (),
+        (Value::Object(input), Some(Value::Object(output))) => {
+            for (key, value) in input {
+                let pointer = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+                lost_values(value, output.get(key), &pointer, lost);
+            }
+        }
+        (Value::Array(input), Some(Value::Array(output))) if input.len() == output.len() => {
+            for (index, (input, output)) in input.iter().zip(output).enumerate() {
+                lost_values(input, Some(output), &format!("{pointer}/{index}"), lost);
+            }
+        }
+        (input, Some(output)) if input == output => (),
+        _ => lost.push(pointer.to_owned()),
+    }
+}
+
+/// Round trips a normalized value through the backend type `T`, returning the canonical JSON
+fn round_trip<T>(value: &Value) -> Result<String, Error>
+where
+    T: DeserializeOwned + Serialize + PartialEq,
+{
+    let data = T::deserialize(value)?;
+    let json = to_canonical_json(&data)?;
+    let output: Value = serde_json::from_str(&json)?;
+    let mut lost = Vec::new();
+    lost_values(value, Some(&output), "", &mut lost);
+    if !lost.is_empty() {
+        bail!("round trip loses data at {}", lost.join(", "));
+    }
+    if T::deserialize(&output)? != data {
+        bail!("round trip changes the data");
+    }
+    Ok(json)
+}
+
+/// Data file and its canonical formatting
+#[derive(Clone, Debug)]
+pub struct Formatted {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+}
+
+impl Formatted {
+    pub fn is_canonical(&self) -> bool {
+        self.old == self.new
+    }
+}
+
+/// Formats one `metadata.json` file in memory, without writing it
+pub fn format_metadata<T>(kind: MetadataKind, path: &Path) -> Result<Formatted, Error>
+where
+    T: DeserializeOwned + Serialize + PartialEq,
+{
+    let old = fs::read_to_string(path)?;
+    let mut value = serde_json::from_str(&old)?;
+    migrate(kind, &mut value)?;
+    normalize_strings(&mut value);
+    let new = round_trip::<T>(&value)?;
+    Ok(Formatted {
+        path: path.to_owned(),
+        old,
+        new,
+    })
+}
+
+/// Formats `config/games.json` in memory, without writing it
+pub fn format_cfgs(path: &Path) -> Result<Formatted, Error> {
+    let old = fs::read_to_string(path)?;
+    let mut value = serde_json::from_str(&old)?;
+    normalize_strings(&mut value);
+    let new = round_trip::<BTreeMap<String, GameConfig>>(&value)?;
+    Ok(Formatted {
+        path: path.to_owned(),
+        old,
+        new,
+    })
+}
+
+/// Formats `config/games.json` and every `metadata.json` file in the given root directory.
+///
+/// Nothing is written, and files that fail to format are returned as errors.
+pub fn format_all(root: &Path) -> Result<Vec<Result<Formatted, Error>>, Error> {
+    let path = root.join("config/games.json");
+    let mut files = vec![format_cfgs(&path).map_err(|err| anyhow!("{}: {err:#}", path.display()))];
+    let games_dir = root.join("data/cartridges");
+    let mut game_dirs = fs::read_dir(&games_dir)
+        .with_context(|| format!("Failed to read {}", games_dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>, Error>>()?;
+    game_dirs.sort();
+    for game_dir in game_dirs.iter().filter(|dir| dir.is_dir()) {
+        format_dir::<Cartridge>(MetadataKind::Cartridge, game_dir, &mut files)?;
+    }
+    format_consoles::<DmgConsole>(root, &mut files)?;
+    format_consoles::<SgbConsole>(root, &mut files)?;
+    format_consoles::<MgbConsole>(root, &mut files)?;
+    format_consoles::<MglConsole>(root, &mut files)?;
+    format_consoles::<Sgb2Console>(root, &mut files)?;
+    format_consoles::<CgbConsole>(root, &mut files)?;
+    format_consoles::<AgbConsole>(root, &mut files)?;
+    format_consoles::<AgsConsole>(root, &mut files)?;
+    format_consoles::<GbsConsole>(root, &mut files)?;
+    format_consoles::<OxyConsole>(root, &mut files)?;
+    Ok(files)
+}
+
+fn format_consoles<C: ConsoleSubmission + PartialEq>(
+    root: &Path,
+    files: &mut Vec<Result<Formatted, Error>>,
+) -> Result<(), Error> {
+    let dir = root.join("data/consoles").join(C::CONSOLE.code());
+    if dir.exists() {
+        format_dir::<C>(MetadataKind::Console(C::CONSOLE), &dir, files)?;
+    }
+    Ok(())
+}
+
+fn format_dir<T>(
+    kind: MetadataKind,
+    dir: &Path,
+    files: &mut Vec<Result<Formatted, Error>>,
+) -> Result<(), Error>
+where
+    T: DeserializeOwned + Serialize + PartialEq,
+{
+    for dir in submission_dirs(dir)? {
+        let path = dir.join("metadata.json");
+        files.push(
+            format_metadata::<T>(kind, &path).map_err(|err| anyhow!("{}: {err:#}", path.display())),
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_round_trip() {
+    use crate::Console;
+
+    let mut value = serde_json::json!({
+        "slug": "G10000000",
+        "contributor": "test",
+        "shell": { "serial": " G10000000", "outlier": false },
+        "mainboard": { "label": "DMG-CPU-06", "u1": { "label": "DMG-CPU  C" } }
+    });
+    migrate(MetadataKind::Console(Console::Dmg), &mut value).unwrap();
+    normalize_strings(&mut value);
+    let json = round_trip::<DmgConsole>(&value).unwrap();
+    assert!(json.starts_with("{\n  \"schema_version\": 1,\n  \"slug\": \"G10000000\","));
+    assert!(json.contains("\"label\": \"DMG-CPU C\""));
+    assert!(!json.contains("outlier"));
+
+    let mut lost = Vec::new();
+    lost_values(
+        &serde_json::json!({ "a": { "b/c": 1, "d": false } }),
+        Some(&serde_json::json!({ "a": {} })),
+        "",
+        &mut lost,
+    );
+    assert_eq!(lost, vec!["/a/b~1c"]);
+}
@@ -2,12 +2,14 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub mod agb;
 pub mod ags;
 pub mod cartridge;
 pub mod cgb;
+pub mod console;
 pub mod dmg;
 pub mod gbs;
 pub mod mgb;
@@ -20,7 +22,7 @@ pub(crate) fn is_not_outlier(outlier: &bool) -> bool {
     !outlier
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Chip {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -39,7 +41,7 @@ impl Chip {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct LcdChip {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -51,7 +53,7 @@ pub struct LcdChip {
     pub outlier: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct LcdScreen {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -2,16 +2,27 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::ops::Index;
 
 use crate::{
-    input::{is_not_outlier, Chip},
+    config::console::ConsoleDesignator,
+    input::{
+        console::{ConsolePhotoSet, ConsoleShell, ConsoleSubmission},
+        is_not_outlier, Chip,
+    },
     time::Month,
+    Console,
 };
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct AgsConsole {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_version: u32,
     pub slug: String,
     pub contributor: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -20,7 +31,7 @@ pub struct AgsConsole {
     pub mainboard: AgsMainboard,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct AgsShell {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,7 +45,7 @@ pub struct AgsShell {
     pub outlier: bool,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub enum AgsShellColor {
     Cobalt,
     Platinum,
@@ -47,7 +58,7 @@ pub enum AgsShellColor {
     Tribal,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct AgsMainboard {
     pub label: String,
@@ -77,3 +88,56 @@ pub struct AgsMainboard {
     #[serde(skip_serializing_if = "is_not_outlier")]
     pub outlier: bool,
 }
+
+impl Index<ConsoleDesignator> for AgsMainboard {
+    type Output = Option<Chip>;
+
+    fn index(&self, index: ConsoleDesignator) -> &Self::Output {
+        match index {
+            ConsoleDesignator::U1 => &self.u1,
+            ConsoleDesignator::U2 => &self.u2,
+            ConsoleDesignator::U3 => &self.u3,
+            ConsoleDesignator::U4 => &self.u4,
+            ConsoleDesignator::U5 => &self.u5,
+            ConsoleDesignator::X1 => &self.x1,
+            _ => &None,
+        }
+    }
+}
+
+impl ConsoleSubmission for AgsConsole {
+    const CONSOLE: Console = Console::Ags;
+    const PHOTOS: ConsolePhotoSet = ConsolePhotoSet::Ags;
+    type Mainboard = AgsMainboard;
+
+    fn slug(&self) -> &str {
+        &self.slug
+    }
+    fn contributor(&self) -> &str {
+        &self.contributor
+    }
+    fn index(&self) -> Option<u16> {
+        self.index
+    }
+    fn shell(&self) -> ConsoleShell<'_> {
+        ConsoleShell {
+            color: self.shell.color.map(|color| format!("{:?}", color)),
+            release_code: self.shell.release_code.as_deref(),
+            serial: self.shell.serial.as_deref(),
+            outlier: self.shell.outlier,
+            ..ConsoleShell::default()
+        }
+    }
+    fn mainboard(&self) -> &Self::Mainboard {
+        &self.mainboard
+    }
+    fn mainboard_label(&self) -> &str {
+        &self.mainboard.label
+    }
+    fn year_hint(&self) -> Option<u16> {
+        self.mainboard.year.or(Some(2003))
+    }
+    fn has_outliers(&self) -> bool {
+        self.shell.outlier || self.mainboard.outlier
+    }
+}
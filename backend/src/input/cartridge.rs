@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{ops::Index, str};
 use time::Date;
@@ -14,9 +15,13 @@ use crate::{
     ParseError,
 };
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Cartridge {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_version: u32,
     pub code: String,
     pub slug: String,
     pub contributor: String,
@@ -27,7 +32,7 @@ pub struct Cartridge {
     pub dump: Option<CartridgeDump>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CartridgeShell {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -39,7 +44,7 @@ pub struct CartridgeShell {
     pub outlier: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CartridgeBoard {
     pub label: String,
@@ -67,6 +72,8 @@ pub struct CartridgeBoard {
     pub u7: Option<Chip>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x1: Option<Chip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bt1: Option<Chip>,
     #[serde(default)]
     #[serde(skip_serializing_if = "is_not_outlier")]
     pub outlier: bool,
@@ -85,15 +92,17 @@ impl Index<PartDesignator> for CartridgeBoard {
             PartDesignator::U6 => &self.u6,
             PartDesignator::U7 => &self.u7,
             PartDesignator::X1 => &self.x1,
+            PartDesignator::Bt1 => &self.bt1,
         }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CartridgeDump {
     pub tool: String,
     #[serde(with = "date_format")]
+    #[schemars(schema_with = "date_format::schema")]
     pub date: Date,
     pub sha256: Sha256,
 }
@@ -101,6 +110,10 @@ pub struct CartridgeDump {
 impl std::error::Error for ParseError {}
 
 mod date_format {
+    use schemars::{
+        gen::SchemaGenerator,
+        schema::{InstanceType, Schema, SchemaObject},
+    };
     use serde::{de::Visitor, Deserializer, Serializer};
     use time::{format_description::FormatItem, macros::format_description, Date};
 
@@ -115,6 +128,14 @@ mod date_format {
             .map_err(serde::ser::Error::custom)?;
         serializer.serialize_str(&string)
     }
+    pub fn schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("date".to_owned()),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Date, D::Error>
     where
         D: Deserializer<'de>,
@@ -144,6 +165,8 @@ mod date_format {
 fn test_deserialize() {
     let cart: Cartridge = serde_json::from_str(
         r#"{
+            "$schema": "../../../../schemas/cartridge.schema.json",
+            "schema_version": 1,
             "code": "DMG-ASDF-0",
             "slug": "dude-1",
             "contributor": "dude",
@@ -179,6 +202,9 @@ fn test_deserialize() {
                 "x1": {
                     "label": "KDS"
                 },
+                "bt1": {
+                    "label": "CR2025 MAXELL"
+                },
                 "outlier": true
             },
             "dump": {
@@ -192,6 +218,8 @@ fn test_deserialize() {
     assert_eq!(
         cart,
         Cartridge {
+            schema: Some("../../../../schemas/cartridge.schema.json".to_owned()),
+            schema_version: 1,
             code: "DMG-ASDF-0".to_owned(),
             slug: "dude-1".to_owned(),
             contributor: "dude".to_owned(),
@@ -236,6 +264,10 @@ fn test_deserialize() {
                     label: Some("KDS".to_owned()),
                     outlier: false,
                 }),
+                bt1: Some(Chip {
+                    label: Some("CR2025 MAXELL".to_owned()),
+                    outlier: false,
+                }),
                 outlier: true
             },
             dump: Some(CartridgeDump {
@@ -254,6 +286,7 @@ fn test_deserialize() {
 fn test_deserialize_minimal() {
     let cart: Cartridge = serde_json::from_str(
         r#"{
+            "schema_version": 1,
             "code": "DMG-ASDF-0",
             "slug": "dude-1",
             "contributor": "dude",
@@ -268,6 +301,8 @@ fn test_deserialize_minimal() {
     assert_eq!(
         cart,
         Cartridge {
+            schema: None,
+            schema_version: 1,
             code: "DMG-ASDF-0".to_owned(),
             slug: "dude-1".to_owned(),
             contributor: "dude".to_owned(),
@@ -291,6 +326,7 @@ fn test_deserialize_minimal() {
                 u6: None,
                 u7: None,
                 x1: None,
+                bt1: None,
                 outlier: false
             },
             dump: None,
@@ -2,16 +2,27 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::ops::Index;
 
 use crate::{
-    input::{is_not_outlier, Chip},
+    config::console::ConsoleDesignator,
+    input::{
+        console::{ConsoleShell, ConsoleSubmission},
+        is_not_outlier, Chip,
+    },
     time::{Jun, Month},
+    Console,
 };
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CgbConsole {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_version: u32,
     pub slug: String,
     pub contributor: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -20,7 +31,7 @@ pub struct CgbConsole {
     pub mainboard: CgbMainboard,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CgbShell {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,7 +45,7 @@ pub struct CgbShell {
     pub outlier: bool,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub enum CgbShellColor {
     Grape,
     Teal,
@@ -45,7 +56,7 @@ pub enum CgbShellColor {
     NeotonesIce,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CgbMainboard {
     pub label: String,
@@ -75,3 +86,54 @@ pub struct CgbMainboard {
     #[serde(skip_serializing_if = "is_not_outlier")]
     pub outlier: bool,
 }
+
+impl Index<ConsoleDesignator> for CgbMainboard {
+    type Output = Option<Chip>;
+
+    fn index(&self, index: ConsoleDesignator) -> &Self::Output {
+        match index {
+            ConsoleDesignator::U1 => &self.u1,
+            ConsoleDesignator::U2 => &self.u2,
+            ConsoleDesignator::U3 => &self.u3,
+            ConsoleDesignator::U4 => &self.u4,
+            ConsoleDesignator::X1 => &self.x1,
+            _ => &None,
+        }
+    }
+}
+
+impl ConsoleSubmission for CgbConsole {
+    const CONSOLE: Console = Console::Cgb;
+    type Mainboard = CgbMainboard;
+
+    fn slug(&self) -> &str {
+        &self.slug
+    }
+    fn contributor(&self) -> &str {
+        &self.contributor
+    }
+    fn index(&self) -> Option<u16> {
+        self.index
+    }
+    fn shell(&self) -> ConsoleShell<'_> {
+        ConsoleShell {
+            color: self.shell.color.map(|color| format!("{:?}", color)),
+            release_code: self.shell.release_code.as_deref(),
+            serial: self.shell.serial.as_deref(),
+            outlier: self.shell.outlier,
+            ..ConsoleShell::default()
+        }
+    }
+    fn mainboard(&self) -> &Self::Mainboard {
+        &self.mainboard
+    }
+    fn mainboard_label(&self) -> &str {
+        &self.mainboard.label
+    }
+    fn year_hint(&self) -> Option<u16> {
+        self.mainboard.year.or(Some(1998))
+    }
+    fn has_outliers(&self) -> bool {
+        self.shell.outlier || self.mainboard.outlier
+    }
+}
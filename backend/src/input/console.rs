@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+use std::ops::Index;
+
+use crate::{
+    config::console::{console_board_chips, ConsoleChip, ConsoleDesignator},
+    input::Chip,
+    Console,
+};
+
+/// Set of photo files a console submission directory may contain
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ConsolePhotoSet {
+    Default,
+    Dmg,
+    Ags,
+}
+
+impl ConsolePhotoSet {
+    /// Returns the photo file names in display order
+    pub fn files(&self) -> &'static [&'static str] {
+        match self {
+            ConsolePhotoSet::Default => &[
+                "01_front.jpg",
+                "02_back.jpg",
+                "03_pcb_front.jpg",
+                "04_pcb_back.jpg",
+            ],
+            ConsolePhotoSet::Dmg => &[
+                "01_front.jpg",
+                "02_back.jpg",
+                "03_mainboard_front.jpg",
+                "04_mainboard_back.jpg",
+                "05_lcd_board_front.jpg",
+                "06_lcd_board_back.jpg",
+                "07_power_board_front.jpg",
+                "08_power_board_back.jpg",
+                "09_jack_board_front.jpg",
+                "10_jack_board_back.jpg",
+            ],
+            ConsolePhotoSet::Ags => &[
+                "01_front.jpg",
+                "02_top.jpg",
+                "03_back.jpg",
+                "04_pcb_front.jpg",
+                "05_pcb_back.jpg",
+            ],
+        }
+    }
+}
+
+/// Console shell information that is common to all consoles
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ConsoleShell<'a> {
+    pub color: Option<String>,
+    pub release_code: Option<&'a str>,
+    pub serial: Option<&'a str>,
+    pub stamp: Option<&'a str>,
+    pub outlier: bool,
+}
+
+/// A console submission (= contents of one `metadata.json` file in `data/consoles`)
+pub trait ConsoleSubmission:
+    DeserializeOwned + Serialize + JsonSchema + Send + Sync + 'static
+{
+    const CONSOLE: Console;
+    const PHOTOS: ConsolePhotoSet = ConsolePhotoSet::Default;
+    type Mainboard: Index<ConsoleDesignator, Output = Option<Chip>>;
+
+    fn slug(&self) -> &str;
+    fn contributor(&self) -> &str;
+    /// Contributor-specific index for submissions that don't have a serial number
+    fn index(&self) -> Option<u16>;
+    fn shell(&self) -> ConsoleShell<'_>;
+    fn mainboard(&self) -> &Self::Mainboard;
+    fn mainboard_label(&self) -> &str;
+    /// Year used to complete partial years in chip labels
+    fn year_hint(&self) -> Option<u16>;
+    /// Returns true if any part of the submission has been marked as an outlier
+    fn has_outliers(&self) -> bool;
+
+    fn serial(&self) -> Option<&str> {
+        self.shell().serial
+    }
+    fn title(&self) -> String {
+        match (self.serial(), self.index()) {
+            (Some(serial), _) => serial.to_owned(),
+            (None, Some(index)) => format!("Unit #{index}"),
+            (None, None) => self.slug().to_owned(),
+        }
+    }
+    /// Returns all populated mainboard chips
+    fn mainboard_chips(&self) -> Vec<(ConsoleChip, &Chip)> {
+        let mainboard = self.mainboard();
+        console_board_chips(Self::CONSOLE, self.mainboard_label())
+            .iter()
+            .filter_map(|&chip| Some((chip, mainboard[chip.designator].as_ref()?)))
+            .collect()
+    }
+}
+
+#[test]
+fn test_console_submission() {
+    use crate::{
+        config::console::ConsoleChipRole,
+        input::{dmg::DmgConsole, gbs::GbsConsole},
+    };
+
+    let console: DmgConsole = serde_json::from_str(
+        r#"{
+            "schema_version": 1,
+            "slug": "G10000000",
+            "contributor": "test",
+            "shell": { "serial": "G10000000" },
+            "mainboard": { "label": "DMG-CPU-06", "u1": { "label": "DMG-CPU C" } }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(console.title(), "G10000000");
+    assert!(!console.has_outliers());
+    let chips = console.mainboard_chips();
+    assert_eq!(chips.len(), 1);
+    assert_eq!(chips[0].0.designator, ConsoleDesignator::U1);
+    assert_eq!(chips[0].0.role, ConsoleChipRole::Cpu);
+    assert_eq!(console.year_hint(), Some(1996));
+
+    let console: GbsConsole = serde_json::from_str(
+        r#"{
+            "schema_version": 1,
+            "slug": "test1",
+            "contributor": "test",
+            "index": 1,
+            "shell": {},
+            "mainboard": { "label": "DOL-GBS-01" }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(console.title(), "Unit #1");
+    assert_eq!(console.year_hint(), Some(2003));
+    assert_eq!(GbsConsole::PHOTOS.files().len(), 4);
+}
@@ -2,16 +2,28 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::ops::Index;
 
 use crate::{
-    input::{is_not_outlier, Chip, LcdScreen},
+    config::console::ConsoleDesignator,
+    input::{
+        console::{ConsolePhotoSet, ConsoleShell, ConsoleSubmission},
+        is_not_outlier, Chip, LcdScreen,
+    },
+    parser::{gen1_soc::gen1_soc, LabelParser},
     time::Month,
+    Console,
 };
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DmgConsole {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_version: u32,
     pub slug: String,
     pub contributor: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -26,7 +38,7 @@ pub struct DmgConsole {
     pub jack_board: Option<DmgJackBoard>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DmgShell {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -38,7 +50,7 @@ pub struct DmgShell {
     pub outlier: bool,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub enum DmgShellColor {
     OffWhite,
     DeepBlack,
@@ -50,7 +62,7 @@ pub enum DmgShellColor {
     CoolBlue,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DmgMainboard {
     pub label: String,
@@ -75,7 +87,72 @@ pub struct DmgMainboard {
     pub outlier: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+impl Index<ConsoleDesignator> for DmgMainboard {
+    type Output = Option<Chip>;
+
+    fn index(&self, index: ConsoleDesignator) -> &Self::Output {
+        match index {
+            ConsoleDesignator::U1 => &self.u1,
+            ConsoleDesignator::U2 => &self.u2,
+            ConsoleDesignator::U3 => &self.u3,
+            ConsoleDesignator::U4 => &self.u4,
+            ConsoleDesignator::X1 => &self.x1,
+            _ => &None,
+        }
+    }
+}
+
+impl ConsoleSubmission for DmgConsole {
+    const CONSOLE: Console = Console::Dmg;
+    const PHOTOS: ConsolePhotoSet = ConsolePhotoSet::Dmg;
+    type Mainboard = DmgMainboard;
+
+    fn slug(&self) -> &str {
+        &self.slug
+    }
+    fn contributor(&self) -> &str {
+        &self.contributor
+    }
+    fn index(&self) -> Option<u16> {
+        self.index
+    }
+    fn shell(&self) -> ConsoleShell<'_> {
+        ConsoleShell {
+            color: self.shell.color.map(|color| format!("{:?}", color)),
+            serial: self.shell.serial.as_deref(),
+            outlier: self.shell.outlier,
+            ..ConsoleShell::default()
+        }
+    }
+    fn mainboard(&self) -> &Self::Mainboard {
+        &self.mainboard
+    }
+    fn mainboard_label(&self) -> &str {
+        &self.mainboard.label
+    }
+    /// Uses the CPU date code, because DMG mainboards don't have a date
+    fn year_hint(&self) -> Option<u16> {
+        let cpu_year = self
+            .mainboard
+            .u1
+            .as_ref()
+            .and_then(|chip| chip.label.as_deref())
+            .and_then(|label| gen1_soc().parse(label).ok())
+            .and_then(|cpu| cpu.year?.full_year(None));
+        Some(cpu_year.unwrap_or(1996))
+    }
+    fn has_outliers(&self) -> bool {
+        self.shell.outlier
+            || self.mainboard.outlier
+            || self.lcd_board.as_ref().is_some_and(|board| {
+                board.outlier || board.screen.as_ref().is_some_and(|screen| screen.outlier)
+            })
+            || self.power_board.as_ref().is_some_and(|board| board.outlier)
+            || self.jack_board.as_ref().is_some_and(|board| board.outlier)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DmgLcdBoard {
     pub label: String,
@@ -98,7 +175,7 @@ pub struct DmgLcdBoard {
     pub outlier: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DmgPowerBoard {
     pub kind: String,
@@ -111,7 +188,7 @@ pub struct DmgPowerBoard {
     pub outlier: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct DmgJackBoard {
     pub kind: String,
@@ -2,16 +2,27 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::ops::Index;
 
 use crate::{
-    input::{is_not_outlier, Chip},
+    config::console::ConsoleDesignator,
+    input::{
+        console::{ConsoleShell, ConsoleSubmission},
+        is_not_outlier, Chip,
+    },
     time::Month,
+    Console,
 };
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GbsConsole {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_version: u32,
     pub slug: String,
     pub contributor: String,
     pub index: u16,
@@ -19,7 +30,7 @@ pub struct GbsConsole {
     pub mainboard: GbsMainboard,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GbsShell {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -31,7 +42,7 @@ pub struct GbsShell {
     pub outlier: bool,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub enum GbsShellColor {
     Indigo,
     Black,
@@ -39,7 +50,7 @@ pub enum GbsShellColor {
     Platinum,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GbsMainboard {
     pub label: String,
@@ -73,3 +84,54 @@ pub struct GbsMainboard {
     #[serde(skip_serializing_if = "is_not_outlier")]
     pub outlier: bool,
 }
+
+impl Index<ConsoleDesignator> for GbsMainboard {
+    type Output = Option<Chip>;
+
+    fn index(&self, index: ConsoleDesignator) -> &Self::Output {
+        match index {
+            ConsoleDesignator::U2 => &self.u2,
+            ConsoleDesignator::U3 => &self.u3,
+            ConsoleDesignator::U4 => &self.u4,
+            ConsoleDesignator::U5 => &self.u5,
+            ConsoleDesignator::U6 => &self.u6,
+            ConsoleDesignator::Y1 => &self.y1,
+            _ => &None,
+        }
+    }
+}
+
+impl ConsoleSubmission for GbsConsole {
+    const CONSOLE: Console = Console::Gbs;
+    type Mainboard = GbsMainboard;
+
+    fn slug(&self) -> &str {
+        &self.slug
+    }
+    fn contributor(&self) -> &str {
+        &self.contributor
+    }
+    fn index(&self) -> Option<u16> {
+        Some(self.index)
+    }
+    fn shell(&self) -> ConsoleShell<'_> {
+        ConsoleShell {
+            color: self.shell.color.map(|color| format!("{:?}", color)),
+            release_code: self.shell.release_code.as_deref(),
+            outlier: self.shell.outlier,
+            ..ConsoleShell::default()
+        }
+    }
+    fn mainboard(&self) -> &Self::Mainboard {
+        &self.mainboard
+    }
+    fn mainboard_label(&self) -> &str {
+        &self.mainboard.label
+    }
+    fn year_hint(&self) -> Option<u16> {
+        self.mainboard.year.or(Some(2003))
+    }
+    fn has_outliers(&self) -> bool {
+        self.shell.outlier || self.mainboard.outlier
+    }
+}
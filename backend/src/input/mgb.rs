@@ -2,16 +2,27 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::ops::Index;
 
 use crate::{
-    input::{is_not_outlier, Chip, LcdScreen},
+    config::console::ConsoleDesignator,
+    input::{
+        console::{ConsoleShell, ConsoleSubmission},
+        is_not_outlier, Chip, LcdScreen,
+    },
     time::{Jun, Month},
+    Console,
 };
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct MgbConsole {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub schema_version: u32,
     pub slug: String,
     pub contributor: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -21,7 +32,7 @@ pub struct MgbConsole {
     pub screen: LcdScreen,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct MgbShell {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -35,7 +46,7 @@ pub struct MgbShell {
     pub outlier: bool,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub enum MgbShellColor {
     Silver,
     Black,
@@ -49,7 +60,7 @@ pub enum MgbShellColor {
     OffWhite,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct MgbMainboard {
     pub label: String,
@@ -79,3 +90,54 @@ pub struct MgbMainboard {
     #[serde(skip_serializing_if = "is_not_outlier")]
     pub outlier: bool,
 }
+
+impl Index<ConsoleDesignator> for MgbMainboard {
+    type Output = Option<Chip>;
+
+    fn index(&self, index: ConsoleDesignator) -> &Self::Output {
+        match index {
+            ConsoleDesignator::U1 => &self.u1,
+            ConsoleDesignator::U2 => &self.u2,
+            ConsoleDesignator::U3 => &self.u3,
+            ConsoleDesignator::U4 => &self.u4,
+            ConsoleDesignator::X1 => &self.x1,
+            _ => &None,
+        }
+    }
+}
+
+impl ConsoleSubmission for MgbConsole {
+    const CONSOLE: Console = Console::Mgb;
+    type Mainboard = MgbMainboard;
+
+    fn slug(&self) -> &str {
+        &self.slug
+    }
+    fn contributor(&self) -> &str {
+        &self.contributor
+    }
+    fn index(&self) -> Option<u16> {
+        self.index
+    }
+    fn shell(&self) -> ConsoleShell<'_> {
+        ConsoleShell {
+            color: self.shell.color.map(|color| format!("{:?}", color)),
+            release_code: self.shell.release_code.as_deref(),
+            serial: self.shell.serial.as_deref(),
+            outlier: self.shell.outlier,
+            ..ConsoleShell::default()
+        }
+    }
+    fn mainboard(&self) -> &Self::Mainboard {
+        &self.mainboard
+    }
+    fn mainboard_label(&self) -> &str {
+        &self.mainboard.label
+    }
+    fn year_hint(&self) -> Option<u16> {
+        self.mainboard.year
+    }
+    fn has_outliers(&self) -> bool {
+        self.shell.outlier || self.mainboard.outlier || self.screen.outlier
+    }
+}
@@ -4,13 +4,19 @@
 
 use std::fmt;
 
+pub mod analysis;
 pub mod config;
+pub mod db;
+pub mod format;
 pub mod input;
+pub mod lint;
+pub mod migration;
 pub mod parser;
+pub mod schema;
 pub mod sha256;
 pub mod time;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Console {
     Dmg,
     Sgb,
@@ -0,0 +1,765 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Validation of all submission data without building the site.
+//!
+//! Every `metadata.json` file and `config/games.json` are checked with the same parsers and
+//! consistency rules that the site build uses, but all problems are collected instead of stopping
+//! at the first one. The layout of the data directory, photo files and submission naming are also
+//! checked.
+
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    analysis::repro,
+    config::{
+        cartridge::{
+            game_id, load_cfgs, BoardLayout, ChipRole, ChipRoleConfig, GameConfig, PartDesignator,
+            UnlicensedCode,
+        },
+        console::{console_board_chips, default_console_board_chips, ChipParser},
+        mainboard::mainboard_revision,
+    },
+    db::{
+        chip::{parse_cartridge_chip, parse_console_chip, ChipData},
+        photos, submission_dirs, Database, Loaded, Submission, CARTRIDGE_PHOTOS,
+    },
+    input::{
+        agb::AgbConsole, ags::AgsConsole, cartridge::Cartridge, cgb::CgbConsole,
+        console::ConsoleSubmission, dmg::DmgConsole, gbs::GbsConsole, mgb::MgbConsole,
+        mgl::MglConsole, oxy::OxyConsole, sgb::SgbConsole, sgb2::Sgb2Console,
+    },
+    migration::{read_metadata, MetadataKind},
+    parser::{self, LabelParser},
+    Console,
+};
+
+mod structure;
+
+/// Photo files that every submission should have
+const REQUIRED_PHOTOS: &[&str] = &["01_front.jpg"];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+    /// The file can't be used at all, so the site build skips it
+    Fatal,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Severity::Warning, Severity::Error, Severity::Fatal]
+            .into_iter()
+            .find(|severity| severity.name() == s)
+            .ok_or_else(|| format!("Unknown severity {s}"))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Problem {
+    pub severity: Severity,
+    /// File path relative to the root directory
+    pub path: PathBuf,
+    /// JSON pointer to the value in the file, or an empty string for the whole file
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if !self.pointer.is_empty() {
+            write!(f, "#{}", self.pointer)?;
+        }
+        write!(f, ": {}: {}", self.severity, self.message)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Report {
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == severity)
+            .count()
+    }
+    /// Returns true if any problem is at least as severe as the threshold
+    pub fn exceeds(&self, threshold: Severity) -> bool {
+        self.problems
+            .iter()
+            .any(|problem| problem.severity >= threshold)
+    }
+    pub fn has_errors(&self) -> bool {
+        self.exceeds(Severity::Error)
+    }
+    /// Returns the files that have fatal problems
+    pub fn fatal_files(&self) -> HashSet<&Path> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == Severity::Fatal)
+            .map(|problem| problem.path.as_path())
+            .collect()
+    }
+    /// Groups the problems by file, keeping their original order within each file
+    pub fn by_file(&self) -> BTreeMap<&Path, Vec<&Problem>> {
+        let mut files: BTreeMap<&Path, Vec<&Problem>> = BTreeMap::new();
+        for problem in &self.problems {
+            files.entry(&problem.path).or_default().push(problem);
+        }
+        files
+    }
+}
+
+/// Checks all data in the given root directory
+pub fn lint(root: &Path) -> Report {
+    lint_and_load(root).0
+}
+
+/// Checks all data in the given root directory, and returns the database of the submissions
+/// that have no fatal problems.
+///
+/// The database is built from the data that was parsed for checking, so the files are only read
+/// once.
+pub fn lint_and_load(root: &Path) -> (Report, Database) {
+    let mut linter = Linter {
+        root,
+        report: Report::default(),
+        names: Vec::new(),
+        submissions: Vec::new(),
+    };
+    linter.lint_structure();
+    let cfgs = linter.lint_cfgs();
+    linter.lint_cartridges(cfgs.as_ref());
+    linter.lint_consoles::<DmgConsole>();
+    linter.lint_consoles::<SgbConsole>();
+    linter.lint_consoles::<MgbConsole>();
+    linter.lint_consoles::<MglConsole>();
+    linter.lint_consoles::<Sgb2Console>();
+    linter.lint_consoles::<CgbConsole>();
+    linter.lint_consoles::<AgbConsole>();
+    linter.lint_consoles::<AgsConsole>();
+    linter.lint_consoles::<GbsConsole>();
+    linter.lint_consoles::<OxyConsole>();
+    linter.lint_names();
+
+    let skipped = linter
+        .report
+        .fatal_files()
+        .into_iter()
+        .filter_map(|path| path.parent())
+        .map(Path::to_path_buf)
+        .collect::<HashSet<_>>();
+    let mut submissions = Vec::new();
+    for (dir, submission) in std::mem::take(&mut linter.submissions) {
+        if skipped.contains(dir.strip_prefix(root).unwrap_or(&dir)) {
+            continue;
+        }
+        match submission {
+            Ok(submission) => submissions.push(submission),
+            Err(err) => linter
+                .file(&dir.join("metadata.json"))
+                .fatal("", format!("{err:#}")),
+        }
+    }
+    let db = Database::new(cfgs.unwrap_or_default(), submissions);
+    (linter.report, db)
+}
+
+/// Escapes a key for use as a JSON pointer segment
+fn pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+struct Linter<'a> {
+    root: &'a Path,
+    report: Report,
+    names: Vec<structure::SubmissionName>,
+    /// Parsed submissions and their directories
+    submissions: Vec<(PathBuf, Result<Submission, Error>)>,
+}
+
+impl<'a> Linter<'a> {
+    fn file(&mut self, path: &Path) -> FileLinter<'_> {
+        let path = path.strip_prefix(self.root).unwrap_or(path).to_owned();
+        FileLinter {
+            path,
+            problems: &mut self.report.problems,
+        }
+    }
+    /// Lists submission directories, or reports the directory itself if it can't be read.
+    ///
+    /// A missing directory has no submissions.
+    fn submission_dirs(&mut self, dir: &Path) -> Vec<PathBuf> {
+        if !dir.exists() {
+            return Vec::new();
+        }
+        submission_dirs(dir).unwrap_or_else(|err| {
+            self.file(dir).fatal("", format!("{err:#}"));
+            Vec::new()
+        })
+    }
+    /// Reads, migrates and deserializes a `metadata.json` file
+    fn load<T: for<'de> Deserialize<'de>>(
+        &mut self,
+        kind: MetadataKind,
+        dir: &Path,
+    ) -> Option<(T, Value)> {
+        let path = dir.join("metadata.json");
+        let mut file = self.file(&path);
+        let (data, value) = read_metadata(kind, &path)
+            .map_err(|err| file.fatal("", format!("{err:#}")))
+            .ok()?;
+        let slug = value.get("slug").and_then(Value::as_str);
+        if dir.file_name().and_then(|name| name.to_str()) != slug {
+            file.fatal("/slug", "slug doesn't match the directory");
+        }
+        Some((data, value))
+    }
+    fn lint_cfgs(&mut self) -> Option<BTreeMap<String, GameConfig>> {
+        let path = self.root.join("config/games.json");
+        let mut file = self.file(&path);
+        let cfgs = load_cfgs(&path)
+            .map_err(|err| file.fatal("", format!("{err:#}")))
+            .ok()?;
+        for (code, cfg) in &cfgs {
+            let pointer = format!("/{}", pointer_segment(code));
+            if cfg.is_unlicensed() {
+                if let Err(err) = UnlicensedCode::parse(code) {
+                    file.error(&pointer, format!("invalid unlicensed code {code}: {err}"));
+                }
+            }
+            if cfg.layouts.is_empty() {
+                file.error(&format!("{pointer}/layouts"), "no board layouts");
+            }
+        }
+        Some(cfgs)
+    }
+    fn lint_cartridges(&mut self, cfgs: Option<&BTreeMap<String, GameConfig>>) {
+        let games_dir = self.root.join("data/cartridges");
+        let entries = match fs::read_dir(&games_dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.file(&games_dir).fatal("", err.to_string());
+                return;
+            }
+        };
+        let mut game_dirs = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        game_dirs.sort();
+        for game_dir in game_dirs {
+            let mut cartridges = Vec::new();
+            for dir in self.submission_dirs(&game_dir) {
+                self.lint_submission_files(&dir, CARTRIDGE_PHOTOS, REQUIRED_PHOTOS);
+                let cartridge = match self.load::<Cartridge>(MetadataKind::Cartridge, &dir) {
+                    Some((cartridge, _)) => cartridge,
+                    None => continue,
+                };
+                self.lint_name(
+                    &dir,
+                    &cartridge.slug,
+                    &cartridge.contributor,
+                    Some(cartridge.index),
+                    None,
+                );
+                cartridges.push((dir, cartridge));
+            }
+            // Without game configs, all submissions would be reported as unknown games
+            if let Some(cfgs) = cfgs {
+                for (dir, cartridge) in &cartridges {
+                    let release_year = cfgs.get(&cartridge.code).and_then(|cfg| {
+                        let same_game = cartridges
+                            .iter()
+                            .map(|(_, other)| other)
+                            .filter(|other| other.code == cartridge.code);
+                        repro::release_year(same_game, cfg)
+                    });
+                    let mut file = self.file(&dir.join("metadata.json"));
+                    file.lint_cartridge(cfgs, cartridge, release_year);
+                }
+                for (dir, cartridge) in cartridges {
+                    let loaded = Loaded {
+                        data: cartridge,
+                        photos: photos(&dir, CARTRIDGE_PHOTOS),
+                        dir: dir.clone(),
+                    };
+                    let submission = Submission::from_cartridge(cfgs, loaded);
+                    self.submissions.push((dir, submission));
+                }
+            }
+        }
+    }
+    fn lint_consoles<C: ConsoleSubmission>(&mut self) {
+        let console_dir = self.root.join("data/consoles").join(C::CONSOLE.code());
+        for dir in self.submission_dirs(&console_dir) {
+            self.lint_submission_files(&dir, C::PHOTOS.files(), REQUIRED_PHOTOS);
+            let (console, value) = match self.load::<C>(MetadataKind::Console(C::CONSOLE), &dir) {
+                Some(loaded) => loaded,
+                None => continue,
+            };
+            self.lint_name(
+                &dir,
+                console.slug(),
+                console.contributor(),
+                console.index(),
+                console.serial(),
+            );
+            let mut file = self.file(&dir.join("metadata.json"));
+            file.lint_console(&console, &value);
+            let loaded = Loaded {
+                data: console,
+                photos: photos(&dir, C::PHOTOS.files()),
+                dir: dir.clone(),
+            };
+            self.submissions
+                .push((dir, Submission::from_console(loaded)));
+        }
+    }
+}
+
+struct FileLinter<'a> {
+    path: PathBuf,
+    problems: &'a mut Vec<Problem>,
+}
+
+impl<'a> FileLinter<'a> {
+    fn report(&mut self, severity: Severity, pointer: &str, message: impl Into<String>) {
+        self.problems.push(Problem {
+            severity,
+            path: self.path.clone(),
+            pointer: pointer.to_owned(),
+            message: message.into(),
+        });
+    }
+    fn error(&mut self, pointer: &str, message: impl Into<String>) {
+        self.report(Severity::Error, pointer, message);
+    }
+    fn warning(&mut self, pointer: &str, message: impl Into<String>) {
+        self.report(Severity::Warning, pointer, message);
+    }
+    fn fatal(&mut self, pointer: &str, message: impl Into<String>) {
+        self.report(Severity::Fatal, pointer, message);
+    }
+    /// Reports a chip label that can't be parsed or has an impossible date
+    fn check_chip(&mut self, pointer: &str, result: Result<ChipData, String>) -> Option<ChipData> {
+        match result {
+            Ok(data) => {
+                if let Some(year) = data.year.filter(|year| !(1988..2010).contains(year)) {
+                    self.error(pointer, format!("chip year {year} is out of range"));
+                }
+                Some(data)
+            }
+            Err(err) => {
+                self.error(pointer, err);
+                None
+            }
+        }
+    }
+    fn parse<T>(&mut self, pointer: &str, parser: &impl LabelParser<T>, label: &str) {
+        if let Err(err) = parser.parse(label) {
+            self.error(pointer, format!("failed to parse {label}: {err}"));
+        }
+    }
+    fn lint_cartridge(
+        &mut self,
+        cfgs: &BTreeMap<String, GameConfig>,
+        cartridge: &Cartridge,
+        release_year: Option<u16>,
+    ) {
+        let code = &cartridge.code;
+        let cfg = match cfgs.get(code) {
+            Some(cfg) => cfg,
+            None => {
+                self.fatal("/code", format!("unknown game code {code}"));
+                return;
+            }
+        };
+        if cfg.is_unlicensed() {
+            if let Err(err) = UnlicensedCode::parse(code) {
+                self.error("/code", format!("invalid unlicensed code {code}: {err}"));
+            }
+        }
+
+        let board = &cartridge.board;
+        if let Some(year) = board.year {
+            if !(1989..2010).contains(&year) {
+                self.error("/board/year", format!("year {year} is out of range"));
+            }
+        }
+        match BoardLayout::resolve(&board.label, cfg) {
+            None => self.fatal(
+                "/board/label",
+                format!("unknown board layout for {}", board.label),
+            ),
+            Some(layout) if !cfg.layouts.contains(&layout) => self.error(
+                "/board/label",
+                format!("board layout {layout:?} is not used by {code}"),
+            ),
+            Some(layout) => {
+                let roles = ChipRoleConfig::from(layout);
+                for designator in PartDesignator::ALL {
+                    let pointer = format!("/board/{}", designator.as_str().to_lowercase());
+                    match (&board[designator], roles[designator]) {
+                        (None, _) => (),
+                        (Some(_), None) => self.error(
+                            &pointer,
+                            format!("board layout {layout:?} has no {}", designator.as_str()),
+                        ),
+                        // Unlicensed chips fall back to the plain label
+                        (Some(_), Some(_)) if layout.is_unlicensed() => (),
+                        (Some(chip), Some(role)) => {
+                            if let Some(label) = chip.label.as_deref() {
+                                self.check_chip(
+                                    &format!("{pointer}/label"),
+                                    parse_cartridge_chip(layout, board.year, role, label),
+                                );
+                            }
+                        }
+                    }
+                }
+                // A mapper identified from its label should match the mapper in the game config
+                let mapper_chip = roles.iter().find(|&(_, role)| role == ChipRole::Mapper);
+                if let (Some(expected), Some((designator, _))) = (cfg.mapper, mapper_chip) {
+                    let mapper = board[designator]
+                        .as_ref()
+                        .and_then(|chip| chip.label.as_deref())
+                        .and_then(|label| parser::mapper::mapper().parse(label).ok())
+                        .map(|mapper| mapper.mbc_type);
+                    if let Some(mapper) = mapper.filter(|&mapper| mapper != expected) {
+                        self.warning(
+                            &format!("/board/{}/label", designator.as_str().to_lowercase()),
+                            format!(
+                                "mapper {} doesn't match the mapper {} of {code}",
+                                mapper.display_name(),
+                                expected.display_name()
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(shell_code) = cartridge.shell.code.as_deref() {
+            match parser::cartridge_shell::shell_code().parse(shell_code) {
+                Err(err) => self.error(
+                    "/shell/code",
+                    format!("invalid shell code {shell_code}: {err}"),
+                ),
+                Ok(parsed) => {
+                    if !cfg.is_unlicensed() && !is_shell_of_game(&parsed.game_id, code) {
+                        self.warning(
+                            "/shell/code",
+                            format!("shell code {shell_code} doesn't match the game"),
+                        );
+                    }
+                }
+            }
+        }
+        if let Some(stamp) = cartridge.shell.stamp.as_deref() {
+            self.parse(
+                "/shell/stamp",
+                parser::cartridge_shell::shell_stamp(),
+                stamp,
+            );
+        }
+
+        if let Some(dump) = &cartridge.dump {
+            match cfg.sha256 {
+                None => self.warning("/dump/sha256", "game config has no SHA256"),
+                Some(sha256) if sha256 == dump.sha256 => (),
+                Some(sha256) => self.error(
+                    "/dump/sha256",
+                    format!("SHA256 doesn't match the game config ({sha256})"),
+                ),
+            }
+        }
+
+        let report = repro::analyze(cartridge, cfg, release_year);
+        if report.is_likely_repro() {
+            let findings = report
+                .findings
+                .iter()
+                .map(|finding| finding.to_string())
+                .collect::<Vec<_>>();
+            self.warning(
+                "",
+                format!("possible reproduction cartridge: {}", findings.join(", ")),
+            );
+        }
+    }
+    fn lint_console<C: ConsoleSubmission>(&mut self, console: &C, value: &Value) {
+        let slug = console.slug();
+        if let Some(serial) = console.serial().filter(|&serial| serial != slug) {
+            self.fatal(
+                "/shell/serial",
+                format!("serial {serial} doesn't match the slug {slug}"),
+            );
+        }
+
+        let year_hint = console.year_hint();
+        let mut kinds = Vec::new();
+        for (config, chip) in console.mainboard_chips() {
+            if let Some(label) = chip.label.as_deref() {
+                let pointer = chip_pointer(config.designator.as_str());
+                if let Some(data) = self.check_chip(
+                    &pointer,
+                    parse_console_chip(config.parser, year_hint, label),
+                ) {
+                    kinds.push((config.designator, data.kind));
+                }
+            }
+        }
+        let label = console.mainboard_label();
+        let config = console_board_chips(C::CONSOLE, label);
+        for chip in default_console_board_chips(C::CONSOLE) {
+            let designator = chip.designator;
+            if console.mainboard()[designator].is_some()
+                && !config.iter().any(|chip| chip.designator == designator)
+            {
+                self.warning(
+                    &chip_pointer(designator.as_str()),
+                    format!("mainboard {label} has no {}", designator.as_str()),
+                );
+            }
+        }
+        match mainboard_revision(C::CONSOLE, label) {
+            None => self.warning(
+                "/mainboard/label",
+                format!("unknown mainboard revision {label}"),
+            ),
+            Some(revision) => {
+                let deviations = revision.check(|designator| {
+                    kinds
+                        .iter()
+                        .find(|(d, _)| *d == designator)
+                        .and_then(|(_, kind)| kind.as_deref())
+                });
+                for deviation in deviations {
+                    self.warning(
+                        &chip_pointer(deviation.designator.as_str()),
+                        deviation.to_string(),
+                    );
+                }
+            }
+        }
+
+        self.lint_stamps(C::CONSOLE, value);
+        if let Some(label) = value
+            .pointer("/lcd_board/chip/label")
+            .and_then(Value::as_str)
+        {
+            self.check_chip(
+                "/lcd_board/chip/label",
+                parse_console_chip(ChipParser::DmgReg, year_hint, label),
+            );
+        }
+        for screen in ["/screen", "/lcd_board/screen"] {
+            let label = |field: &str| {
+                let pointer = format!("{screen}{field}/label");
+                let label = value.pointer(&pointer).and_then(Value::as_str)?;
+                Some((pointer, label))
+            };
+            if let Some((pointer, label)) = label("") {
+                self.parse(&pointer, parser::lcd_screen::lcd_screen(), label);
+            }
+            for driver in ["/column_driver", "/row_driver"] {
+                if let Some((pointer, label)) = label(driver) {
+                    self.parse(&pointer, parser::lcd_chip::lcd_chip(), label);
+                }
+            }
+        }
+    }
+    /// Checks board stamps of the consoles whose stamp formats are known
+    fn lint_stamps(&mut self, console: Console, value: &Value) {
+        for board in ["/mainboard", "/lcd_board"] {
+            let pointer = format!("{board}/stamp");
+            let stamp = match value.pointer(&pointer).and_then(Value::as_str) {
+                Some(stamp) => stamp,
+                None => continue,
+            };
+            let outlier = value
+                .pointer(&format!("{board}/outlier"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let is_dmg_stamp = match console {
+                Console::Dmg if outlier => continue,
+                Console::Dmg | Console::Mgb => true,
+                Console::Cgb => stamp.starts_with(['6', '7', '8', '9']),
+                Console::Mgl | Console::Agb | Console::Gbs => false,
+                _ => continue,
+            };
+            if is_dmg_stamp {
+                self.parse(&pointer, parser::dmg_stamp::dmg_stamp(), stamp);
+            } else {
+                self.parse(&pointer, parser::cgb_stamp::cgb_stamp(), stamp);
+            }
+        }
+    }
+}
+
+fn chip_pointer(designator: &str) -> String {
+    format!("/mainboard/{}/label", designator.to_lowercase())
+}
+
+/// Returns true if the game id of a shell code belongs to the game.
+///
+/// Shell codes of early games only have the first two letters of the game id, e.g. `ML` in
+/// `DMG-ML-USA` for `DMG-MLA-1`.
+fn is_shell_of_game(shell_game_id: &str, code: &str) -> bool {
+    match game_id(shell_game_id) {
+        Some(shell_id) => game_id(code).is_some_and(|id| id.starts_with(shell_id)),
+        None => true,
+    }
+}
+
+#[test]
+fn test_is_shell_of_game() {
+    let matches = |shell_code, code| {
+        let shell_code = parser::cartridge_shell::shell_code().parse(shell_code);
+        is_shell_of_game(&shell_code.unwrap().game_id, code)
+    };
+    assert!(matches("DMG-ML-USA", "DMG-MLA-1"));
+    assert!(matches("DMG-AYWJ-JPN", "DMG-AYWJ-JPN"));
+    assert!(matches("DIS-CGB-AW8A-USA", "CGB-AW8E-USA"));
+    assert!(!matches("DMG-BB-EUR", "DMG-MLA-1"));
+    assert!(!matches("DMG-AYWJ-JPN", "DMG-AYXJ-JPN"));
+}
+
+/// Creates a root directory with the given files and empty `data/cartridges` and `data/consoles`
+/// directories, and calls `f` with it. The directory is removed afterwards, even if `f` panics
+#[cfg(test)]
+fn with_fixture(files: &[(&str, &str)], f: impl FnOnce(&Path)) {
+    use std::{
+        panic,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let root = std::env::temp_dir().join(format!(
+        "gbhwdb-lint-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(root.join("data/cartridges")).unwrap();
+    fs::create_dir_all(root.join("data/consoles")).unwrap();
+    for (path, text) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| f(&root)));
+    fs::remove_dir_all(&root).unwrap();
+    if let Err(err) = result {
+        panic::resume_unwind(err);
+    }
+}
+
+#[test]
+fn test_lint() {
+    let files = [
+        ("config/games.json", "{}"),
+        (
+            "data/consoles/DMG/G10000000/metadata.json",
+            r#"{"slug": "G10000000", "contributor": "test",
+                "shell": {"serial": "G10000001"},
+                "mainboard": {"label": "DMG-CPU-06", "u1": {"label": "DMG-CPU X"}}}"#,
+        ),
+        ("data/consoles/DMG/G10000000/03_mainboard_frnt.jpg", ""),
+        ("data/consoles/MGB/broken/metadata.json", "{"),
+        ("data/consoles/MGB/notes.txt", ""),
+    ];
+    with_fixture(&files, |root| {
+        let report = lint(root);
+        let problems = report
+            .problems
+            .iter()
+            .filter(|problem| problem.severity >= Severity::Error)
+            .map(|problem| (problem.path.to_str().unwrap(), problem.pointer.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            [
+                ("data/consoles/MGB/notes.txt", ""),
+                ("data/consoles/DMG/G10000000/03_mainboard_frnt.jpg", ""),
+                ("data/consoles/DMG/G10000000/metadata.json", "/shell/serial"),
+                (
+                    "data/consoles/DMG/G10000000/metadata.json",
+                    "/mainboard/u1/label"
+                ),
+                ("data/consoles/MGB/broken/metadata.json", ""),
+            ]
+        );
+        assert!(report.has_errors());
+        assert_eq!(report.fatal_files().len(), 2);
+    });
+}
+
+#[test]
+fn test_lint_unlicensed_mapper() {
+    let files = [
+        (
+            "config/games.json",
+            r#"{"UNL-WT-BIBLE-0": {"name": "Test", "rom_verified": false, "platform": "gb",
+                "layouts": ["unl_rom_mapper"], "mapper": "wisdom_tree"}}"#,
+        ),
+        (
+            "data/cartridges/UNL-WT-BIBLE-0/test-1/metadata.json",
+            r#"{"code": "UNL-WT-BIBLE-0", "slug": "test-1", "contributor": "test", "index": 1,
+                "shell": {}, "board": {"label": "WT-01", "u1": {"label": "BIBLE"},
+                "u2": {"label": "MBC5 LZ9GB31 9922 A"}}}"#,
+        ),
+    ];
+    with_fixture(&files, |root| {
+        let (report, db) = lint_and_load(root);
+        assert_eq!(db.submissions.len(), 1);
+        let cartridge = db.submissions[0].data::<Cartridge>().unwrap();
+        assert_eq!(cartridge.board.label, "WT-01");
+        let problems = report
+            .problems
+            .iter()
+            .filter(|problem| problem.pointer == "/board/u2/label")
+            .map(|problem| (problem.severity, problem.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            [(
+                Severity::Warning,
+                "mapper MBC5 doesn't match the mapper Wisdom Tree of UNL-WT-BIBLE-0"
+            )]
+        );
+    });
+}
@@ -0,0 +1,260 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Checks of the data directory layout, photo files, and submission naming rules.
+//!
+//! The loaders only look for `metadata.json` files at fixed depths, so everything else in the data
+//! directory is silently ignored by the site build.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use super::Linter;
+use crate::Console;
+
+/// Slug and index of a submission, for checks across submissions
+pub(super) struct SubmissionName {
+    path: PathBuf,
+    contributor: String,
+    index: u16,
+    prefix: String,
+}
+
+impl<'a> Linter<'a> {
+    /// Checks the photos and other files in a submission directory
+    pub(super) fn lint_submission_files(&mut self, dir: &Path, photos: &[&str], required: &[&str]) {
+        for (path, is_dir) in self.list_dir(dir) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if name == "metadata.json" {
+                continue;
+            }
+            let mut file = self.file(&path);
+            if is_dir {
+                file.error("", "unexpected directory in a submission");
+            } else if photos.contains(&name.as_ref()) {
+                if !is_jpeg(&path) {
+                    file.error("", "photo is not a JPEG file");
+                }
+            } else {
+                let closest = photos
+                    .iter()
+                    .min_by_key(|photo| edit_distance(photo, &name))
+                    .filter(|photo| edit_distance(photo, &name) <= 3);
+                match closest {
+                    Some(photo) => file.error("", format!("unknown file, did you mean {photo}?")),
+                    None => file.error(
+                        "",
+                        format!("unknown file, expected one of {}", photos.join(", ")),
+                    ),
+                }
+            }
+        }
+        for photo in required {
+            let path = dir.join(photo);
+            if !path.is_file() {
+                self.file(&path).warning("", "missing photo");
+            }
+        }
+    }
+    /// Checks that the slug is `<contributor>-<index>` unless the submission has a serial number
+    pub(super) fn lint_name(
+        &mut self,
+        dir: &Path,
+        slug: &str,
+        contributor: &str,
+        index: Option<u16>,
+        serial: Option<&str>,
+    ) {
+        let path = dir.join("metadata.json");
+        let mut file = self.file(&path);
+        if serial.is_some() {
+            // Serial numbers are checked against the slug when the file is loaded
+            return;
+        }
+        let index = match index {
+            Some(index) => index,
+            None => {
+                file.error(
+                    "/index",
+                    "submissions without a serial number need an index",
+                );
+                return;
+            }
+        };
+        let prefix = match slug.strip_suffix(&format!("-{index}")) {
+            Some(prefix) if is_slug_prefix(prefix) => prefix,
+            _ => {
+                file.error(
+                    "/slug",
+                    format!("slug {slug} should be <contributor>-{index} in lowercase"),
+                );
+                return;
+            }
+        };
+        self.names.push(SubmissionName {
+            path,
+            contributor: contributor.to_owned(),
+            index,
+            prefix: prefix.to_owned(),
+        });
+    }
+    /// Reports duplicate indices and contributors whose slugs use different prefixes
+    pub(super) fn lint_names(&mut self) {
+        let names = std::mem::take(&mut self.names);
+        let mut indices: BTreeMap<(&Path, &str, u16), Vec<&Path>> = BTreeMap::new();
+        let mut prefixes: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for name in &names {
+            let category = name.path.parent().and_then(Path::parent);
+            let key = (
+                category.unwrap_or(&name.path),
+                name.contributor.as_str(),
+                name.index,
+            );
+            indices.entry(key).or_default().push(&name.path);
+            prefixes
+                .entry(&name.contributor)
+                .or_default()
+                .insert(&name.prefix);
+        }
+        for ((_, contributor, index), paths) in indices {
+            if paths.len() > 1 {
+                for path in paths {
+                    self.file(path).error(
+                        "/index",
+                        format!("{contributor} has several submissions with index {index}"),
+                    );
+                }
+            }
+        }
+        for name in &names {
+            let prefixes = &prefixes[name.contributor.as_str()];
+            if prefixes.len() > 1 {
+                let prefixes = prefixes.iter().copied().collect::<Vec<_>>();
+                self.file(&name.path).warning(
+                    "/slug",
+                    format!(
+                        "{} uses different slug prefixes: {}",
+                        name.contributor,
+                        prefixes.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+    /// Reports files and directories that the loaders would ignore
+    pub(super) fn lint_structure(&mut self) {
+        let data_dir = self.root.join("data");
+        for (path, is_dir) in self.list_dir(&data_dir) {
+            let name = path.file_name().unwrap_or_default();
+            if name == "LICENSE" && !is_dir {
+                continue;
+            }
+            if name == "cartridges" && is_dir {
+                for (path, is_dir) in self.list_dir(&path) {
+                    if is_dir {
+                        self.lint_category_dir(&path);
+                    } else {
+                        self.file(&path)
+                            .error("", "unexpected file, expected a game directory");
+                    }
+                }
+            } else if name == "consoles" && is_dir {
+                for (path, is_dir) in self.list_dir(&path) {
+                    let name = path.file_name().unwrap_or_default();
+                    if is_dir && Console::ALL.iter().any(|console| name == console.code()) {
+                        self.lint_category_dir(&path);
+                    } else {
+                        self.file(&path)
+                            .error("", "unexpected file, expected a console directory");
+                    }
+                }
+            } else {
+                self.file(&path)
+                    .error("", "unexpected file in the data directory");
+            }
+        }
+    }
+    /// Checks a game or console directory, which should only contain submission directories
+    fn lint_category_dir(&mut self, dir: &Path) {
+        for (path, is_dir) in self.list_dir(dir) {
+            if !is_dir {
+                self.file(&path)
+                    .error("", "unexpected file, expected a submission directory");
+            } else if !path.join("metadata.json").is_file() {
+                self.file(&path)
+                    .error("", "submission directory has no metadata.json");
+            }
+        }
+    }
+    /// Lists a directory in sorted order, returning the paths and whether they're directories
+    fn list_dir(&mut self, dir: &Path) -> Vec<(PathBuf, bool)> {
+        let entries = fs::read_dir(dir).and_then(|entries| {
+            entries
+                .map(|entry| {
+                    let entry = entry?;
+                    Ok((entry.path(), entry.file_type()?.is_dir()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        });
+        match entries {
+            Ok(mut entries) => {
+                entries.sort();
+                entries
+            }
+            Err(err) => {
+                self.file(dir).error("", err.to_string());
+                Vec::new()
+            }
+        }
+    }
+}
+
+fn is_slug_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && !prefix.starts_with('-')
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Checks the JPEG start of image marker.
+///
+/// Photos are stored in Git LFS, so a pointer file is accepted if the photo hasn't been fetched.
+fn is_jpeg(path: &Path) -> bool {
+    const LFS_POINTER: &[u8] = b"version https://git-lfs.github.com/spec/";
+    let mut header = [0; LFS_POINTER.len()];
+    let len = File::open(path)
+        .and_then(|file| file.take(header.len() as u64).read(&mut header))
+        .unwrap_or(0);
+    header[..len].starts_with(&[0xff, 0xd8, 0xff]) || header[..len] == *LFS_POINTER
+}
+
+/// Returns the Levenshtein distance of two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != b);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("03_pcb_back.jpg", "03_pcb_bak.jpg"), 1);
+    assert_eq!(edit_distance("01_front.jpg", "01_front.jpeg"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert!(is_slug_prefix("red-vazquez"));
+    assert!(!is_slug_prefix("Red Vazquez"));
+}
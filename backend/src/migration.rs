@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Migrations of `metadata.json` files from older versions of the submission format.
+//!
+//! Every submission has a `schema_version`, and files without one are version 0. Files are
+//! migrated to the current version whenever they are loaded, so older files keep working until
+//! they are upgraded in place.
+
+use anyhow::{anyhow, bail, Context, Error};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    db::submission_dirs,
+    format::to_canonical_json,
+    input::{
+        agb::AgbConsole, ags::AgsConsole, cartridge::Cartridge, cgb::CgbConsole,
+        console::ConsoleSubmission, dmg::DmgConsole, gbs::GbsConsole, mgb::MgbConsole,
+        mgl::MglConsole, oxy::OxyConsole, sgb::SgbConsole, sgb2::Sgb2Console,
+    },
+    Console,
+};
+
+/// Current version of the submission format
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Type of submission stored in a `metadata.json` file
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MetadataKind {
+    Cartridge,
+    Console(Console),
+}
+
+/// Transformation of a submission from the previous version to `version`
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    migrate: fn(MetadataKind, &mut Map<String, Value>) -> Result<(), Error>,
+}
+
+/// All migrations in version order
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "add schema_version",
+    migrate: |_, _| Ok(()),
+}];
+
+/// Returns the `schema_version` of a submission
+pub fn schema_version(value: &Value) -> Result<u32, Error> {
+    match value.get("schema_version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("invalid schema_version {version}")),
+    }
+}
+
+/// Migrates a submission to the current version, and returns the migrations that were applied
+pub fn migrate(kind: MetadataKind, value: &mut Value) -> Result<Vec<&'static Migration>, Error> {
+    let version = schema_version(value)?;
+    if version > SCHEMA_VERSION {
+        bail!("schema_version {version} is newer than the supported version {SCHEMA_VERSION}");
+    }
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("expected a JSON object"))?;
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+        (migration.migrate)(kind, object)
+            .with_context(|| format!("Failed to migrate to version {}", migration.version))?;
+        object.insert("schema_version".to_owned(), migration.version.into());
+        applied.push(migration);
+    }
+    Ok(applied)
+}
+
+/// Reads and migrates a `metadata.json` file, and deserializes it.
+///
+/// Returns the migrated JSON value too.
+pub fn read_metadata<T: DeserializeOwned>(
+    kind: MetadataKind,
+    path: &Path,
+) -> Result<(T, Value), Error> {
+    let text = fs::read_to_string(path)?;
+    let mut value = serde_json::from_str(&text)?;
+    migrate(kind, &mut value)?;
+    let data = T::deserialize(&value)?;
+    Ok((data, value))
+}
+
+/// Upgrade of one `metadata.json` file to the current version
+#[derive(Clone, Debug)]
+pub struct Upgrade {
+    pub path: PathBuf,
+    pub from_version: u32,
+    pub migrations: Vec<&'static Migration>,
+    pub old: String,
+    pub new: String,
+}
+
+impl Upgrade {
+    /// Returns the number of added and removed lines
+    ///
+    /// ```
+    /// # use gbhwdb_backend::migration::Upgrade;
+    /// let upgrade = Upgrade {
+    ///     path: "metadata.json".into(),
+    ///     from_version: 0,
+    ///     migrations: Vec::new(),
+    ///     old: "{\n  \"a\": 1\n}\n".to_owned(),
+    ///     new: "{\n  \"a\": 2,\n  \"b\": 3\n}\n".to_owned(),
+    /// };
+    /// assert_eq!(upgrade.line_changes(), (2, 1));
+    /// ```
+    pub fn line_changes(&self) -> (usize, usize) {
+        let mut counts: HashMap<&str, isize> = HashMap::new();
+        for line in self.old.lines() {
+            *counts.entry(line).or_default() -= 1;
+        }
+        for line in self.new.lines() {
+            *counts.entry(line).or_default() += 1;
+        }
+        let added = counts.values().filter(|&&count| count > 0).sum::<isize>();
+        let removed = -counts.values().filter(|&&count| count < 0).sum::<isize>();
+        (added as usize, removed as usize)
+    }
+}
+
+/// Upgrades one file in memory, without writing it
+pub fn upgrade_file<T: DeserializeOwned + Serialize>(
+    kind: MetadataKind,
+    path: &Path,
+) -> Result<Upgrade, Error> {
+    let old = fs::read_to_string(path)?;
+    let mut value: Value = serde_json::from_str(&old)?;
+    let from_version = schema_version(&value)?;
+    let migrations = migrate(kind, &mut value)?;
+    let data = T::deserialize(&value)?;
+    let new = to_canonical_json(&data)?;
+    Ok(Upgrade {
+        path: path.to_owned(),
+        from_version,
+        migrations,
+        old,
+        new,
+    })
+}
+
+/// Upgrades every `metadata.json` file in the data directory of the given root directory.
+///
+/// Only files that need migrations are returned, and nothing is written.
+pub fn upgrade_all(root: &Path) -> Result<Vec<Upgrade>, Error> {
+    let mut upgrades = Vec::new();
+    let games_dir = root.join("data/cartridges");
+    let mut game_dirs = fs::read_dir(&games_dir)
+        .with_context(|| format!("Failed to read {}", games_dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>, Error>>()?;
+    game_dirs.sort();
+    for game_dir in game_dirs.iter().filter(|dir| dir.is_dir()) {
+        upgrade_dir::<Cartridge>(MetadataKind::Cartridge, game_dir, &mut upgrades)?;
+    }
+    upgrade_consoles::<DmgConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<SgbConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<MgbConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<MglConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<Sgb2Console>(root, &mut upgrades)?;
+    upgrade_consoles::<CgbConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<AgbConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<AgsConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<GbsConsole>(root, &mut upgrades)?;
+    upgrade_consoles::<OxyConsole>(root, &mut upgrades)?;
+    Ok(upgrades)
+}
+
+fn upgrade_consoles<C: ConsoleSubmission>(
+    root: &Path,
+    upgrades: &mut Vec<Upgrade>,
+) -> Result<(), Error> {
+    let dir = root.join("data/consoles").join(C::CONSOLE.code());
+    if dir.exists() {
+        upgrade_dir::<C>(MetadataKind::Console(C::CONSOLE), &dir, upgrades)?;
+    }
+    Ok(())
+}
+
+fn upgrade_dir<T: DeserializeOwned + Serialize>(
+    kind: MetadataKind,
+    dir: &Path,
+    upgrades: &mut Vec<Upgrade>,
+) -> Result<(), Error> {
+    for dir in submission_dirs(dir)? {
+        let path = dir.join("metadata.json");
+        let upgrade = upgrade_file::<T>(kind, &path)
+            .with_context(|| format!("Failed to upgrade {}", path.display()))?;
+        if !upgrade.migrations.is_empty() {
+            upgrades.push(upgrade);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_migrate() {
+    let mut value = serde_json::json!({"slug": "G10000000"});
+    let kind = MetadataKind::Console(Console::Dmg);
+    let applied = migrate(kind, &mut value).unwrap();
+    assert_eq!(applied.len(), MIGRATIONS.len());
+    assert_eq!(schema_version(&value).unwrap(), SCHEMA_VERSION);
+    assert!(migrate(kind, &mut value).unwrap().is_empty());
+
+    let mut value = serde_json::json!({ "schema_version": SCHEMA_VERSION + 1 });
+    assert!(migrate(kind, &mut value).is_err());
+}
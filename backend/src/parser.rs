@@ -6,7 +6,7 @@ use log::warn;
 use regex::{Captures, Regex, RegexBuilder, RegexSet, RegexSetBuilder};
 use std::str::FromStr;
 
-use crate::time::{Month, Week};
+use crate::time::{guess_full_year, Month, Week};
 
 pub use self::{
     accelerometer::Accelerometer,
@@ -15,6 +15,8 @@ pub use self::{
     agb_reg::AgbReg,
     ags_charge_ctrl::AgsChargeController,
     ags_pmic_old::AgsPmicOld,
+    battery::Battery,
+    cartridge_shell::{ShellCode, ShellStamp},
     cgb_reg::CgbReg,
     cgb_soc::CgbSoc,
     cgb_stamp::CgbStamp,
@@ -34,7 +36,10 @@ pub use self::{
     lcd_chip::LcdChip,
     lcd_screen::LcdScreen,
     line_decoder::LineDecoder,
-    mapper::{Huc1Version, Mapper, MapperType, Mbc1Version, Mbc2Version, Mbc3Version},
+    mapper::{
+        Huc1Version, Mapper, MapperFamily, MapperType, Mbc1Version, Mbc2Version, Mbc3Version,
+        SachenVersion,
+    },
     mask_rom::MaskRom,
     mgb_amp::MgbAmp,
     mgl_transformer::Transformer,
@@ -58,6 +63,8 @@ pub mod agb_soc_qfp_156;
 pub mod ags_charge_ctrl;
 pub mod ags_pmic_new;
 pub mod ags_pmic_old;
+pub mod battery;
+pub mod cartridge_shell;
 pub mod cgb_reg;
 pub mod cgb_soc;
 pub mod cgb_stamp;
@@ -164,6 +171,7 @@ pub enum Manufacturer {
     LsiLogic,
     Kinseki,
     Macronix,
+    Maxell,
     Mitsubishi,
     Mitsumi,
     MoselVitelic,
@@ -176,6 +184,7 @@ pub enum Manufacturer {
     Sanyo,
     Sharp,
     Smsc,
+    Sony,
     StMicro,
     Tdk,
     TexasInstruments,
@@ -201,6 +210,7 @@ impl Manufacturer {
             Manufacturer::Lgs => "Lucky GoldStar",
             Manufacturer::LsiLogic => "LSI Logic",
             Manufacturer::Macronix => "Macronix",
+            Manufacturer::Maxell => "Maxell",
             Manufacturer::Mitsubishi => "Mitsubishi",
             Manufacturer::Mitsumi => "Mitsumi",
             Manufacturer::MoselVitelic => "Mosel-Vitelic",
@@ -213,6 +223,7 @@ impl Manufacturer {
             Manufacturer::Sanyo => "Sanyo",
             Manufacturer::Sharp => "Sharp",
             Manufacturer::Smsc => "Standard Microsystems Corporation",
+            Manufacturer::Sony => "Sony",
             Manufacturer::StMicro => "STMicroelectronics",
             Manufacturer::Tdk => "TDK",
             Manufacturer::TexasInstruments => "Texas Instruments",
@@ -229,6 +240,17 @@ pub enum Year {
     Partial(u8),
 }
 
+impl Year {
+    /// Returns the full year, guessing the decade of a partial year from the given hint
+    pub fn full_year(&self, hint: Option<u16>) -> Option<u16> {
+        match (*self, hint) {
+            (Year::Full(year), _) => Some(year),
+            (Year::Partial(year), Some(hint)) => Some(guess_full_year(hint, year)),
+            (Year::Partial(_), None) => None,
+        }
+    }
+}
+
 pub fn year1(text: &str) -> Result<Year, String> {
     match u8::from_str(text) {
         Ok(value) => Ok(Year::Partial(value)),
@@ -0,0 +1,159 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use super::{year1, LabelParser, Manufacturer, Year};
+use crate::{
+    macros::{multi_parser, single_parser},
+    time::Month,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Battery {
+    pub kind: String,
+    pub manufacturer: Option<Manufacturer>,
+    pub year: Option<Year>,
+    pub month: Option<Month>,
+}
+
+/// Parses a 1-character month where October-December are encoded as X, Y, Z
+fn month1_xyz(text: &str) -> Result<Month, String> {
+    match text {
+        "X" => Ok(Month::October),
+        "Y" => Ok(Month::November),
+        "Z" => Ok(Month::December),
+        _ => text
+            .parse::<u8>()
+            .ok()
+            .and_then(|v| Month::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid 1-character month: {}", text)),
+    }
+}
+
+fn battery(
+    manufacturer: Manufacturer,
+    year: Option<&str>,
+    month: Option<&str>,
+) -> Result<Battery, String> {
+    Ok(Battery {
+        kind: "CR2025".to_owned(),
+        manufacturer: Some(manufacturer),
+        year: year.map(year1).transpose()?,
+        month: month.map(month1_xyz).transpose()?,
+    })
+}
+
+/// ```
+/// use gbhwdb_backend::{
+///     parser::{self, LabelParser, Manufacturer, Year},
+///     time::Month,
+/// };
+/// let battery = parser::battery::maxell_cr2025().parse("CR2025 MAXELL").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Maxell));
+/// assert_eq!(battery.year, None);
+/// assert_eq!(battery.month, None);
+/// let battery = parser::battery::maxell_cr2025().parse("CR2025 MAXELL JAPAN 9X").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Maxell));
+/// assert_eq!(battery.year, Some(Year::Partial(9)));
+/// assert_eq!(battery.month, Some(Month::October));
+/// let battery = parser::battery::maxell_cr2025().parse("MAXELL CR2025 3V 74").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Maxell));
+/// assert_eq!(battery.year, Some(Year::Partial(7)));
+/// assert_eq!(battery.month, Some(Month::April));
+/// ```
+pub fn maxell_cr2025() -> &'static impl LabelParser<Battery> {
+    single_parser!(
+        Battery,
+        r#"^(?:CR2025\ MAXELL|MAXELL\ CR2025)(?:\ 3V)?(?:\ JAPAN)?(?:\ ([0-9])([1-9XYZ]))?$"#,
+        move |c| {
+            battery(
+                Manufacturer::Maxell,
+                c.get(1).map(|m| m.as_str()),
+                c.get(2).map(|m| m.as_str()),
+            )
+        }
+    )
+}
+
+/// ```
+/// use gbhwdb_backend::{
+///     parser::{self, LabelParser, Manufacturer, Year},
+///     time::Month,
+/// };
+/// let battery = parser::battery::panasonic_cr2025().parse("PANASONIC CR2025 3V").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Panasonic));
+/// assert_eq!(battery.year, None);
+/// assert_eq!(battery.month, None);
+/// let battery = parser::battery::panasonic_cr2025().parse("CR2025 PANASONIC 3V 8Z").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Panasonic));
+/// assert_eq!(battery.year, Some(Year::Partial(8)));
+/// assert_eq!(battery.month, Some(Month::December));
+/// let battery = parser::battery::panasonic_cr2025().parse("NATIONAL CR2025 3V 93").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Panasonic));
+/// assert_eq!(battery.year, Some(Year::Partial(9)));
+/// assert_eq!(battery.month, Some(Month::March));
+/// ```
+pub fn panasonic_cr2025() -> &'static impl LabelParser<Battery> {
+    single_parser!(
+        Battery,
+        r#"^(?:(?:PANASONIC|NATIONAL)\ CR2025|CR2025\ PANASONIC)(?:\ 3V)?(?:\ JAPAN)?(?:\ ([0-9])([1-9XYZ]))?$"#,
+        move |c| {
+            battery(
+                Manufacturer::Panasonic,
+                c.get(1).map(|m| m.as_str()),
+                c.get(2).map(|m| m.as_str()),
+            )
+        }
+    )
+}
+
+/// ```
+/// use gbhwdb_backend::{
+///     parser::{self, LabelParser, Manufacturer, Year},
+///     time::Month,
+/// };
+/// let battery = parser::battery::sony_cr2025().parse("CR2025 SONY 3V").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Sony));
+/// assert_eq!(battery.year, None);
+/// assert_eq!(battery.month, None);
+/// let battery = parser::battery::sony_cr2025().parse("SONY CR2025 3V 05").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Sony));
+/// assert_eq!(battery.year, Some(Year::Partial(0)));
+/// assert_eq!(battery.month, Some(Month::May));
+/// ```
+pub fn sony_cr2025() -> &'static impl LabelParser<Battery> {
+    single_parser!(
+        Battery,
+        r#"^(?:CR2025\ SONY|SONY\ CR2025)(?:\ 3V)?(?:\ JAPAN)?(?:\ ([0-9])([1-9XYZ]))?$"#,
+        move |c| {
+            battery(
+                Manufacturer::Sony,
+                c.get(1).map(|m| m.as_str()),
+                c.get(2).map(|m| m.as_str()),
+            )
+        }
+    )
+}
+
+/// ```
+/// use gbhwdb_backend::{
+///     parser::{self, LabelParser, Manufacturer, Year},
+///     time::Month,
+/// };
+/// let battery = parser::battery::battery_cr2025().parse("CR2025 MAXELL JAPAN 9X").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Maxell));
+/// assert_eq!(battery.year, Some(Year::Partial(9)));
+/// assert_eq!(battery.month, Some(Month::October));
+/// let battery = parser::battery::battery_cr2025().parse("CR2025 PANASONIC 3V 8Y").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Panasonic));
+/// assert_eq!(battery.year, Some(Year::Partial(8)));
+/// assert_eq!(battery.month, Some(Month::November));
+/// let battery = parser::battery::battery_cr2025().parse("SONY CR2025 3V 05").unwrap();
+/// assert_eq!(battery.manufacturer, Some(Manufacturer::Sony));
+/// assert_eq!(battery.year, Some(Year::Partial(0)));
+/// assert_eq!(battery.month, Some(Month::May));
+/// assert!(parser::battery::battery_cr2025().parse("CR2025 MAXELL 9A").is_err());
+/// ```
+pub fn battery_cr2025() -> &'static impl LabelParser<Battery> {
+    multi_parser!(Battery, maxell_cr2025(), panasonic_cr2025(), sony_cr2025(),)
+}
@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::str::FromStr;
+
+use super::LabelParser;
+use crate::macros::single_parser;
+
+/// Code printed on the back label of the shell, e.g. `DMG-AYWJ-JPN`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShellCode {
+    pub platform: String,
+    pub game_id: String,
+    pub region: Option<String>,
+    pub revision: Option<u8>,
+    /// Display/demo units have an extra `DIS-` prefix
+    pub is_display: bool,
+}
+
+/// Stamp on the front label of the shell, e.g. `22A`.
+///
+/// The stamp is not a date code: the same stamp appears on cartridges made over a decade apart
+/// (e.g. `22` on boards dated from 1989 to 2002), so it is kept as is.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShellStamp {
+    pub number: u8,
+    pub letter: Option<char>,
+}
+
+/// ```
+/// use gbhwdb_backend::parser::{self, LabelParser};
+/// assert!(parser::cartridge_shell::shell_code().parse("DMG-AYWJ-JPN").is_ok());
+/// assert!(parser::cartridge_shell::shell_code().parse("DMG-ML-USA-1").is_ok());
+/// assert!(parser::cartridge_shell::shell_code().parse("DMG-HLA").is_ok());
+/// assert!(parser::cartridge_shell::shell_code().parse("DIS-CGB-AW8A-USA").is_ok());
+/// ```
+pub fn shell_code() -> &'static impl LabelParser<ShellCode> {
+    single_parser!(
+        ShellCode,
+        r#"^(DIS-)?(DMG|CGB)-([A-Z0-9]{2,4})(?:-([A-Z]{3}))?(?:-([0-9]))?$"#,
+        move |c| {
+            Ok(ShellCode {
+                platform: c[2].to_owned(),
+                game_id: c[3].to_owned(),
+                region: c.get(4).map(|m| m.as_str().to_owned()),
+                revision: c
+                    .get(5)
+                    .map(|m| u8::from_str(m.as_str()))
+                    .transpose()
+                    .map_err(|err| err.to_string())?,
+                is_display: c.get(1).is_some(),
+            })
+        }
+    )
+}
+
+/// ```
+/// use gbhwdb_backend::parser::{self, LabelParser};
+/// assert!(parser::cartridge_shell::shell_stamp().parse("34").is_ok());
+/// assert!(parser::cartridge_shell::shell_stamp().parse("00A").is_ok());
+/// ```
+pub fn shell_stamp() -> &'static impl LabelParser<ShellStamp> {
+    single_parser!(ShellStamp, r#"^([0-9]{2})([A-Z])?$"#, move |c| {
+        Ok(ShellStamp {
+            number: u8::from_str(&c[1]).map_err(|err| err.to_string())?,
+            letter: c.get(2).and_then(|m| m.as_str().chars().next()),
+        })
+    })
+}
@@ -2,13 +2,16 @@
 //
 // SPDX-License-Identifier: MIT
 
+use serde::{Deserialize, Serialize};
+
 use super::{week2, year1, year2, LabelParser, Manufacturer, Year};
 use crate::{
     macros::{multi_parser, single_parser},
     time::Week,
 };
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Mbc1Version {
     Original,
     A,
@@ -16,26 +19,37 @@ pub enum Mbc1Version {
     B1,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Mbc2Version {
     Original,
     A,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Mbc3Version {
     Original,
     A,
     B,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Huc1Version {
     Original,
     A,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SachenVersion {
+    Mmc1,
+    Mmc2,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MapperType {
     Mbc1(Mbc1Version),
     Mbc2(Mbc2Version),
@@ -47,6 +61,10 @@ pub enum MapperType {
     Huc1(Huc1Version),
     Huc3,
     Mmm01,
+    Tama5,
+    WisdomTree,
+    Sachen(SachenVersion),
+    Multicart,
 }
 
 impl MapperType {
@@ -69,8 +87,98 @@ impl MapperType {
             MapperType::Huc3 => "HuC-3",
             MapperType::Huc1(Huc1Version::Original) => "HuC-1",
             MapperType::Huc1(Huc1Version::A) => "HuC-1A",
+            MapperType::Tama5 => "TAMA5",
+            MapperType::WisdomTree => "Wisdom Tree",
+            MapperType::Sachen(SachenVersion::Mmc1) => "Sachen MMC1",
+            MapperType::Sachen(SachenVersion::Mmc2) => "Sachen MMC2",
+            MapperType::Multicart => "Multicart",
+        }
+    }
+    pub fn family(&self) -> MapperFamily {
+        match self {
+            MapperType::Mbc1(_) => MapperFamily::Mbc1,
+            MapperType::Mbc2(_) => MapperFamily::Mbc2,
+            MapperType::Mbc3(_) => MapperFamily::Mbc3,
+            MapperType::Mbc30 => MapperFamily::Mbc30,
+            MapperType::Mbc5 => MapperFamily::Mbc5,
+            MapperType::Mbc6 => MapperFamily::Mbc6,
+            MapperType::Mbc7 => MapperFamily::Mbc7,
+            MapperType::Mmm01 => MapperFamily::Mmm01,
+            MapperType::Huc1(_) => MapperFamily::Huc1,
+            MapperType::Huc3 => MapperFamily::Huc3,
+            MapperType::Tama5 => MapperFamily::Tama5,
+            MapperType::WisdomTree => MapperFamily::WisdomTree,
+            MapperType::Sachen(_) => MapperFamily::Sachen,
+            MapperType::Multicart => MapperFamily::Multicart,
+        }
+    }
+    pub fn is_unlicensed(&self) -> bool {
+        self.family().is_unlicensed()
+    }
+}
+
+/// Mapper type without the silicon version
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MapperFamily {
+    Mbc1,
+    Mbc2,
+    Mbc3,
+    Mbc30,
+    Mbc5,
+    Mbc6,
+    Mbc7,
+    Mmm01,
+    Huc1,
+    Huc3,
+    Tama5,
+    WisdomTree,
+    Sachen,
+    Multicart,
+}
+
+impl MapperFamily {
+    pub fn id(&self) -> &'static str {
+        match self {
+            MapperFamily::Mbc1 => "mbc1",
+            MapperFamily::Mbc2 => "mbc2",
+            MapperFamily::Mbc3 => "mbc3",
+            MapperFamily::Mbc30 => "mbc30",
+            MapperFamily::Mbc5 => "mbc5",
+            MapperFamily::Mbc6 => "mbc6",
+            MapperFamily::Mbc7 => "mbc7",
+            MapperFamily::Mmm01 => "mmm01",
+            MapperFamily::Huc1 => "huc1",
+            MapperFamily::Huc3 => "huc3",
+            MapperFamily::Tama5 => "tama5",
+            MapperFamily::WisdomTree => "wisdom-tree",
+            MapperFamily::Sachen => "sachen",
+            MapperFamily::Multicart => "multicart",
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapperFamily::Mbc1 => "MBC1",
+            MapperFamily::Mbc2 => "MBC2",
+            MapperFamily::Mbc3 => "MBC3",
+            MapperFamily::Mbc30 => "MBC30",
+            MapperFamily::Mbc5 => "MBC5",
+            MapperFamily::Mbc6 => "MBC6",
+            MapperFamily::Mbc7 => "MBC7",
+            MapperFamily::Mmm01 => "MMM01",
+            MapperFamily::Huc1 => "HuC-1",
+            MapperFamily::Huc3 => "HuC-3",
+            MapperFamily::Tama5 => "TAMA5",
+            MapperFamily::WisdomTree => "Wisdom Tree",
+            MapperFamily::Sachen => "Sachen",
+            MapperFamily::Multicart => "Multicart",
         }
     }
+    pub fn is_unlicensed(&self) -> bool {
+        matches!(
+            self,
+            MapperFamily::WisdomTree | MapperFamily::Sachen | MapperFamily::Multicart
+        )
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! JSON Schemas of the `metadata.json` submission formats.
+//!
+//! The schemas are generated from the input types, so they always match what the loader accepts.
+//! Submission files can refer to them with a `$schema` key to get validation and completion in
+//! editors.
+
+use schemars::{gen::SchemaGenerator, schema::RootSchema};
+
+use crate::{
+    input::{
+        agb::AgbConsole, ags::AgsConsole, cartridge::Cartridge, cgb::CgbConsole,
+        console::ConsoleSubmission, dmg::DmgConsole, gbs::GbsConsole, mgb::MgbConsole,
+        mgl::MglConsole, oxy::OxyConsole, sgb::SgbConsole, sgb2::Sgb2Console,
+    },
+    Console,
+};
+
+/// Directory of the generated schemas, relative to the root directory
+pub const SCHEMA_DIR: &str = "schemas";
+
+pub const CARTRIDGE_SCHEMA: &str = "cartridge.schema.json";
+
+pub fn console_schema_file(console: Console) -> String {
+    format!("{}.schema.json", console.id())
+}
+
+/// Returns the `$schema` value for a `metadata.json` file in `data/cartridges/*/*` or
+/// `data/consoles/*/*`.
+///
+/// ```
+/// # use gbhwdb_backend::schema::{schema_ref, CARTRIDGE_SCHEMA};
+/// assert_eq!(schema_ref(CARTRIDGE_SCHEMA), "../../../../schemas/cartridge.schema.json");
+/// ```
+pub fn schema_ref(file: &str) -> String {
+    format!("../../../../{SCHEMA_DIR}/{file}")
+}
+
+/// Returns the file name and schema of every submission format
+pub fn submission_schemas() -> Vec<(String, RootSchema)> {
+    vec![
+        (
+            CARTRIDGE_SCHEMA.to_owned(),
+            SchemaGenerator::default().into_root_schema_for::<Cartridge>(),
+        ),
+        console_schema::<DmgConsole>(),
+        console_schema::<SgbConsole>(),
+        console_schema::<MgbConsole>(),
+        console_schema::<MglConsole>(),
+        console_schema::<Sgb2Console>(),
+        console_schema::<CgbConsole>(),
+        console_schema::<AgbConsole>(),
+        console_schema::<AgsConsole>(),
+        console_schema::<GbsConsole>(),
+        console_schema::<OxyConsole>(),
+    ]
+}
+
+fn console_schema<C: ConsoleSubmission>() -> (String, RootSchema) {
+    (
+        console_schema_file(C::CONSOLE),
+        SchemaGenerator::default().into_root_schema_for::<C>(),
+    )
+}
+
+#[test]
+fn test_schemas_are_up_to_date() {
+    use crate::format::to_canonical_json;
+
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(SCHEMA_DIR);
+    for (file, schema) in submission_schemas() {
+        let expected = to_canonical_json(&schema).unwrap();
+        let actual = std::fs::read_to_string(dir.join(&file)).unwrap_or_default();
+        assert!(
+            actual == expected,
+            "{SCHEMA_DIR}/{file} is outdated, regenerate it with gbhwdb-schema"
+        );
+    }
+}
+
+#[test]
+fn test_schema_version_is_optional() {
+    // Files without a schema_version are version 0, and the loader migrates them
+    for (file, schema) in submission_schemas() {
+        let object = schema.schema.object.expect("object schema");
+        assert!(
+            !object.required.contains("schema_version"),
+            "{file} requires schema_version"
+        );
+    }
+}
@@ -2,6 +2,11 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt, str};
 
@@ -69,3 +74,20 @@ impl<'de> Deserialize<'de> for Sha256 {
         deserializer.deserialize_str(Visitor)
     }
 }
+
+impl JsonSchema for Sha256 {
+    fn schema_name() -> String {
+        "Sha256".to_owned()
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^[0-9a-fA-F]{64}$".to_owned()),
+                ..StringValidation::default()
+            })),
+            ..SchemaObject::default()
+        }
+        .into()
+    }
+}
@@ -2,8 +2,14 @@
 //
 // SPDX-License-Identifier: MIT
 
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, NumberValidation, Schema, SchemaObject},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use std::{fmt, ops::RangeInclusive};
+use time::{Date, Weekday};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[repr(transparent)]
@@ -98,6 +104,15 @@ impl Month {
     }
 }
 
+impl JsonSchema for Month {
+    fn schema_name() -> String {
+        "Month".to_owned()
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        integer_schema(1..=12)
+    }
+}
+
 impl fmt::Display for Month {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self.name(), f)
@@ -133,6 +148,15 @@ impl TryFrom<u8> for Jun {
     }
 }
 
+impl JsonSchema for Jun {
+    fn schema_name() -> String {
+        "Jun".to_owned()
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        integer_schema(1..=3)
+    }
+}
+
 impl Jun {
     pub fn range(&self, year: u16, month: Month) -> RangeInclusive<u8> {
         use Month::*;
@@ -149,6 +173,83 @@ impl Jun {
     }
 }
 
+/// Returns a schema of integers that are serialized from a `u8` within the range
+fn integer_schema(range: RangeInclusive<u8>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Integer.into()),
+        number: Some(Box::new(NumberValidation {
+            minimum: Some(f64::from(*range.start())),
+            maximum: Some(f64::from(*range.end())),
+            ..NumberValidation::default()
+        })),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
 fn is_leap_year(year: u16) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
 }
+
+/// Returns the first and last day covered by a date code.
+///
+/// Weeks are interpreted as ISO 8601 weeks. If only the year is known, the range covers the whole
+/// year.
+///
+/// ```
+/// # use gbhwdb_backend::time::{date_range, Month, Week};
+/// # use time::macros::date;
+/// let week = Week::try_from(7).unwrap();
+/// assert_eq!(date_range(1992, None, Some(week)), Some(date!(1992-02-10)..=date!(1992-02-16)));
+/// assert_eq!(date_range(1996, Some(Month::February), None), Some(date!(1996-02-01)..=date!(1996-02-29)));
+/// assert_eq!(date_range(1990, None, None), Some(date!(1990-01-01)..=date!(1990-12-31)));
+/// ```
+pub fn date_range(
+    year: u16,
+    month: Option<Month>,
+    week: Option<Week>,
+) -> Option<RangeInclusive<Date>> {
+    let year = i32::from(year);
+    if let Some(Week(week)) = week {
+        let first = Date::from_iso_week_date(year, week, Weekday::Monday).ok()?;
+        let last = Date::from_iso_week_date(year, week, Weekday::Sunday).ok()?;
+        return Some(first..=last);
+    }
+    let (first, last) = match month {
+        Some(month) => {
+            let month = ::time::Month::try_from(u8::from(month)).ok()?;
+            let first = Date::from_calendar_date(year, month, 1).ok()?;
+            let last = match month {
+                ::time::Month::December => Date::from_calendar_date(year, month, 31).ok()?,
+                _ => Date::from_calendar_date(year, month.next(), 1)
+                    .ok()?
+                    .previous_day()?,
+            };
+            (first, last)
+        }
+        None => (
+            Date::from_ordinal_date(year, 1).ok()?,
+            Date::from_calendar_date(year, ::time::Month::December, 31).ok()?,
+        ),
+    };
+    Some(first..=last)
+}
+
+pub fn guess_full_year(hint: u16, partial_year: u8) -> u16 {
+    let partial_year = u16::from(partial_year);
+    let decades = [1980, 1990, 2000];
+    decades
+        .into_iter()
+        .map(|decade: u16| decade + partial_year)
+        .min_by_key(|&year| hint.abs_diff(year))
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_guess_full_year() {
+    assert_eq!(1992, guess_full_year(1992, 2));
+    assert_eq!(1989, guess_full_year(1989, 9));
+    assert_eq!(1990, guess_full_year(1990, 0));
+    assert_eq!(1999, guess_full_year(1998, 9));
+    assert_eq!(2000, guess_full_year(2005, 0));
+}
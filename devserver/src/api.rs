@@ -0,0 +1,449 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Read-only JSON API over the parsed submission database, served under `/api`.
+//!
+//! - `/consoles`
+//! - `/consoles/:console/submissions`, `/consoles/:console/submissions/:slug`
+//! - `/games`, `/games/:code`
+//! - `/games/:code/submissions`, `/games/:code/submissions/:slug`
+//! - `/chips`: chip kind counts, filtered by `console` (or `cartridge`) and `role`
+//! - `/skipped`: fatal problems of the submissions that were left out of the database
+//!
+//! Lists are paginated with `page` (starting from 1) and `per_page`. Submission lists can be
+//! filtered with `board`, `contributor`, `chip_kind`, `year_from`, `year_to` and a filter
+//! expression `q`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use gbhwdb_backend::{
+    config::cartridge::GameConfig,
+    db::{self, expr::Expr, Category, Database, Submission, SubmissionChip},
+    time::{Month, Week},
+    Console,
+};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use crate::live::{LiveDatabase, Snapshot};
+
+const DEFAULT_PER_PAGE: usize = 50;
+const MAX_PER_PAGE: usize = 500;
+
+pub fn router(db: LiveDatabase) -> Router {
+    Router::new()
+        .route("/consoles", get(list_consoles))
+        .route(
+            "/consoles/:console/submissions",
+            get(list_console_submissions),
+        )
+        .route(
+            "/consoles/:console/submissions/:slug",
+            get(get_console_submission),
+        )
+        .route("/games", get(list_games))
+        .route("/games/:code", get(get_game))
+        .route("/games/:code/submissions", get(list_game_submissions))
+        .route("/games/:code/submissions/:slug", get(get_game_submission))
+        .route("/chips", get(list_chip_kinds))
+        .route("/skipped", get(list_skipped))
+        .with_state(db)
+}
+
+#[derive(Clone, Debug)]
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn not_found(what: impl Into<String>) -> ApiError {
+        ApiError(StatusCode::NOT_FOUND, what.into())
+    }
+    fn bad_request(what: impl Into<String>) -> ApiError {
+        ApiError(StatusCode::BAD_REQUEST, what.into())
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(ErrorBody { error: self.1 })).into_response()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Page<T> {
+    page: usize,
+    per_page: usize,
+    total: usize,
+    items: Vec<T>,
+}
+
+/// Returns one page of items. Pages are numbered from 1
+fn paginate<T>(
+    items: impl IntoIterator<Item = T>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+) -> Page<T> {
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let items = items.into_iter().collect::<Vec<_>>();
+    let total = items.len();
+    Page {
+        page,
+        per_page,
+        total,
+        items: items
+            .into_iter()
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .collect(),
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct PageParams {
+    page: Option<usize>,
+    per_page: Option<usize>,
+}
+
+fn find_console(id: &str) -> Result<Console, ApiError> {
+    Console::ALL
+        .into_iter()
+        .find(|console| console.id().eq_ignore_ascii_case(id))
+        .ok_or_else(|| ApiError::not_found(format!("Unknown console {id}")))
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ConsoleInfo {
+    id: &'static str,
+    code: &'static str,
+    name: &'static str,
+    submissions: usize,
+}
+
+async fn list_consoles(State(db): State<Arc<Database>>) -> Json<Vec<ConsoleInfo>> {
+    Json(
+        Console::ALL
+            .into_iter()
+            .map(|console| ConsoleInfo {
+                id: console.id(),
+                code: console.code(),
+                name: console.name(),
+                submissions: db
+                    .submissions
+                    .iter()
+                    .filter(|submission| submission.category == Category::Console(console))
+                    .count(),
+            })
+            .collect(),
+    )
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SubmissionSummary<'a> {
+    code: &'a str,
+    slug: &'a str,
+    title: &'a str,
+    contributor: &'a str,
+    board: &'a str,
+    outlier: bool,
+    photos: Vec<&'static str>,
+}
+
+impl<'a> From<&'a Submission> for SubmissionSummary<'a> {
+    fn from(submission: &'a Submission) -> Self {
+        SubmissionSummary {
+            code: &submission.code,
+            slug: &submission.slug,
+            title: &submission.title,
+            contributor: &submission.contributor,
+            board: &submission.board,
+            outlier: submission.outlier,
+            photos: submission.photos.iter().map(|photo| photo.name).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ChipInfo<'a> {
+    designator: &'static str,
+    role: &'static str,
+    label: Option<&'a str>,
+    kind: Option<&'a str>,
+    manufacturer: Option<&'static str>,
+    year: Option<u16>,
+    month: Option<Month>,
+    week: Option<Week>,
+    rom_code: Option<&'a str>,
+}
+
+impl<'a> From<&'a SubmissionChip> for ChipInfo<'a> {
+    fn from(chip: &'a SubmissionChip) -> Self {
+        ChipInfo {
+            designator: chip.designator,
+            role: chip.role,
+            label: chip.chip.label.as_deref(),
+            kind: chip.chip.kind.as_deref(),
+            manufacturer: chip
+                .chip
+                .manufacturer
+                .map(|manufacturer| manufacturer.name()),
+            year: chip.chip.year,
+            month: chip.chip.month,
+            week: chip.chip.week,
+            rom_code: chip.chip.rom_code.as_deref(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SubmissionDetail<'a> {
+    #[serde(flatten)]
+    summary: SubmissionSummary<'a>,
+    chips: Vec<ChipInfo<'a>>,
+    metadata: &'a serde_json::Value,
+}
+
+/// Submission list filters. Chip filters must all match the same chip
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct SubmissionParams {
+    board: Option<String>,
+    contributor: Option<String>,
+    chip_kind: Option<String>,
+    year_from: Option<u16>,
+    year_to: Option<u16>,
+    /// Filter expression (see `gbhwdb_backend::db::expr`)
+    q: Option<String>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+}
+
+fn list_submissions<'a>(
+    db: &'a Database,
+    category: Category,
+    code: &str,
+    params: SubmissionParams,
+) -> Result<Page<SubmissionSummary<'a>>, ApiError> {
+    let years = match (params.year_from, params.year_to) {
+        (None, None) => None,
+        (from, to) => Some(from.unwrap_or(u16::MIN)..=to.unwrap_or(u16::MAX)),
+    };
+    let query = db::Query {
+        category: Some(category),
+        board: params.board,
+        contributor: params.contributor,
+        chip_kind: params.chip_kind,
+        manufacturer: None,
+        years,
+    };
+    let expr = params
+        .q
+        .as_deref()
+        .map(Expr::parse)
+        .transpose()
+        .map_err(|err| ApiError::bad_request(format!("Invalid filter expression: {err}")))?;
+    let submissions = db
+        .submissions
+        .iter()
+        .filter(|submission| submission.code == code && query.matches(submission))
+        .filter(|submission| expr.iter().all(|expr| expr.matches(submission)))
+        .map(SubmissionSummary::from);
+    Ok(paginate(submissions, params.page, params.per_page))
+}
+
+fn get_submission<'a>(
+    db: &'a Database,
+    category: Category,
+    code: &str,
+    slug: &str,
+) -> Result<SubmissionDetail<'a>, ApiError> {
+    let submission = db
+        .submissions
+        .iter()
+        .find(|submission| {
+            submission.category == category && submission.code == code && submission.slug == slug
+        })
+        .ok_or_else(|| ApiError::not_found(format!("Unknown submission {code}/{slug}")))?;
+    Ok(SubmissionDetail {
+        summary: SubmissionSummary::from(submission),
+        chips: submission.chips.iter().map(ChipInfo::from).collect(),
+        metadata: &submission.metadata,
+    })
+}
+
+async fn list_console_submissions(
+    State(db): State<Arc<Database>>,
+    Path(console): Path<String>,
+    Query(params): Query<SubmissionParams>,
+) -> Response {
+    let result = find_console(&console).and_then(|console| {
+        list_submissions(&db, Category::Console(console), console.code(), params)
+    });
+    to_response(result)
+}
+
+async fn get_console_submission(
+    State(db): State<Arc<Database>>,
+    Path((console, slug)): Path<(String, String)>,
+) -> Response {
+    let result = find_console(&console)
+        .and_then(|console| get_submission(&db, Category::Console(console), console.code(), &slug));
+    to_response(result)
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct GameInfo<'a> {
+    code: &'a str,
+    #[serde(flatten)]
+    cfg: &'a GameConfig,
+    submissions: usize,
+}
+
+fn game_info<'a>(db: &'a Database, code: &'a str, cfg: &'a GameConfig) -> GameInfo<'a> {
+    GameInfo {
+        code,
+        cfg,
+        submissions: db
+            .submissions
+            .iter()
+            .filter(|submission| {
+                submission.category == Category::Cartridge && submission.code == code
+            })
+            .count(),
+    }
+}
+
+async fn list_games(State(db): State<Arc<Database>>, Query(params): Query<PageParams>) -> Response {
+    let games = db.cfgs.iter().map(|(code, cfg)| game_info(&db, code, cfg));
+    Json(paginate(games, params.page, params.per_page)).into_response()
+}
+
+async fn get_game(State(db): State<Arc<Database>>, Path(code): Path<String>) -> Response {
+    let result = db
+        .cfgs
+        .get_key_value(&code)
+        .map(|(code, cfg)| game_info(&db, code, cfg))
+        .ok_or_else(|| ApiError::not_found(format!("Unknown game {code}")));
+    to_response(result)
+}
+
+async fn list_game_submissions(
+    State(db): State<Arc<Database>>,
+    Path(code): Path<String>,
+    Query(params): Query<SubmissionParams>,
+) -> Response {
+    if !db.cfgs.contains_key(&code) {
+        return ApiError::not_found(format!("Unknown game {code}")).into_response();
+    }
+    to_response(list_submissions(&db, Category::Cartridge, &code, params))
+}
+
+async fn get_game_submission(
+    State(db): State<Arc<Database>>,
+    Path((code, slug)): Path<(String, String)>,
+) -> Response {
+    to_response(get_submission(&db, Category::Cartridge, &code, &slug))
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct ChipParams {
+    /// Console id, or `cartridge` for cartridge chips
+    console: Option<String>,
+    role: Option<String>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct ChipKindInfo<'a> {
+    kind: &'a str,
+    count: usize,
+    manufacturers: BTreeMap<&'static str, usize>,
+    first_year: Option<u16>,
+    last_year: Option<u16>,
+}
+
+async fn list_chip_kinds(
+    State(db): State<Arc<Database>>,
+    Query(params): Query<ChipParams>,
+) -> Response {
+    let category = match params.console.as_deref() {
+        None => None,
+        Some(id) if id.eq_ignore_ascii_case("cartridge") => Some(Category::Cartridge),
+        Some(id) => match find_console(id) {
+            Ok(console) => Some(Category::Console(console)),
+            Err(err) => return err.into_response(),
+        },
+    };
+    let mut kinds: BTreeMap<&str, ChipKindInfo> = BTreeMap::new();
+    let chips = db
+        .submissions
+        .iter()
+        .filter(|submission| {
+            category
+                .iter()
+                .all(|&category| submission.category == category)
+        })
+        .flat_map(|submission| &submission.chips)
+        .filter(|chip| {
+            params
+                .role
+                .iter()
+                .all(|role| chip.role.eq_ignore_ascii_case(role))
+        });
+    for chip in chips {
+        let Some(kind) = chip.chip.kind.as_deref() else {
+            continue;
+        };
+        let info = kinds.entry(kind).or_insert_with(|| ChipKindInfo {
+            kind,
+            ..ChipKindInfo::default()
+        });
+        info.count += 1;
+        if let Some(manufacturer) = chip.chip.manufacturer {
+            *info.manufacturers.entry(manufacturer.name()).or_default() += 1;
+        }
+        if let Some(year) = chip.chip.year {
+            info.first_year = Some(info.first_year.map_or(year, |first| first.min(year)));
+            info.last_year = Some(info.last_year.map_or(year, |last| last.max(year)));
+        }
+    }
+    let mut kinds = kinds.into_values().collect::<Vec<_>>();
+    kinds.sort_by_key(|info| Reverse(info.count));
+    Json(paginate(kinds, params.page, params.per_page)).into_response()
+}
+
+async fn list_skipped(State(snapshot): State<Arc<Snapshot>>) -> Response {
+    Json(&snapshot.skipped).into_response()
+}
+
+fn to_response<T: Serialize>(result: Result<T, ApiError>) -> Response {
+    match result {
+        Ok(value) => Json(value).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+#[test]
+fn test_paginate() {
+    let page = paginate(1..=120, None, None);
+    assert_eq!((page.page, page.per_page, page.total), (1, 50, 120));
+    assert_eq!(page.items.first(), Some(&1));
+    let page = paginate(1..=120, Some(3), Some(50));
+    assert_eq!(page.items, (101..=120).collect::<Vec<_>>());
+    let page = paginate(1..=120, Some(0), Some(10_000));
+    assert_eq!((page.page, page.per_page, page.items.len()), (1, 500, 120));
+    assert!(paginate(1..=120, Some(4), None).items.is_empty());
+}
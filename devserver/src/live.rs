@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Database that is reloaded when the data files change.
+//!
+//! Submissions with fatal lint problems are skipped like in the site build, so one broken file
+//! doesn't take down the whole API.
+
+use axum::extract::FromRef;
+use gbhwdb_backend::{
+    db::Database,
+    lint::{lint_and_load, Problem, Severity},
+};
+use log::info;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    thread,
+    time::{Duration, SystemTime},
+};
+use tokio::task::JoinHandle;
+
+/// How often the data files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the data files must stay unchanged before they are reloaded, so saving several files
+/// at once causes only one reload
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Loaded database and the problems of the submissions that were left out of it
+#[derive(Debug)]
+pub struct Snapshot {
+    pub db: Arc<Database>,
+    /// Fatal problems of the skipped submissions
+    pub skipped: Vec<Problem>,
+}
+
+impl Snapshot {
+    pub fn load(root: &Path) -> Snapshot {
+        let (report, db) = lint_and_load(root);
+        let skipped = report
+            .problems
+            .into_iter()
+            .filter(|problem| problem.severity == Severity::Fatal)
+            .collect();
+        Snapshot {
+            db: Arc::new(db),
+            skipped,
+        }
+    }
+}
+
+/// Number of entries and the latest modification time in the data files
+type Fingerprint = (usize, Option<SystemTime>);
+
+fn add_fingerprint(path: &Path, fingerprint: &mut Fingerprint) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    fingerprint.0 += 1;
+    fingerprint.1 = fingerprint.1.max(metadata.modified().ok());
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            add_fingerprint(&entry.path(), fingerprint);
+        }
+    }
+}
+
+/// Returns the fingerprint of `config/games.json` and everything in `data`.
+///
+/// Directories are included, so removing or renaming a file changes the fingerprint too.
+fn fingerprint(root: &Path) -> Fingerprint {
+    let mut fingerprint = (0, None);
+    add_fingerprint(&root.join("config/games.json"), &mut fingerprint);
+    add_fingerprint(&root.join("data"), &mut fingerprint);
+    fingerprint
+}
+
+/// Shared handle to the current snapshot, used as the state of the API router
+#[derive(Clone, Debug)]
+pub struct LiveDatabase {
+    current: Arc<RwLock<Arc<Snapshot>>>,
+}
+
+impl LiveDatabase {
+    pub fn new(snapshot: Snapshot) -> LiveDatabase {
+        LiveDatabase {
+            current: Arc::new(RwLock::new(Arc::new(snapshot))),
+        }
+    }
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        let current = self.current.read().unwrap_or_else(|err| err.into_inner());
+        Arc::clone(&current)
+    }
+    fn replace(&self, snapshot: Snapshot) {
+        let mut current = self.current.write().unwrap_or_else(|err| err.into_inner());
+        *current = Arc::new(snapshot);
+    }
+    /// Starts a background task that reloads the snapshot whenever the data files in the root
+    /// directory change
+    pub fn watch(&self, root: PathBuf) -> JoinHandle<()> {
+        let live = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut loaded = fingerprint(&root);
+            loop {
+                thread::sleep(POLL_INTERVAL);
+                let mut current = fingerprint(&root);
+                if current == loaded {
+                    continue;
+                }
+                loop {
+                    thread::sleep(DEBOUNCE);
+                    let next = fingerprint(&root);
+                    if next == current {
+                        break;
+                    }
+                    current = next;
+                }
+                loaded = current;
+                let snapshot = Snapshot::load(&root);
+                info!(
+                    "Reloaded the database ({} fatal problems)",
+                    snapshot.skipped.len()
+                );
+                live.replace(snapshot);
+            }
+        })
+    }
+}
+
+impl FromRef<LiveDatabase> for Arc<Snapshot> {
+    fn from_ref(live: &LiveDatabase) -> Self {
+        live.snapshot()
+    }
+}
+
+impl FromRef<LiveDatabase> for Arc<Database> {
+    fn from_ref(live: &LiveDatabase) -> Self {
+        Arc::clone(&live.snapshot().db)
+    }
+}
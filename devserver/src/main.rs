@@ -2,19 +2,42 @@
 //
 // SPDX-License-Identifier: MIT
 
+use anyhow::Error;
 use axum::{routing::get_service, Router};
-use std::net::SocketAddr;
+use log::{info, warn, LevelFilter};
+use simplelog::{ColorChoice, TermLogger, TerminalMode};
+use std::{net::SocketAddr, path::PathBuf};
 use tower_http::services::ServeDir;
 
+use crate::live::{LiveDatabase, Snapshot};
+
+mod api;
+mod live;
+
 #[tokio::main]
-async fn main() {
-    let app = Router::new().fallback(get_service(ServeDir::new("build")));
+async fn main() -> Result<(), Error> {
+    let _ = TermLogger::init(
+        LevelFilter::Info,
+        simplelog::Config::default(),
+        TerminalMode::Mixed,
+        ColorChoice::Auto,
+    );
+    let root = PathBuf::from(".");
+    let snapshot = Snapshot::load(&root);
+    for problem in &snapshot.skipped {
+        warn!("Skipped {problem}");
+    }
+    let db = LiveDatabase::new(snapshot);
+    db.watch(root);
+    let app = Router::new()
+        .nest("/api", api::router(db))
+        .fallback(get_service(ServeDir::new("build")));
 
     let port = 8080;
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    println!("Development server listening at port {port}");
+    info!("Development server listening at port {port}");
     axum::Server::bind(&addr)
         .serve(app.into_make_service())
-        .await
-        .unwrap();
+        .await?;
+    Ok(())
 }
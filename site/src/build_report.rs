@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use gbhwdb_backend::lint::{Report, Severity};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+#[derive(Serialize)]
+struct BuildReport<'a> {
+    fatal: usize,
+    errors: usize,
+    warnings: usize,
+    files: Vec<FileReport<'a>>,
+}
+
+#[derive(Serialize)]
+struct FileReport<'a> {
+    path: &'a Path,
+    problems: Vec<FileProblem<'a>>,
+}
+
+#[derive(Serialize)]
+struct FileProblem<'a> {
+    severity: Severity,
+    pointer: &'a str,
+    message: &'a str,
+}
+
+/// Writes all problems found during the build as JSON, grouped by file
+pub fn write_build_report(report: &Report, path: impl AsRef<Path>) -> Result<(), Error> {
+    let files = report
+        .by_file()
+        .into_iter()
+        .map(|(path, problems)| FileReport {
+            path,
+            problems: problems
+                .into_iter()
+                .map(|problem| FileProblem {
+                    severity: problem.severity,
+                    pointer: &problem.pointer,
+                    message: &problem.message,
+                })
+                .collect(),
+        })
+        .collect();
+    let build_report = BuildReport {
+        fatal: report.count(Severity::Fatal),
+        errors: report.count(Severity::Error),
+        warnings: report.count(Severity::Warning),
+        files,
+    };
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, &build_report)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Prints all problems found during the build, grouped by file
+pub fn print_build_report(report: &Report) {
+    for (path, problems) in report.by_file() {
+        println!("{}", path.display());
+        for problem in problems {
+            if problem.pointer.is_empty() {
+                println!("  {}: {}", problem.severity, problem.message);
+            } else {
+                println!(
+                    "  {}: {}: {}",
+                    problem.severity, problem.pointer, problem.message
+                );
+            }
+        }
+    }
+    println!(
+        "{} fatal, {} errors, {} warnings",
+        report.count(Severity::Fatal),
+        report.count(Severity::Error),
+        report.count(Severity::Warning)
+    );
+}
@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: MIT
 
 use anyhow::Error;
+use gbhwdb_backend::{
+    config::cartridge::GameConfig,
+    db::{Category, Database, Submission, SubmissionChip},
+};
 use std::{borrow::Cow, io, marker::PhantomData};
 
 use crate::legacy::{HasDateCode, LegacyChip, LegacySubmission};
@@ -54,6 +58,73 @@ where
     Ok(())
 }
 
+struct ChipRow<'a> {
+    submission: &'a Submission,
+    chip: &'a SubmissionChip,
+    game: Option<&'a GameConfig>,
+}
+
+impl<'a> ChipRow<'a> {
+    /// Site directory and submission directory, e.g. `("consoles", "dmg")`
+    fn dirs(&self) -> (&'static str, &'a str) {
+        match self.submission.category {
+            Category::Cartridge => ("cartridges", &self.submission.code),
+            Category::Console(console) => ("consoles", console.id()),
+        }
+    }
+}
+
+/// Writes one row per chip of every console and cartridge submission
+pub fn write_chip_csv<W>(writer: W, url_prefix: &'static str, db: &Database) -> Result<(), Error>
+where
+    W: io::Write,
+{
+    let mut w = csv::Writer::from_writer(writer);
+
+    let builder = Builder::<ChipRow>::new()
+        .add("submission", |r| {
+            format!("{}/{}", r.dirs().1, r.submission.slug).csv()
+        })
+        .add("category", |r| r.submission.category.id().into())
+        .add("code", |r| (&r.submission.code).csv())
+        .add("game", |r| r.game.map(|cfg| cfg.name.as_str()).csv())
+        .add("url", move |r| {
+            let (page_dir, dir) = r.dirs();
+            format!("{url_prefix}/{page_dir}/{dir}/{}.html", r.submission.slug).csv()
+        })
+        .add("board", |r| (&r.submission.board).csv())
+        .add("designator", |r| r.chip.designator.into())
+        .add("role", |r| r.chip.role.into())
+        .add("label", |r| (&r.chip.chip.label).csv())
+        .add("kind", |r| (&r.chip.chip.kind).csv())
+        .add("manufacturer", |r| {
+            r.chip.chip.manufacturer.map(|m| m.name()).csv()
+        })
+        .add("year", |r| r.chip.chip.year.csv())
+        .add("month", |r| r.chip.chip.month.csv())
+        .add("week", |r| r.chip.chip.week.csv())
+        .add("outlier", |r| r.chip.chip.outlier.to_string().csv());
+
+    w.write_record(builder.fields())?;
+    for submission in &db.submissions {
+        let game = match submission.category {
+            Category::Cartridge => db.cfgs.get(&submission.code),
+            Category::Console(_) => None,
+        };
+        for chip in &submission.chips {
+            let row = ChipRow {
+                submission,
+                chip,
+                game,
+            };
+            w.write_record(builder.record(&row))?;
+        }
+    }
+    w.flush()?;
+
+    Ok(())
+}
+
 fn chip() -> Builder<LegacyChip> {
     Builder::<LegacyChip>::new()
         .add("kind", |c| (&c.kind).csv())
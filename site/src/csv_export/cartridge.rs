@@ -30,5 +30,16 @@ impl ToCsv for LegacyMetadata {
             .nest("u6", |m| m.board.u6.as_ref(), chip)
             .nest("u7", |m| m.board.u7.as_ref(), chip)
             .nest("x1", |m| m.board.x1.as_ref(), chip)
+            .nest("bt1", |m| m.board.bt1.as_ref(), chip)
+            .add("repro_score", |m| m.repro.score().to_string().csv())
+            .add("repro_findings", |m| {
+                m.repro
+                    .findings
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+                    .csv()
+            })
     }
 }
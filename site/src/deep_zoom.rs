@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Deep Zoom (DZI) tile pyramids for high resolution photos.
+//!
+//! A pyramid consists of a `.dzi` descriptor and a `_files` directory next to it. Every level
+//! halves the size of the level above it, down to a single pixel at level 0, and each level is
+//! split into JPEG tiles named `{level}/{column}_{row}.jpg`.
+
+use anyhow::Error;
+use image::{imageops::FilterType, DynamicImage, ImageOutputFormat};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+const TILE_SIZE: u32 = 254;
+/// Number of extra pixels on each side of a tile shared with its neighbours, which hides seams
+/// when tiles are scaled
+const OVERLAP: u32 = 1;
+
+/// Returns the tile directory of a `.dzi` file, e.g. `photo_files` for `photo.dzi`
+pub fn tile_dir(dzi: &Path) -> PathBuf {
+    let stem = dzi.file_stem().unwrap_or_default().to_string_lossy();
+    dzi.with_file_name(format!("{stem}_files"))
+}
+
+/// Returns the (start, length) ranges of tiles along one axis of a level
+fn tile_ranges(size: u32) -> impl Iterator<Item = (u32, u32)> {
+    (0..size.div_ceil(TILE_SIZE)).map(move |index| {
+        let start = (index * TILE_SIZE).saturating_sub(OVERLAP);
+        let end = ((index + 1) * TILE_SIZE + OVERLAP).min(size);
+        (start, end - start)
+    })
+}
+
+/// Writes the tile pyramid of an image and its `.dzi` descriptor.
+///
+/// Old tiles are removed first, and the descriptor is written last, so an interrupted build
+/// leaves no descriptor behind.
+pub fn write_pyramid(img: &DynamicImage, dzi: &Path) -> Result<(), Error> {
+    let tile_dir = tile_dir(dzi);
+    if dzi.exists() {
+        fs::remove_file(dzi)?;
+    }
+    if tile_dir.exists() {
+        fs::remove_dir_all(&tile_dir)?;
+    }
+
+    let (width, height) = (img.width(), img.height());
+    let max_level = u32::BITS - (width.max(height).max(1) - 1).leading_zeros();
+    let mut level_img = DynamicImage::ImageRgb8(img.to_rgb8());
+    for level in (0..=max_level).rev() {
+        let scale = 1 << (max_level - level);
+        let (level_width, level_height) = (width.div_ceil(scale), height.div_ceil(scale));
+        if (level_img.width(), level_img.height()) != (level_width, level_height) {
+            // Each level is scaled from the one above it, which is much faster than scaling
+            // from the original every time
+            level_img = level_img.resize_exact(level_width, level_height, FilterType::Triangle);
+        }
+        let level_dir = tile_dir.join(level.to_string());
+        fs::create_dir_all(&level_dir)?;
+        for (column, (x, tile_width)) in tile_ranges(level_width).enumerate() {
+            for (row, (y, tile_height)) in tile_ranges(level_height).enumerate() {
+                let tile = level_img.crop_imm(x, y, tile_width, tile_height);
+                let path = level_dir.join(format!("{column}_{row}.jpg"));
+                let mut w = BufWriter::new(File::create(path)?);
+                tile.write_to(&mut w, ImageOutputFormat::Jpeg(80))?;
+                w.flush()?;
+            }
+        }
+    }
+
+    fs::write(
+        dzi,
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                r#"<Image xmlns="http://schemas.microsoft.com/deepzoom/2008" Format="jpg" Overlap="{overlap}" TileSize="{tile_size}">"#,
+                r#"<Size Width="{width}" Height="{height}"/>"#,
+                r#"</Image>"#,
+            ),
+            overlap = OVERLAP,
+            tile_size = TILE_SIZE,
+            width = width,
+            height = height,
+        ),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,504 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Minimal JPEG metadata handling for published photos.
+//!
+//! Photos taken with phones and cameras often contain GPS coordinates, camera serial numbers and
+//! timestamps in their EXIF data. Published photos keep only the orientation, which browsers need
+//! to display the photo correctly.
+
+use anyhow::{bail, Error};
+use image::DynamicImage;
+use std::{collections::BTreeSet, fmt, io::Read};
+
+const SOI: u8 = 0xd8;
+const SOS: u8 = 0xda;
+const APP0: u8 = 0xe0;
+const APP1: u8 = 0xe1;
+const APP13: u8 = 0xed;
+const COM: u8 = 0xfe;
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+
+/// Category of metadata that was removed from a photo
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Removed {
+    GpsLocation,
+    SerialNumber,
+    Owner,
+    CameraModel,
+    Timestamps,
+    MakerNote,
+    OtherExif,
+    Xmp,
+    Iptc,
+    Comment,
+}
+
+impl Removed {
+    fn from_tag(tag: u16) -> Removed {
+        match tag {
+            TAG_GPS_IFD => Removed::GpsLocation,
+            // BodySerialNumber, LensSerialNumber
+            0xa431 | 0xa435 => Removed::SerialNumber,
+            // Artist, Copyright, CameraOwnerName
+            0x013b | 0x8298 | 0xa430 => Removed::Owner,
+            // Make, Model, LensMake, LensModel
+            0x010f | 0x0110 | 0xa433 | 0xa434 => Removed::CameraModel,
+            // DateTime, DateTimeOriginal, DateTimeDigitized and their subseconds and offsets
+            0x0132 | 0x9003 | 0x9004 | 0x9010..=0x9012 | 0x9290..=0x9292 => Removed::Timestamps,
+            0x927c => Removed::MakerNote,
+            _ => Removed::OtherExif,
+        }
+    }
+}
+
+impl fmt::Display for Removed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Removed::GpsLocation => "GPS location",
+            Removed::SerialNumber => "serial numbers",
+            Removed::Owner => "owner",
+            Removed::CameraModel => "camera model",
+            Removed::Timestamps => "timestamps",
+            Removed::MakerNote => "maker note",
+            Removed::OtherExif => "other EXIF data",
+            Removed::Xmp => "XMP",
+            Removed::Iptc => "IPTC",
+            Removed::Comment => "comment",
+        })
+    }
+}
+
+/// A JPEG file with its metadata removed
+#[derive(Clone, Debug)]
+pub struct Stripped {
+    pub data: Vec<u8>,
+    /// EXIF orientation (1-8) of the original file, which is kept in the stripped file
+    pub orientation: Option<u16>,
+    pub removed: BTreeSet<Removed>,
+}
+
+/// JPEG marker and the segment payload without the length
+type Segment<'a> = (u8, &'a [u8]);
+
+/// Returns the JPEG segments before the image data, and the rest of the file starting from the
+/// start of scan marker
+fn segments(jpeg: &[u8]) -> Result<(Vec<Segment<'_>>, &[u8]), Error> {
+    if !jpeg.starts_with(&[0xff, SOI]) {
+        bail!("not a JPEG file");
+    }
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    loop {
+        // Markers may be preceded by any number of fill bytes
+        while jpeg.get(pos) == Some(&0xff) && jpeg.get(pos + 1) == Some(&0xff) {
+            pos += 1;
+        }
+        match jpeg.get(pos..pos + 2) {
+            Some(&[0xff, SOS]) => return Ok((segments, &jpeg[pos..])),
+            Some(&[0xff, marker]) => {
+                let len = match jpeg.get(pos + 2..pos + 4) {
+                    Some(&[hi, lo]) => usize::from(u16::from_be_bytes([hi, lo])),
+                    _ => bail!("truncated JPEG segment"),
+                };
+                let payload = match jpeg.get(pos + 4..pos + 2 + len) {
+                    Some(payload) if len >= 2 => payload,
+                    _ => bail!("truncated JPEG segment"),
+                };
+                segments.push((marker, payload));
+                pos += 2 + len;
+            }
+            _ => bail!("invalid JPEG marker at offset {pos}"),
+        }
+    }
+}
+
+/// Reader for the TIFF structure inside an EXIF segment
+struct Tiff<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Tiff<'a>> {
+        let big_endian = match data.get(0..4)? {
+            b"MM\0*" => true,
+            b"II*\0" => false,
+            _ => return None,
+        };
+        Some(Tiff { data, big_endian })
+    }
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+    /// Returns the (tag, value offset) pairs of an IFD
+    fn entries(&self, ifd: usize) -> Vec<(u16, usize)> {
+        let count = self.u16(ifd).unwrap_or(0);
+        (0..usize::from(count))
+            .map(|index| ifd + 2 + index * 12)
+            .map_while(|entry| Some((self.u16(entry)?, entry + 8)))
+            .collect()
+    }
+}
+
+/// Reads the orientation from an EXIF segment, and collects the kinds of data it contains
+fn parse_exif(exif: &[u8], removed: &mut BTreeSet<Removed>) -> Option<u16> {
+    let tiff = Tiff::new(exif.strip_prefix(EXIF_HEADER)?)?;
+    let mut orientation = None;
+    let mut ifds = vec![tiff.u32(4)? as usize];
+    let mut visited = Vec::new();
+    while let Some(ifd) = ifds.pop() {
+        // Malformed files may have loops
+        if visited.contains(&ifd) {
+            continue;
+        }
+        visited.push(ifd);
+        for (tag, value) in tiff.entries(ifd) {
+            match tag {
+                TAG_ORIENTATION => orientation = tiff.u16(value),
+                TAG_EXIF_IFD => ifds.extend(tiff.u32(value).map(|offset| offset as usize)),
+                _ => {
+                    removed.insert(Removed::from_tag(tag));
+                }
+            }
+        }
+    }
+    orientation.filter(|orientation| (1..=8).contains(orientation))
+}
+
+/// Builds an EXIF segment payload that only contains the orientation
+fn orientation_exif(orientation: u16) -> Vec<u8> {
+    let mut exif = EXIF_HEADER.to_vec();
+    // Big-endian TIFF header with IFD0 right after it
+    exif.extend_from_slice(b"MM\0*\0\0\0\x08");
+    exif.extend_from_slice(&1u16.to_be_bytes());
+    // Tag, type SHORT, count 1, value padded to 4 bytes
+    exif.extend_from_slice(&TAG_ORIENTATION.to_be_bytes());
+    exif.extend_from_slice(&3u16.to_be_bytes());
+    exif.extend_from_slice(&1u32.to_be_bytes());
+    exif.extend_from_slice(&orientation.to_be_bytes());
+    exif.extend_from_slice(&[0, 0]);
+    // No next IFD
+    exif.extend_from_slice(&0u32.to_be_bytes());
+    exif
+}
+
+/// Removes EXIF, XMP, IPTC and comment segments from a JPEG file without recompressing it.
+///
+/// The EXIF orientation is kept if it's something else than the default.
+pub fn strip_metadata(jpeg: &[u8]) -> Result<Stripped, Error> {
+    let (segments, image_data) = segments(jpeg)?;
+    let mut removed = BTreeSet::new();
+    let mut orientation = None;
+    let mut kept = Vec::new();
+    for (marker, payload) in segments {
+        match marker {
+            APP1 if payload.starts_with(EXIF_HEADER) => {
+                orientation = orientation.or(parse_exif(payload, &mut removed));
+            }
+            APP1 => {
+                removed.insert(Removed::Xmp);
+            }
+            APP13 => {
+                removed.insert(Removed::Iptc);
+            }
+            COM => {
+                removed.insert(Removed::Comment);
+            }
+            _ => kept.push((marker, payload)),
+        }
+    }
+
+    let mut data = Vec::with_capacity(jpeg.len());
+    data.extend_from_slice(&[0xff, SOI]);
+    let mut kept = kept.into_iter().peekable();
+    // EXIF must come right after the JFIF segment if there is one
+    if let Some(&(APP0, payload)) = kept.peek() {
+        write_segment(&mut data, APP0, payload);
+        kept.next();
+    }
+    if let Some(orientation) = orientation.filter(|&orientation| orientation != 1) {
+        write_segment(&mut data, APP1, &orientation_exif(orientation));
+    }
+    for (marker, payload) in kept {
+        write_segment(&mut data, marker, payload);
+    }
+    data.extend_from_slice(image_data);
+    Ok(Stripped {
+        data,
+        orientation,
+        removed,
+    })
+}
+
+fn write_segment(data: &mut Vec<u8>, marker: u8, payload: &[u8]) {
+    data.extend_from_slice(&[0xff, marker]);
+    data.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+    data.extend_from_slice(payload);
+}
+
+/// Reads the beginning of a JPEG file up to and including the start of scan marker.
+///
+/// The result has all metadata segments but no image data, so it can be passed to
+/// `read_orientation` without reading the whole file.
+pub fn read_header(mut r: impl Read) -> Result<Vec<u8>, Error> {
+    let mut header = vec![0; 2];
+    r.read_exact(&mut header)?;
+    if header != [0xff, SOI] {
+        bail!("not a JPEG file");
+    }
+    loop {
+        let mut marker = [0; 2];
+        r.read_exact(&mut marker)?;
+        // Markers may be preceded by any number of fill bytes
+        while marker == [0xff, 0xff] {
+            r.read_exact(&mut marker[1..])?;
+        }
+        header.extend_from_slice(&marker);
+        match marker {
+            [0xff, SOS] => return Ok(header),
+            [0xff, _] => {
+                let mut len = [0; 2];
+                r.read_exact(&mut len)?;
+                header.extend_from_slice(&len);
+                let len = usize::from(u16::from_be_bytes(len));
+                if len < 2 {
+                    bail!("truncated JPEG segment");
+                }
+                let start = header.len();
+                header.resize(start + len - 2, 0);
+                r.read_exact(&mut header[start..])?;
+            }
+            _ => bail!("invalid JPEG marker at offset {}", header.len() - 2),
+        }
+    }
+}
+
+/// Reads the EXIF orientation (1-8) of a JPEG file
+pub fn read_orientation(jpeg: &[u8]) -> Option<u16> {
+    let (segments, _) = segments(jpeg).ok()?;
+    segments
+        .into_iter()
+        .filter(|&(marker, payload)| marker == APP1 && payload.starts_with(EXIF_HEADER))
+        .find_map(|(_, payload)| parse_exif(payload, &mut BTreeSet::new()))
+}
+
+/// Rotates and flips an image so that it's displayed upright without the EXIF orientation
+pub fn apply_orientation(img: DynamicImage, orientation: Option<u16>) -> DynamicImage {
+    match orientation {
+        Some(2) => img.fliph(),
+        Some(3) => img.rotate180(),
+        Some(4) => img.flipv(),
+        Some(5) => img.rotate90().fliph(),
+        Some(6) => img.rotate90(),
+        Some(7) => img.rotate270().fliph(),
+        Some(8) => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// Builds an EXIF segment payload with the given IFDs. EXIF and GPS IFD pointers refer to the
+/// index of another IFD in the list
+#[cfg(test)]
+fn test_exif(big_endian: bool, ifds: &[&[(u16, u32)]]) -> Vec<u8> {
+    let u16_bytes = |value: u16| {
+        if big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        }
+    };
+    let u32_bytes = |value: u32| {
+        if big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        }
+    };
+    let mut offsets = Vec::new();
+    let mut offset = 8;
+    for ifd in ifds {
+        offsets.push(offset as u32);
+        offset += 2 + ifd.len() * 12 + 4;
+    }
+    let mut exif = EXIF_HEADER.to_vec();
+    exif.extend_from_slice(if big_endian { b"MM\0*" } else { b"II*\0" });
+    exif.extend_from_slice(&u32_bytes(8));
+    for ifd in ifds {
+        exif.extend_from_slice(&u16_bytes(ifd.len() as u16));
+        for &(tag, value) in ifd.iter() {
+            exif.extend_from_slice(&u16_bytes(tag));
+            match tag {
+                TAG_ORIENTATION => {
+                    exif.extend_from_slice(&u16_bytes(3));
+                    exif.extend_from_slice(&u32_bytes(1));
+                    exif.extend_from_slice(&u16_bytes(value as u16));
+                    exif.extend_from_slice(&[0, 0]);
+                }
+                TAG_EXIF_IFD | TAG_GPS_IFD => {
+                    exif.extend_from_slice(&u16_bytes(4));
+                    exif.extend_from_slice(&u32_bytes(1));
+                    exif.extend_from_slice(&u32_bytes(offsets[value as usize]));
+                }
+                _ => {
+                    exif.extend_from_slice(&u16_bytes(4));
+                    exif.extend_from_slice(&u32_bytes(1));
+                    exif.extend_from_slice(&u32_bytes(value));
+                }
+            }
+        }
+        exif.extend_from_slice(&u32_bytes(0));
+    }
+    exif
+}
+
+/// Builds a JPEG file from segments, followed by a dummy start of scan
+#[cfg(test)]
+fn test_jpeg(segments: &[(u8, &[u8])]) -> Vec<u8> {
+    let mut jpeg = vec![0xff, SOI];
+    for &(marker, payload) in segments {
+        write_segment(&mut jpeg, marker, payload);
+    }
+    jpeg.extend_from_slice(&[0xff, SOS, 0x00, 0x02, 0x12, 0x34, 0xff, 0xd9]);
+    jpeg
+}
+
+#[test]
+fn test_strip_metadata() {
+    let jfif = b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0";
+    let xmp = b"http://ns.adobe.com/xap/1.0/\0<x:xmpmeta/>";
+    for big_endian in [false, true] {
+        let exif = test_exif(
+            big_endian,
+            &[
+                &[
+                    (0x010f, 0),
+                    (TAG_ORIENTATION, 6),
+                    (TAG_EXIF_IFD, 1),
+                    (TAG_GPS_IFD, 2),
+                ],
+                &[(0xa431, 0)],
+                &[(0x0001, 0)],
+            ],
+        );
+        let jpeg = test_jpeg(&[(APP0, jfif), (APP1, &exif), (APP1, xmp), (COM, b"test")]);
+        assert_eq!(read_orientation(&jpeg), Some(6));
+        let header = read_header(jpeg.as_slice()).unwrap();
+        assert_eq!(header, jpeg[..jpeg.len() - 6]);
+        assert_eq!(read_orientation(&header), Some(6));
+
+        let stripped = strip_metadata(&jpeg).unwrap();
+        assert_eq!(stripped.orientation, Some(6));
+        assert_eq!(
+            stripped.removed.into_iter().collect::<Vec<_>>(),
+            [
+                Removed::GpsLocation,
+                Removed::SerialNumber,
+                Removed::CameraModel,
+                Removed::Xmp,
+                Removed::Comment
+            ]
+        );
+        assert_eq!(
+            stripped.data,
+            test_jpeg(&[(APP0, jfif), (APP1, &orientation_exif(6))])
+        );
+        assert_eq!(read_orientation(&stripped.data), Some(6));
+        let again = strip_metadata(&stripped.data).unwrap();
+        assert!(again.removed.is_empty());
+        assert_eq!(again.data, stripped.data);
+    }
+}
+
+#[test]
+fn test_strip_metadata_default_orientation() {
+    let exif = test_exif(false, &[&[(TAG_ORIENTATION, 1), (0x0132, 0)]]);
+    let stripped = strip_metadata(&test_jpeg(&[(APP1, &exif)])).unwrap();
+    assert_eq!(stripped.orientation, Some(1));
+    assert_eq!(stripped.data, test_jpeg(&[]));
+}
+
+#[test]
+fn test_looping_ifd() {
+    // IFD0 and the EXIF IFD point to each other, and the EXIF IFD also points to itself
+    let exif = test_exif(
+        true,
+        &[
+            &[(TAG_ORIENTATION, 8), (TAG_EXIF_IFD, 1)],
+            &[(TAG_EXIF_IFD, 0), (TAG_EXIF_IFD, 1)],
+        ],
+    );
+    assert_eq!(read_orientation(&test_jpeg(&[(APP1, &exif)])), Some(8));
+}
+
+#[test]
+fn test_truncated() {
+    // Segment length beyond the end of the file
+    assert!(strip_metadata(&[0xff, SOI, 0xff, APP1, 0x00, 0x10, b'E', b'x']).is_err());
+    // Segment length smaller than the length field itself
+    assert!(strip_metadata(&[0xff, SOI, 0xff, APP0, 0x00, 0x01, 0xff, SOS]).is_err());
+    // Missing segment length
+    assert!(strip_metadata(&[0xff, SOI, 0xff, APP0, 0x00]).is_err());
+    assert!(strip_metadata(&[0xff, SOI]).is_err());
+    assert!(strip_metadata(b"not a JPEG").is_err());
+    assert!(read_header(&[0xff, SOI, 0xff, APP1, 0x00, 0x10, b'E', b'x'][..]).is_err());
+    assert!(read_header(&[0xff, SOI, 0xff, APP0, 0x00, 0x01, 0xff, SOS][..]).is_err());
+    assert!(read_header(&b"not a JPEG"[..]).is_err());
+
+    // Truncated TIFF structure and IFD offsets beyond the end of the segment
+    let mut exif = test_exif(false, &[&[(TAG_ORIENTATION, 6), (TAG_EXIF_IFD, 0)]]);
+    exif.truncate(exif.len() - 20);
+    assert_eq!(read_orientation(&test_jpeg(&[(APP1, &exif)])), None);
+    let exif = [EXIF_HEADER, b"II*\0\xff\xff\xff\xff"].concat();
+    let stripped = strip_metadata(&test_jpeg(&[(APP1, &exif)])).unwrap();
+    assert_eq!(stripped.orientation, None);
+}
+
+#[test]
+fn test_apply_orientation() {
+    use image::{GenericImageView, RgbImage};
+
+    // 3x2 image where every pixel has a different value
+    let img = DynamicImage::ImageRgb8(RgbImage::from_fn(3, 2, |x, y| {
+        image::Rgb([x as u8, y as u8, 0])
+    }));
+    let pixel = |img: &DynamicImage, x, y| {
+        let [r, g, _, _] = img.get_pixel(x, y).0;
+        (r as u32, g as u32)
+    };
+
+    // Transpose: (x, y) moves to (y, x)
+    let transposed = apply_orientation(img.clone(), Some(5));
+    assert_eq!(transposed.dimensions(), (2, 3));
+    for (x, y) in [(0, 0), (2, 0), (1, 1), (2, 1)] {
+        assert_eq!(pixel(&transposed, y, x), (x, y));
+    }
+    // Transverse: (x, y) moves to (height - 1 - y, width - 1 - x)
+    let transversed = apply_orientation(img.clone(), Some(7));
+    assert_eq!(transversed.dimensions(), (2, 3));
+    for (x, y) in [(0, 0), (2, 0), (1, 1), (2, 1)] {
+        assert_eq!(pixel(&transversed, 1 - y, 2 - x), (x, y));
+    }
+
+    assert_eq!(apply_orientation(img.clone(), None), img);
+    assert_eq!(apply_orientation(img.clone(), Some(1)), img);
+}
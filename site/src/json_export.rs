@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use gbhwdb_backend::db::{export::export, Database};
+use serde::Serialize;
+use std::{
+    fs::{create_dir_all, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+pub const SITE_URL: &str = "https://gbhwdb.gekkio.fi";
+
+/// Writes the versioned JSON export (see `gbhwdb_backend::db::export`) to the target directory
+pub fn write_json_export(db: &Database, target_dir: impl AsRef<Path>) -> Result<(), Error> {
+    let target_dir = target_dir.as_ref();
+    let export = export(db, SITE_URL);
+    for (path, file) in &export.files {
+        write_json(&target_dir.join(path), file)?;
+    }
+    write_json(&target_dir.join("index.json"), &export.index)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    let mut w = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut w, value)?;
+    w.flush()?;
+    Ok(())
+}
@@ -4,7 +4,7 @@
 
 use gbhwdb_backend::{
     parser::*,
-    time::{Jun, Month, Week},
+    time::{guess_full_year, Jun, Month, Week},
 };
 use serde::{Deserialize, Serialize};
 
@@ -106,6 +106,14 @@ pub trait LegacyPhotos: 'static {
     fn front(&self) -> Option<&LegacyPhoto>;
     fn infos() -> Vec<PhotoInfo<Self>>;
     fn photos(&self) -> Vec<&LegacyPhoto>;
+    fn photos_mut(&mut self) -> Vec<&mut LegacyPhoto>;
+}
+
+/// Photos of a console submission
+pub trait LegacyConsolePhotos: LegacyPhotos + Sized {
+    /// Builds the photos from files that are listed in the same order as in
+    /// `ConsolePhotoSet::files`
+    fn from_files(files: Vec<Option<LegacyPhoto>>) -> Self;
 }
 
 pub struct PhotoInfo<P: ?Sized> {
@@ -147,6 +155,17 @@ pub struct LegacyChip {
     pub rom_code: Option<String>,
 }
 
+impl LegacyChip {
+    /// Chip whose label couldn't be parsed
+    pub fn unparsed(label: &str) -> LegacyChip {
+        LegacyChip {
+            kind: Some("unparsed".to_owned()),
+            label: Some(label.to_owned()),
+            ..LegacyChip::default()
+        }
+    }
+}
+
 impl HasDateCode for LegacyChip {
     fn date_code(&self) -> DateCode {
         DateCode {
@@ -173,6 +192,19 @@ pub struct LegacyDefaultPhotos {
     pub pcb_back: Option<LegacyPhoto>,
 }
 
+impl LegacyConsolePhotos for LegacyDefaultPhotos {
+    fn from_files(files: Vec<Option<LegacyPhoto>>) -> Self {
+        let [front, back, pcb_front, pcb_back]: [Option<LegacyPhoto>; 4] =
+            files.try_into().expect("Unexpected number of photo files");
+        LegacyDefaultPhotos {
+            front,
+            back,
+            pcb_front,
+            pcb_back,
+        }
+    }
+}
+
 impl LegacyPhotos for LegacyDefaultPhotos {
     fn infos() -> Vec<PhotoInfo<Self>> {
         vec![
@@ -201,6 +233,18 @@ impl LegacyPhotos for LegacyDefaultPhotos {
             .filter_map(|photo| photo.as_ref())
             .collect()
     }
+
+    fn photos_mut(&mut self) -> Vec<&mut LegacyPhoto> {
+        [
+            &mut self.front,
+            &mut self.back,
+            &mut self.pcb_front,
+            &mut self.pcb_back,
+        ]
+        .into_iter()
+        .filter_map(|photo| photo.as_mut())
+        .collect()
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -208,6 +252,35 @@ impl LegacyPhotos for LegacyDefaultPhotos {
 pub struct LegacyPhoto {
     pub path: String,
     pub name: String,
+    /// Widths of the scaled down versions in ascending order, filled in when photos are processed
+    #[serde(skip)]
+    pub widths: Vec<u32>,
+    /// True if a Deep Zoom tile pyramid is generated for the photo
+    #[serde(skip)]
+    pub deep_zoom: bool,
+}
+
+impl LegacyPhoto {
+    fn stem(&self) -> &str {
+        self.name
+            .rsplit_once('.')
+            .map_or(self.name.as_str(), |(stem, _)| stem)
+    }
+    /// Returns the file name of a scaled down version, e.g. `01_front_640w.webp`
+    pub fn scaled_name(&self, width: u32, extension: &str) -> String {
+        format!("{stem}_{width}w.{extension}", stem = self.stem())
+    }
+    /// Returns the file name of the Deep Zoom descriptor, e.g. `03_mainboard_front.dzi`
+    pub fn deep_zoom_name(&self) -> String {
+        format!("{stem}.dzi", stem = self.stem())
+    }
+    /// Returns the file name of the largest scaled down JPEG, or the original if there are none
+    pub fn preview_name(&self) -> String {
+        match self.widths.last() {
+            Some(&width) => self.scaled_name(width, "jpg"),
+            None => self.name.clone(),
+        }
+    }
 }
 
 pub fn to_legacy_manufacturer(manufacturer: Option<Manufacturer>) -> Option<String> {
@@ -220,27 +293,5 @@ pub fn to_legacy_year(year_hint: Option<u16>, chip_year: Option<Year>) -> Option
         (Some(year_hint), Some(Year::Partial(year))) => Some(guess_full_year(year_hint, year)),
         _ => None,
     })
-    .map(|year| {
-        assert!(year >= 1988 && year < 2010);
-        year
-    })
-}
-
-pub fn guess_full_year(hint: u16, partial_year: u8) -> u16 {
-    let partial_year = u16::from(partial_year);
-    let decades = [1980, 1990, 2000];
-    decades
-        .into_iter()
-        .map(|decade: u16| decade + partial_year)
-        .min_by_key(|&year| hint.abs_diff(year))
-        .unwrap_or(0)
-}
-
-#[test]
-fn test_guess_full_year() {
-    assert_eq!(1992, guess_full_year(1992, 2));
-    assert_eq!(1989, guess_full_year(1989, 9));
-    assert_eq!(1990, guess_full_year(1990, 0));
-    assert_eq!(1999, guess_full_year(1998, 9));
-    assert_eq!(2000, guess_full_year(2005, 0));
+    .filter(|year| (1988..2010).contains(year))
 }
@@ -3,15 +3,13 @@
 // SPDX-License-Identifier: MIT
 
 use gbhwdb_backend::{
-    config::cartridge::*,
-    input::{cartridge::*, Chip},
-    parser::*,
-    time::Month,
+    analysis::repro::ReproReport, config::cartridge::*, db::chip::cartridge_chips,
+    input::cartridge::*, parser::*, time::Month,
 };
 use serde::Serialize;
 use std::ops::{Index, IndexMut};
 
-use super::{to_legacy_manufacturer, to_legacy_year, DateCode, HasDateCode, LegacyChip};
+use super::{DateCode, HasDateCode, LegacyChip};
 
 #[derive(Clone, Debug, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -24,6 +22,8 @@ pub struct LegacyMetadata {
     pub stamp: Option<String>,
     pub board: LegacyBoard,
     pub dump: Option<CartridgeDump>,
+    #[serde(skip)]
+    pub repro: ReproReport,
 }
 
 impl super::LegacyMetadata for LegacyMetadata {}
@@ -33,6 +33,8 @@ impl super::LegacyMetadata for LegacyMetadata {}
 pub struct LegacyBoard {
     #[serde(skip)]
     pub layout: BoardLayout,
+    #[serde(skip)]
+    pub mapper: Option<MapperType>,
     #[serde(rename = "type")]
     pub kind: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -59,6 +61,8 @@ pub struct LegacyBoard {
     pub u7: Option<LegacyChip>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x1: Option<LegacyChip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bt1: Option<LegacyChip>,
 }
 
 impl Index<PartDesignator> for LegacyBoard {
@@ -74,6 +78,7 @@ impl Index<PartDesignator> for LegacyBoard {
             PartDesignator::U6 => &self.u6,
             PartDesignator::U7 => &self.u7,
             PartDesignator::X1 => &self.x1,
+            PartDesignator::Bt1 => &self.bt1,
         }
     }
 }
@@ -89,6 +94,7 @@ impl IndexMut<PartDesignator> for LegacyBoard {
             PartDesignator::U6 => &mut self.u6,
             PartDesignator::U7 => &mut self.u7,
             PartDesignator::X1 => &mut self.x1,
+            PartDesignator::Bt1 => &mut self.bt1,
         }
     }
 }
@@ -104,164 +110,31 @@ impl HasDateCode for LegacyBoard {
     }
 }
 
-pub fn add_legacy_chips(layout: BoardLayout, board: CartridgeBoard, legacy: &mut LegacyBoard) {
-    let roles = ChipRoleConfig::from(layout);
-    for (designator, role) in roles.iter() {
-        legacy[designator] =
-            to_legacy_chip(layout, board.year, Some(role), board[designator].as_ref());
-    }
-}
-
-pub fn to_legacy_chip(
+pub fn add_legacy_chips(
+    cfg: &GameConfig,
     layout: BoardLayout,
-    board_year: Option<u16>,
-    role: Option<ChipRole>,
-    chip: Option<&Chip>,
-) -> Option<LegacyChip> {
-    if role == None {
-        assert_eq!(chip, None);
+    board: &CartridgeBoard,
+    legacy: &mut LegacyBoard,
+) {
+    for (designator, _, chip) in cartridge_chips(cfg, layout, board) {
+        legacy[designator] = Some(chip.map(LegacyChip::from).unwrap_or_else(|_| {
+            let label = board[designator]
+                .as_ref()
+                .and_then(|chip| chip.label.as_deref());
+            LegacyChip::unparsed(label.unwrap_or_default())
+        }));
     }
-    chip.map(|chip| {
-        let mut legacy = LegacyChip {
-            kind: None,
-            label: chip.label.clone(),
-            manufacturer: None,
-            year: None,
-            month: None,
-            week: None,
-            rom_code: None,
-        };
-        if let Some(label) = chip.label.as_ref() {
-            if let Some(role) = role {
-                match role {
-                    ChipRole::Rom => {
-                        if layout == BoardLayout::Tama {
-                            let chip = gbhwdb_backend::parser::tama::tama()
-                                .parse(&label)
-                                .unwrap_or_else(|_| panic!("{}", label));
-                            legacy.kind = Some("TAMA7".to_owned());
-                            legacy.year = to_legacy_year(board_year, chip.year);
-                            legacy.week = chip.week;
-                        } else {
-                            let chip = gbhwdb_backend::parser::mask_rom::mask_rom()
-                                .parse(&label)
-                                .unwrap_or_else(|_| panic!("{}", label));
-                            legacy.kind = chip.chip_type;
-                            legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                            legacy.year = to_legacy_year(board_year, chip.year);
-                            legacy.week = chip.week;
-                        }
-                    }
-                    ChipRole::Mapper => {
-                        if layout == BoardLayout::Tama {
-                            let chip = gbhwdb_backend::parser::tama::tama()
-                                .parse(&label)
-                                .unwrap_or_else(|_| panic!("{}", label));
-                            legacy.kind = Some("TAMA5".to_owned());
-                            legacy.year = to_legacy_year(board_year, chip.year);
-                            legacy.week = chip.week;
-                        } else {
-                            let chip = gbhwdb_backend::parser::mapper::mapper()
-                                .parse(&label)
-                                .unwrap_or_else(|_| panic!("{}", label));
-                            legacy.kind = Some(chip.mbc_type.display_name().to_owned());
-                            legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                            legacy.year = to_legacy_year(board_year, chip.year);
-                            legacy.week = chip.week;
-                        }
-                    }
-                    ChipRole::Ram => {
-                        let chip = gbhwdb_backend::parser::ram::ram()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::SupervisorReset => {
-                        let chip = gbhwdb_backend::parser::supervisor_reset::supervisor_reset()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.chip_type);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::Crystal => {
-                        let chip = gbhwdb_backend::parser::crystal_32kihz::crystal_32kihz()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.format_frequency());
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.month = chip.month;
-                    }
-                    ChipRole::Flash => {
-                        let chip = gbhwdb_backend::parser::flash::flash()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::Eeprom => {
-                        let chip = gbhwdb_backend::parser::eeprom::eeprom()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::Accelerometer => {
-                        let chip = gbhwdb_backend::parser::accelerometer::accelerometer()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::LineDecoder => {
-                        let chip = gbhwdb_backend::parser::line_decoder::line_decoder()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                    }
-                    ChipRole::HexInverter => {
-                        let chip = gbhwdb_backend::parser::hex_inverter::hex_inverter()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::Mcu => {
-                        let chip = gbhwdb_backend::parser::tama::tama()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some("TAMA6".to_owned());
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::Rtc => {
-                        let chip = gbhwdb_backend::parser::rtc::rtc()
-                            .parse(&label)
-                            .unwrap_or_else(|_| panic!("{}", label));
-                        legacy.kind = Some(chip.kind);
-                        legacy.manufacturer = to_legacy_manufacturer(chip.manufacturer);
-                        legacy.year = to_legacy_year(board_year, chip.year);
-                        legacy.week = chip.week;
-                    }
-                    ChipRole::Unknown => (),
-                }
-            }
-        }
-        legacy
-    })
+    // The game config is only a fallback for mappers that can't be identified from the labels
+    legacy.mapper = if layout == BoardLayout::Tama {
+        Some(MapperType::Tama5)
+    } else {
+        ChipRoleConfig::from(layout)
+            .iter()
+            .find(|&(_, role)| role == ChipRole::Mapper)
+            .and_then(|(designator, _)| board[designator].as_ref())
+            .and_then(|chip| chip.label.as_deref())
+            .and_then(|label| gbhwdb_backend::parser::mapper::mapper().parse(label).ok())
+            .map(|chip| chip.mbc_type)
+            .or(cfg.mapper)
+    };
 }
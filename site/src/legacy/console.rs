@@ -3,18 +3,28 @@
 // SPDX-License-Identifier: MIT
 
 use gbhwdb_backend::{
-    input::{LcdChip, LcdScreen},
+    config::{
+        console::{
+            console_board_chips, default_console_board_chips, ConsoleChip, ConsoleChipRole,
+            ConsoleDesignator,
+        },
+        mainboard::{mainboard_revision, Deviation, MainboardRevision},
+    },
+    input::{console::ConsoleSubmission, Chip, LcdChip, LcdScreen},
     parser::LabelParser,
     time::{Jun, Month, Week},
     Console,
 };
 use serde::Serialize;
+use std::ops::Index;
 
 use super::{
-    to_legacy_year, DateCode, HasDateCode, LegacyChip, LegacyMetadata, LegacyPhoto, LegacyPhotos,
-    PhotoInfo, PhotoKind,
+    chip::map_console_chip, to_legacy_year, DateCode, HasDateCode, LegacyChip, LegacyConsolePhotos,
+    LegacyMetadata, LegacyPhoto, LegacyPhotos, PhotoInfo, PhotoKind,
 };
 
+mod submission;
+
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct LegacyMainboard<'a> {
     pub kind: &'a str,
@@ -39,7 +49,26 @@ pub struct LegacyConsoleShell<'a> {
 pub trait LegacyConsoleMetadata: LegacyMetadata {
     const CONSOLE: Console;
 
-    fn chips() -> Vec<ChipInfo<Self>>;
+    fn chips() -> Vec<ChipInfo<Self>> {
+        default_console_board_chips(Self::CONSOLE)
+            .iter()
+            .map(ChipInfo::from_config)
+            .collect()
+    }
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip>;
+    fn mainboard_revision(&self) -> Option<&'static MainboardRevision> {
+        mainboard_revision(Self::CONSOLE, self.mainboard().kind)
+    }
+    fn mainboard_deviations(&self) -> Vec<Deviation> {
+        self.mainboard_revision()
+            .map(|revision| {
+                revision.check(|designator| {
+                    self.mainboard_chip(designator)
+                        .and_then(|chip| chip.kind.as_deref())
+                })
+            })
+            .unwrap_or_default()
+    }
     fn shell(&self) -> LegacyConsoleShell;
     fn mainboard(&self) -> LegacyMainboard;
     fn lcd_panel(&self) -> Option<&LegacyLcdPanel> {
@@ -54,21 +83,73 @@ pub struct ChipInfo<M: ?Sized> {
     pub getter: Box<dyn Fn(&M) -> Option<&LegacyChip>>,
 }
 
-impl<M: ?Sized> ChipInfo<M> {
-    pub fn new(
-        label: &'static str,
-        designator: &'static str,
-        getter: Box<dyn Fn(&M) -> Option<&LegacyChip>>,
-    ) -> Self {
+impl<M: LegacyConsoleMetadata + ?Sized> ChipInfo<M> {
+    pub fn from_config(chip: &ConsoleChip) -> Self {
+        let designator = chip.designator;
         ChipInfo {
-            label,
-            designator,
-            hide_type: false,
-            getter,
+            label: chip.role.display(),
+            designator: designator.as_str(),
+            hide_type: chip.role == ConsoleChipRole::Crystal,
+            getter: Box::new(move |m| m.mainboard_chip(designator)),
         }
     }
 }
 
+/// Chips of a console mainboard, parsed according to the board configuration
+pub struct LegacyBoardChips {
+    chips: Vec<(ConsoleChip, Option<LegacyChip>)>,
+}
+
+impl LegacyBoardChips {
+    pub fn new<B>(year_hint: Option<u16>, config: &[ConsoleChip], board: &B) -> Self
+    where
+        B: Index<ConsoleDesignator, Output = Option<Chip>>,
+    {
+        LegacyBoardChips {
+            chips: config
+                .iter()
+                .map(|&chip| {
+                    let parsed = match &board[chip.designator] {
+                        Some(input) if chip.in_blob && input.label.is_none() => Some(LegacyChip {
+                            kind: Some("blob".to_owned()),
+                            ..LegacyChip::default()
+                        }),
+                        input => map_console_chip(chip.parser, year_hint, input),
+                    };
+                    (chip, parsed)
+                })
+                .collect(),
+        }
+    }
+    pub fn from_submission<C: ConsoleSubmission>(year_hint: Option<u16>, console: &C) -> Self {
+        let config = console_board_chips(C::CONSOLE, console.mainboard_label());
+        LegacyBoardChips::new(year_hint, config, console.mainboard())
+    }
+    /// Takes the first chip with the given role
+    pub fn take_role(&mut self, role: ConsoleChipRole) -> Option<LegacyChip> {
+        self.chips
+            .iter_mut()
+            .find(|(chip, _)| chip.role == role)
+            .and_then(|(_, parsed)| parsed.take())
+    }
+    pub fn take(&mut self, designator: ConsoleDesignator) -> Option<LegacyChip> {
+        self.chips
+            .iter_mut()
+            .find(|(chip, _)| chip.designator == designator)
+            .and_then(|(_, parsed)| parsed.take())
+    }
+}
+
+/// Legacy metadata that can be built from a console submission
+pub trait FromConsoleSubmission: LegacyConsoleMetadata + Sized {
+    type Submission: ConsoleSubmission;
+
+    fn from_submission(console: &Self::Submission, chips: LegacyBoardChips) -> Self;
+    fn sort_group(_console: &Self::Submission) -> Option<String> {
+        None
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct LegacyDmgPhotos {
@@ -104,6 +185,26 @@ pub struct LegacyDmgPhotos {
     pub jack_board_back: Option<LegacyPhoto>,
 }
 
+impl LegacyConsolePhotos for LegacyDmgPhotos {
+    fn from_files(files: Vec<Option<LegacyPhoto>>) -> Self {
+        let [front, back, mainboard_front, mainboard_back, lcd_board_front, lcd_board_back, power_board_front, power_board_back, jack_board_front, jack_board_back]: [Option<LegacyPhoto>; 10] =
+            files.try_into().expect("Unexpected number of photo files");
+        LegacyDmgPhotos {
+            front,
+            top: None,
+            back,
+            mainboard_front,
+            mainboard_back,
+            lcd_board_front,
+            lcd_board_back,
+            power_board_front,
+            power_board_back,
+            jack_board_front,
+            jack_board_back,
+        }
+    }
+}
+
 impl LegacyPhotos for LegacyDmgPhotos {
     fn infos() -> Vec<PhotoInfo<Self>> {
         vec![
@@ -171,6 +272,23 @@ impl LegacyPhotos for LegacyDmgPhotos {
         .filter_map(|photo| photo.as_ref())
         .collect()
     }
+    fn photos_mut(&mut self) -> Vec<&mut LegacyPhoto> {
+        [
+            &mut self.front,
+            &mut self.back,
+            &mut self.mainboard_front,
+            &mut self.mainboard_back,
+            &mut self.lcd_board_front,
+            &mut self.lcd_board_back,
+            &mut self.power_board_front,
+            &mut self.power_board_back,
+            &mut self.jack_board_front,
+            &mut self.jack_board_back,
+        ]
+        .into_iter()
+        .filter_map(|photo| photo.as_mut())
+        .collect()
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize)]
@@ -190,6 +308,20 @@ pub struct LegacyAgsPhotos {
     pub pcb_back: Option<LegacyPhoto>,
 }
 
+impl LegacyConsolePhotos for LegacyAgsPhotos {
+    fn from_files(files: Vec<Option<LegacyPhoto>>) -> Self {
+        let [front, top, back, pcb_front, pcb_back]: [Option<LegacyPhoto>; 5] =
+            files.try_into().expect("Unexpected number of photo files");
+        LegacyAgsPhotos {
+            front,
+            top,
+            back,
+            pcb_front,
+            pcb_back,
+        }
+    }
+}
+
 impl LegacyPhotos for LegacyAgsPhotos {
     fn infos() -> Vec<PhotoInfo<Self>> {
         vec![
@@ -223,6 +355,18 @@ impl LegacyPhotos for LegacyAgsPhotos {
         .filter_map(|photo| photo.as_ref())
         .collect()
     }
+    fn photos_mut(&mut self) -> Vec<&mut LegacyPhoto> {
+        [
+            &mut self.front,
+            &mut self.top,
+            &mut self.back,
+            &mut self.pcb_front,
+            &mut self.pcb_back,
+        ]
+        .into_iter()
+        .filter_map(|photo| photo.as_mut())
+        .collect()
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize)]
@@ -267,23 +411,15 @@ impl LegacyMetadata for LegacyDmgMetadata {
 impl LegacyConsoleMetadata for LegacyDmgMetadata {
     const CONSOLE: Console = Console::Dmg;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("VRAM", "U2", Box::new(|m| m.mainboard.video_ram.as_ref())),
-            ChipInfo::new("WRAM", "U3", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new(
-                "Audio amplifier",
-                "U4",
-                Box::new(|m| m.mainboard.amplifier.as_ref()),
-            ),
-            ChipInfo {
-                label: "Crystal",
-                designator: "X1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.video_ram.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.amplifier.as_ref(),
+            ConsoleDesignator::X1 => self.mainboard.crystal.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -448,15 +584,16 @@ impl LegacyMetadata for LegacySgbMetadata {}
 impl LegacyConsoleMetadata for LegacySgbMetadata {
     const CONSOLE: Console = Console::Sgb;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("ICD2", "U2", Box::new(|m| m.mainboard.icd2.as_ref())),
-            ChipInfo::new("WRAM", "U3", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new("VRAM", "U4", Box::new(|m| m.mainboard.video_ram.as_ref())),
-            ChipInfo::new("ROM", "U5", Box::new(|m| m.mainboard.rom.as_ref())),
-            ChipInfo::new("CIC", "U6", Box::new(|m| m.mainboard.cic.as_ref())),
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.icd2.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.video_ram.as_ref(),
+            ConsoleDesignator::U5 => self.mainboard.rom.as_ref(),
+            ConsoleDesignator::U6 => self.mainboard.cic.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -530,21 +667,17 @@ impl LegacyMetadata for LegacySgb2Metadata {}
 impl LegacyConsoleMetadata for LegacySgb2Metadata {
     const CONSOLE: Console = Console::Sgb2;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("ICD2", "U2", Box::new(|m| m.mainboard.icd2.as_ref())),
-            ChipInfo::new("WRAM", "U3", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new("ROM", "U4", Box::new(|m| m.mainboard.rom.as_ref())),
-            ChipInfo::new("CIC", "U5", Box::new(|m| m.mainboard.cic.as_ref())),
-            ChipInfo {
-                label: "Crystal",
-                designator: "XTAL1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-            ChipInfo::new("Coil", "COIL1", Box::new(|m| m.mainboard.coil.as_ref())),
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.icd2.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.rom.as_ref(),
+            ConsoleDesignator::U5 => self.mainboard.cic.as_ref(),
+            ConsoleDesignator::Xtal1 => self.mainboard.crystal.as_ref(),
+            ConsoleDesignator::Coil1 => self.mainboard.coil.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -599,27 +732,15 @@ impl LegacyMetadata for LegacyMgbMetadata {
 impl LegacyConsoleMetadata for LegacyMgbMetadata {
     const CONSOLE: Console = Console::Mgb;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("WRAM", "U2", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new(
-                "Audio amplifier",
-                "U3",
-                Box::new(|m| m.mainboard.amplifier.as_ref()),
-            ),
-            ChipInfo::new(
-                "LCD bias generator",
-                "U4",
-                Box::new(|m| m.mainboard.regulator.as_ref()),
-            ),
-            ChipInfo {
-                label: "Crystal",
-                designator: "X1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.amplifier.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.regulator.as_ref(),
+            ConsoleDesignator::X1 => self.mainboard.crystal.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -721,28 +842,16 @@ impl LegacyMetadata for LegacyMglMetadata {
 impl LegacyConsoleMetadata for LegacyMglMetadata {
     const CONSOLE: Console = Console::Mgl;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("WRAM", "U2", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new(
-                "Audio amplifier",
-                "U3",
-                Box::new(|m| m.mainboard.amplifier.as_ref()),
-            ),
-            ChipInfo::new(
-                "LCD bias generator",
-                "U4",
-                Box::new(|m| m.mainboard.regulator.as_ref()),
-            ),
-            ChipInfo {
-                label: "Crystal",
-                designator: "X1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-            ChipInfo::new("Transformer", "T1", Box::new(|m| m.mainboard.t1.as_ref())),
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.amplifier.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.regulator.as_ref(),
+            ConsoleDesignator::X1 => self.mainboard.crystal.as_ref(),
+            ConsoleDesignator::T1 => self.mainboard.t1.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -844,27 +953,15 @@ impl LegacyMetadata for LegacyCgbMetadata {}
 impl LegacyConsoleMetadata for LegacyCgbMetadata {
     const CONSOLE: Console = Console::Cgb;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("WRAM", "U2", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new(
-                "Audio amplifier",
-                "U3",
-                Box::new(|m| m.mainboard.amplifier.as_ref()),
-            ),
-            ChipInfo::new(
-                "LCD bias generator",
-                "U4",
-                Box::new(|m| m.mainboard.regulator.as_ref()),
-            ),
-            ChipInfo {
-                label: "Crystal",
-                designator: "X1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.amplifier.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.regulator.as_ref(),
+            ConsoleDesignator::X1 => self.mainboard.crystal.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -958,28 +1055,16 @@ impl LegacyMetadata for LegacyAgbMetadata {}
 impl LegacyConsoleMetadata for LegacyAgbMetadata {
     const CONSOLE: Console = Console::Agb;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("WRAM", "U2", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new(
-                "LCD bias generator",
-                "U3",
-                Box::new(|m| m.mainboard.regulator.as_ref()),
-            ),
-            ChipInfo::new("?", "U4", Box::new(|m| m.mainboard.u4.as_ref())),
-            ChipInfo::new(
-                "Audio amplifier",
-                "U6",
-                Box::new(|m| m.mainboard.amplifier.as_ref()),
-            ),
-            ChipInfo {
-                label: "Crystal",
-                designator: "X1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.regulator.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.u4.as_ref(),
+            ConsoleDesignator::U6 => self.mainboard.amplifier.as_ref(),
+            ConsoleDesignator::X1 => self.mainboard.crystal.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -1058,28 +1143,16 @@ impl LegacyMetadata for LegacyAgsMetadata {}
 impl LegacyConsoleMetadata for LegacyAgsMetadata {
     const CONSOLE: Console = Console::Ags;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("WRAM", "U2", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new(
-                "Amplifier",
-                "U3",
-                Box::new(|m| m.mainboard.amplifier.as_ref()),
-            ),
-            ChipInfo::new("?", "U4", Box::new(|m| m.mainboard.u4.as_ref())),
-            ChipInfo::new(
-                "Battery controller",
-                "U5",
-                Box::new(|m| m.mainboard.u5.as_ref()),
-            ),
-            ChipInfo {
-                label: "Crystal",
-                designator: "X1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.amplifier.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.u4.as_ref(),
+            ConsoleDesignator::U5 => self.mainboard.u5.as_ref(),
+            ConsoleDesignator::X1 => self.mainboard.crystal.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -1172,20 +1245,16 @@ impl LegacyMetadata for LegacyGbsMetadata {}
 impl LegacyConsoleMetadata for LegacyGbsMetadata {
     const CONSOLE: Console = Console::Gbs;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("WRAM", "U2", Box::new(|m| m.mainboard.work_ram.as_ref())),
-            ChipInfo::new("?", "U4", Box::new(|m| m.mainboard.u4.as_ref())),
-            ChipInfo::new("Regulator", "U5", Box::new(|m| m.mainboard.u5.as_ref())),
-            ChipInfo::new("Regulator", "U6", Box::new(|m| m.mainboard.u5.as_ref())),
-            ChipInfo {
-                label: "Crystal",
-                designator: "Y1",
-                hide_type: true,
-                getter: Box::new(|m| m.mainboard.crystal.as_ref()),
-            },
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U2 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U3 => self.mainboard.work_ram.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.u4.as_ref(),
+            ConsoleDesignator::U5 => self.mainboard.u5.as_ref(),
+            ConsoleDesignator::U6 => self.mainboard.u6.as_ref(),
+            ConsoleDesignator::Y1 => self.mainboard.crystal.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -1270,13 +1339,14 @@ impl LegacyMetadata for LegacyOxyMetadata {}
 impl LegacyConsoleMetadata for LegacyOxyMetadata {
     const CONSOLE: Console = Console::Oxy;
 
-    fn chips() -> Vec<ChipInfo<Self>> {
-        vec![
-            ChipInfo::new("CPU", "U1", Box::new(|m| m.mainboard.cpu.as_ref())),
-            ChipInfo::new("?", "U2", Box::new(|m| m.mainboard.u2.as_ref())),
-            ChipInfo::new("?", "U4", Box::new(|m| m.mainboard.u4.as_ref())),
-            ChipInfo::new("?", "U5", Box::new(|m| m.mainboard.u5.as_ref())),
-        ]
+    fn mainboard_chip(&self, designator: ConsoleDesignator) -> Option<&LegacyChip> {
+        match designator {
+            ConsoleDesignator::U1 => self.mainboard.cpu.as_ref(),
+            ConsoleDesignator::U2 => self.mainboard.u2.as_ref(),
+            ConsoleDesignator::U4 => self.mainboard.u4.as_ref(),
+            ConsoleDesignator::U5 => self.mainboard.u5.as_ref(),
+            _ => None,
+        }
     }
 
     fn shell(&self) -> LegacyConsoleShell {
@@ -1358,14 +1428,16 @@ impl HasDateCode for LegacyLcdPanel {
 pub fn to_legacy_lcd_chip(year_hint: Option<u16>, chip: &LcdChip) -> LegacyChip {
     let ribbon_label = &chip.ribbon_label;
     if let Some(label) = &chip.label {
-        let chip = gbhwdb_backend::parser::lcd_chip::lcd_chip()
-            .parse(&label)
-            .unwrap_or_else(|_| panic!("{}", label));
+        let full_label = match &ribbon_label {
+            Some(ribbon_label) => format!("{} {}", ribbon_label, label),
+            None => label.to_owned(),
+        };
+        let chip = match gbhwdb_backend::parser::lcd_chip::lcd_chip().parse(&label) {
+            Ok(chip) => chip,
+            Err(_) => return LegacyChip::unparsed(&full_label),
+        };
         LegacyChip {
-            label: Some(match &ribbon_label {
-                Some(ribbon_label) => format!("{} {}", ribbon_label, label),
-                None => label.to_owned(),
-            }),
+            label: Some(full_label),
             kind: ribbon_label.clone(),
             manufacturer: Some("Sharp".to_owned()),
             year: to_legacy_year(year_hint, chip.year),
@@ -1393,10 +1465,10 @@ pub fn to_legacy_lcd_panel(year_hint: Option<u16>, screen: &LcdScreen) -> Option
         .as_ref()
         .map(|chip| to_legacy_lcd_chip(year_hint, chip));
     let label = screen.label.clone();
-    let screen = screen.label.as_ref().map(|label| {
+    let screen = screen.label.as_ref().and_then(|label| {
         gbhwdb_backend::parser::lcd_screen::lcd_screen()
             .parse(label)
-            .unwrap_or_else(|_| panic!("{}", label))
+            .ok()
     });
     Some(LegacyLcdPanel {
         label,
@@ -0,0 +1,389 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Conversion of console submissions to legacy metadata.
+//!
+//! Chips are parsed according to the mainboard configuration in `config::console`, so the
+//! conversions only pick the chips by role and copy the rest of the submission.
+
+use gbhwdb_backend::{
+    config::console::{ChipParser, ConsoleChipRole, ConsoleDesignator},
+    input::{
+        agb::AgbConsole, ags::AgsConsole, cgb::CgbConsole, console::ConsoleSubmission,
+        dmg::DmgConsole, gbs::GbsConsole, mgb::MgbConsole, mgl::MglConsole, oxy::OxyConsole,
+        sgb::SgbConsole, sgb2::Sgb2Console,
+    },
+    parser::{cgb_stamp::cgb_stamp, dmg_stamp::dmg_stamp, LabelParser},
+};
+
+use super::{
+    to_legacy_lcd_panel, FromConsoleSubmission, LegacyAgbMainboard, LegacyAgbMetadata,
+    LegacyAgsMainboard, LegacyAgsMetadata, LegacyBoardChips, LegacyCgbMainboard, LegacyCgbMetadata,
+    LegacyDmgJackBoard, LegacyDmgLcdBoard, LegacyDmgMainboard, LegacyDmgMetadata,
+    LegacyDmgPowerBoard, LegacyGbsMainboard, LegacyGbsMetadata, LegacyMgbMainboard,
+    LegacyMgbMetadata, LegacyMglMainboard, LegacyMglMetadata, LegacyOxyMainboard,
+    LegacyOxyMetadata, LegacySgb2Mainboard, LegacySgb2Metadata, LegacySgbMainboard,
+    LegacySgbMetadata,
+};
+use crate::legacy::{chip::map_console_chip, to_legacy_year};
+
+impl FromConsoleSubmission for LegacyDmgMetadata {
+    type Submission = DmgConsole;
+
+    fn from_submission(console: &DmgConsole, mut chips: LegacyBoardChips) -> Self {
+        let year_hint = console.year_hint();
+        let mainboard = LegacyDmgMainboard {
+            kind: console.mainboard.label.clone(),
+            circled_letters: console.mainboard.circled_letters.clone(),
+            extra_label: console.mainboard.extra_label.clone(),
+            stamp: console.mainboard.stamp.clone(),
+            cpu: chips.take_role(ConsoleChipRole::Cpu),
+            work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+            video_ram: chips.take_role(ConsoleChipRole::VideoRam),
+            amplifier: chips.take_role(ConsoleChipRole::Amplifier),
+            crystal: chips.take_role(ConsoleChipRole::Crystal),
+        };
+        let lcd_board = console.lcd_board.as_ref().map(|board| LegacyDmgLcdBoard {
+            kind: board.label.clone(),
+            circled_letters: board.circled_letters.clone(),
+            stamp: board.stamp.clone(),
+            year: board.year,
+            month: board.month,
+            lcd_panel: board
+                .screen
+                .as_ref()
+                .and_then(|screen| to_legacy_lcd_panel(year_hint, screen)),
+            regulator: map_console_chip(ChipParser::DmgReg, year_hint, &board.chip),
+        });
+        let power_board = console
+            .power_board
+            .as_ref()
+            .map(|board| LegacyDmgPowerBoard {
+                kind: board.kind.clone(),
+                label: (if board.kind == "D" {
+                    "DC CONV2 DMG"
+                } else {
+                    "DC CONV DMG"
+                })
+                .to_owned(),
+                year: board.year,
+                month: board.month,
+            });
+        let jack_board = console.jack_board.as_ref().map(|board| LegacyDmgJackBoard {
+            kind: board.kind.clone(),
+            extra_label: board.extra_label.clone(),
+        });
+
+        let mainboard_stamp = console
+            .mainboard
+            .stamp
+            .as_ref()
+            .filter(|_| !console.mainboard.outlier);
+        let lcd_board_stamp = console
+            .lcd_board
+            .as_ref()
+            .and_then(|board| board.stamp.as_ref().filter(|_| !board.outlier));
+        let stamp = mainboard_stamp
+            .and_then(|stamp| dmg_stamp().parse(stamp).ok())
+            .or_else(|| lcd_board_stamp.and_then(|stamp| dmg_stamp().parse(stamp).ok()));
+
+        LegacyDmgMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            year: stamp
+                .as_ref()
+                .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
+            month: stamp.as_ref().and_then(|stamp| stamp.month),
+            mainboard,
+            lcd_board,
+            power_board,
+            jack_board,
+        }
+    }
+    fn sort_group(console: &DmgConsole) -> Option<String> {
+        let group = match (console.serial().is_some(), console.has_outliers()) {
+            (true, false) => "A",
+            (false, false) => "B",
+            (true, true) => "C",
+            (false, true) => "D",
+        };
+        Some(group.to_owned())
+    }
+}
+
+impl FromConsoleSubmission for LegacySgbMetadata {
+    type Submission = SgbConsole;
+
+    fn from_submission(console: &SgbConsole, mut chips: LegacyBoardChips) -> Self {
+        LegacySgbMetadata {
+            stamp: console.shell.stamp.clone(),
+            mainboard: LegacySgbMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                letter_at_top_right: console.mainboard.letter_at_top_right.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                icd2: chips.take_role(ConsoleChipRole::Icd2),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                video_ram: chips.take_role(ConsoleChipRole::VideoRam),
+                rom: chips.take_role(ConsoleChipRole::Rom),
+                cic: chips.take_role(ConsoleChipRole::Cic),
+            },
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyMgbMetadata {
+    type Submission = MgbConsole;
+
+    fn from_submission(console: &MgbConsole, mut chips: LegacyBoardChips) -> Self {
+        let year_hint = console.year_hint();
+        let stamp = console
+            .mainboard
+            .stamp
+            .as_ref()
+            .and_then(|stamp| dmg_stamp().parse(stamp).ok());
+        LegacyMgbMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            year: stamp
+                .as_ref()
+                .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
+            month: stamp.as_ref().and_then(|stamp| stamp.month),
+            mainboard: LegacyMgbMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                number_pair: console.mainboard.number_pair.clone(),
+                stamp: console.mainboard.stamp.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                jun: console.mainboard.jun,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                amplifier: chips.take_role(ConsoleChipRole::Amplifier),
+                regulator: chips.take_role(ConsoleChipRole::LcdBiasGenerator),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+            },
+            lcd_panel: to_legacy_lcd_panel(year_hint, &console.screen),
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyMglMetadata {
+    type Submission = MglConsole;
+
+    fn from_submission(console: &MglConsole, mut chips: LegacyBoardChips) -> Self {
+        let year_hint = console.year_hint();
+        let stamp = console
+            .mainboard
+            .stamp
+            .as_ref()
+            .and_then(|stamp| cgb_stamp().parse(stamp).ok());
+        LegacyMglMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            year: stamp
+                .as_ref()
+                .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
+            week: stamp.as_ref().and_then(|stamp| stamp.week),
+            mainboard: LegacyMglMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                number_pair: console.mainboard.number_pair.clone(),
+                stamp: console.mainboard.stamp.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                jun: console.mainboard.jun,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                amplifier: chips.take_role(ConsoleChipRole::Amplifier),
+                regulator: chips.take_role(ConsoleChipRole::LcdBiasGenerator),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+                t1: chips.take_role(ConsoleChipRole::Transformer),
+            },
+            lcd_panel: to_legacy_lcd_panel(year_hint, &console.screen),
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacySgb2Metadata {
+    type Submission = Sgb2Console;
+
+    fn from_submission(console: &Sgb2Console, mut chips: LegacyBoardChips) -> Self {
+        LegacySgb2Metadata {
+            stamp: console.shell.stamp.clone(),
+            mainboard: LegacySgb2Mainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                letter_at_top_right: console.mainboard.letter_at_top_right.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                icd2: chips.take_role(ConsoleChipRole::Icd2),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                rom: chips.take_role(ConsoleChipRole::Rom),
+                cic: chips.take_role(ConsoleChipRole::Cic),
+                coil: chips.take_role(ConsoleChipRole::Coil),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+            },
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyCgbMetadata {
+    type Submission = CgbConsole;
+
+    fn from_submission(console: &CgbConsole, mut chips: LegacyBoardChips) -> Self {
+        // Early boards have DMG-style stamps with a month, later ones CGB-style stamps with a week
+        let stamp = console.mainboard.stamp.as_deref();
+        let old_stamp = stamp
+            .filter(|stamp| stamp.starts_with(&['6', '7', '8', '9'][..]))
+            .and_then(|stamp| dmg_stamp().parse(stamp).ok());
+        let new_stamp = stamp
+            .filter(|stamp| !stamp.starts_with(&['6', '7', '8', '9'][..]))
+            .and_then(|stamp| cgb_stamp().parse(stamp).ok());
+        let stamp_year = new_stamp
+            .as_ref()
+            .and_then(|stamp| stamp.year)
+            .or(old_stamp.as_ref().and_then(|stamp| stamp.year));
+        LegacyCgbMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            year: to_legacy_year(console.year_hint(), stamp_year),
+            month: old_stamp.as_ref().and_then(|stamp| stamp.month),
+            week: new_stamp.as_ref().and_then(|stamp| stamp.week),
+            mainboard: LegacyCgbMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                number_pair: console.mainboard.number_pair.clone(),
+                stamp: console.mainboard.stamp.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                jun: console.mainboard.jun,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                amplifier: chips.take_role(ConsoleChipRole::Amplifier),
+                regulator: chips.take_role(ConsoleChipRole::LcdBiasGenerator),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+            },
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyAgbMetadata {
+    type Submission = AgbConsole;
+
+    fn from_submission(console: &AgbConsole, mut chips: LegacyBoardChips) -> Self {
+        let stamp = console
+            .mainboard
+            .stamp
+            .as_ref()
+            .and_then(|stamp| cgb_stamp().parse(stamp).ok());
+        LegacyAgbMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            year: stamp
+                .as_ref()
+                .and_then(|stamp| to_legacy_year(console.year_hint(), stamp.year)),
+            week: stamp.as_ref().and_then(|stamp| stamp.week),
+            mainboard: LegacyAgbMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                number_pair: console.mainboard.number_pair.clone(),
+                stamp: console.mainboard.stamp.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                amplifier: chips.take_role(ConsoleChipRole::Amplifier),
+                regulator: chips.take_role(ConsoleChipRole::LcdBiasGenerator),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+                u4: chips.take(ConsoleDesignator::U4),
+            },
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyAgsMetadata {
+    type Submission = AgsConsole;
+
+    fn from_submission(console: &AgsConsole, mut chips: LegacyBoardChips) -> Self {
+        LegacyAgsMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            mainboard: LegacyAgsMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                number_pair: console.mainboard.number_pair.clone(),
+                stamp: console.mainboard.stamp.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                // FIXME: Not really an amplifier on boards with the new PMIC
+                amplifier: chips.take(ConsoleDesignator::U3),
+                u4: chips.take(ConsoleDesignator::U4),
+                u5: chips.take_role(ConsoleChipRole::BatteryController),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+            },
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyGbsMetadata {
+    type Submission = GbsConsole;
+
+    fn from_submission(console: &GbsConsole, mut chips: LegacyBoardChips) -> Self {
+        let stamp = console
+            .mainboard
+            .stamp
+            .as_ref()
+            .and_then(|stamp| cgb_stamp().parse(stamp).ok());
+        LegacyGbsMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            year: stamp
+                .as_ref()
+                .and_then(|stamp| to_legacy_year(console.year_hint(), stamp.year)),
+            week: stamp.as_ref().and_then(|stamp| stamp.week),
+            mainboard: LegacyGbsMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                number_pair: console.mainboard.number_pair.clone(),
+                stamp: console.mainboard.stamp.clone(),
+                stamp_front: console.mainboard.stamp_front.clone(),
+                stamp_back: console.mainboard.stamp_back.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                work_ram: chips.take_role(ConsoleChipRole::WorkRam),
+                crystal: chips.take_role(ConsoleChipRole::Crystal),
+                u4: chips.take(ConsoleDesignator::U4),
+                u5: chips.take(ConsoleDesignator::U5),
+                u6: chips.take(ConsoleDesignator::U6),
+            },
+        }
+    }
+}
+
+impl FromConsoleSubmission for LegacyOxyMetadata {
+    type Submission = OxyConsole;
+
+    fn from_submission(console: &OxyConsole, mut chips: LegacyBoardChips) -> Self {
+        LegacyOxyMetadata {
+            color: console.shell.color.map(|c| format!("{:?}", c)),
+            release_code: console.shell.release_code.clone(),
+            mainboard: LegacyOxyMainboard {
+                kind: console.mainboard.label.clone(),
+                circled_letters: console.mainboard.circled_letters.clone(),
+                year: console.mainboard.year,
+                month: console.mainboard.month,
+                cpu: chips.take_role(ConsoleChipRole::Cpu),
+                u2: chips.take(ConsoleDesignator::U2),
+                u4: chips.take(ConsoleDesignator::U4),
+                u5: chips.take(ConsoleDesignator::U5),
+            },
+        }
+    }
+}
@@ -2,56 +2,54 @@
 //
 // SPDX-License-Identifier: MIT
 
-use anyhow::Error;
-use csv_export::{write_submission_csv, ToCsv};
+use anyhow::{Context, Error};
+use build_report::{print_build_report, write_build_report};
+use clap::Parser;
+use csv_export::{write_chip_csv, write_submission_csv, ToCsv};
 use filetime::{set_file_mtime, FileTime};
 use gbhwdb_backend::{
+    analysis::repro,
     config::cartridge::*,
-    input::cartridge::*,
-    parser::{self, LabelParser},
+    db::{Category, Database, Photo},
+    input::{cartridge::Cartridge, console::ConsoleSubmission},
+    lint::{lint_and_load, Severity},
     Console,
 };
 use glob::glob;
-use image::{imageops::FilterType, ImageOutputFormat};
+use image::{imageops::FilterType, DynamicImage, ImageOutputFormat};
 use legacy::LegacyPhotos;
-use log::{debug, info, warn, LevelFilter};
+use log::{debug, info, LevelFilter};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use simplelog::{ColorChoice, TermLogger, TerminalMode};
 use std::{
     collections::{BTreeMap, HashMap},
     fs::{self, create_dir_all, File, Metadata},
-    io::{BufWriter, Write},
+    io::{BufReader, BufWriter, Write},
     path::Path,
+    process::ExitCode,
 };
-use walkdir::{DirEntry, WalkDir};
 
-use crate::legacy::chip::*;
+use crate::legacy::console::{FromConsoleSubmission, LegacyBoardChips};
 use crate::legacy::*;
 use site::{build_site, SubmissionCounts};
 
+mod build_report;
 mod css;
 mod csv_export;
+mod deep_zoom;
+mod exif;
+mod json_export;
 mod legacy;
 mod site;
+mod sqlite_export;
 mod template;
 
-fn is_metadata_file(entry: &DirEntry) -> bool {
-    entry.file_type().is_file() && entry.file_name() == "metadata.json"
-}
-
-fn get_photo(root: &Path, name: &str) -> Option<LegacyPhoto> {
-    if root.join(name).exists() {
-        Some(LegacyPhoto {
-            path: root
-                .canonicalize()
-                .unwrap()
-                .join(name)
-                .display()
-                .to_string(),
-            name: name.to_owned(),
-        })
-    } else {
-        None
+fn to_legacy_photo(photo: &Photo) -> LegacyPhoto {
+    LegacyPhoto {
+        path: photo.path.canonicalize().unwrap().display().to_string(),
+        name: photo.name.to_owned(),
+        widths: Vec::new(),
+        deep_zoom: false,
     }
 }
 
@@ -109,53 +107,164 @@ fn is_outdated(ref_meta: &Metadata, path: &Path) -> bool {
     }
 }
 
+/// Decodes a photo and rotates it according to its EXIF orientation
+fn decode_photo(data: &[u8]) -> Result<DynamicImage, Error> {
+    let img = image::load_from_memory(data)?;
+    Ok(exif::apply_orientation(img, exif::read_orientation(data)))
+}
+
 fn convert_photo(
-    input: impl AsRef<Path>,
+    img: &DynamicImage,
     target: impl AsRef<Path>,
     width: u32,
+    format: ImageOutputFormat,
 ) -> Result<(), Error> {
-    let img = image::open(input.as_ref())?.resize(width, u32::MAX, FilterType::Lanczos3);
+    let img = img.resize(width, u32::MAX, FilterType::Lanczos3);
+    // The JPEG and WebP encoders don't support all color types of decoded images
+    let img = DynamicImage::ImageRgb8(img.to_rgb8());
     let mut w = BufWriter::new(File::create(&target)?);
-    img.write_to(&mut w, ImageOutputFormat::Jpeg(80))?;
+    img.write_to(&mut w, format)?;
     w.flush()?;
     Ok(())
 }
 
-fn process_photos<M, P>(submissions: &[LegacySubmission<M, P>]) -> Result<(), Error>
+/// Returns the width of a photo after applying its EXIF orientation.
+///
+/// Only the headers of the file are read.
+fn photo_width(path: &Path) -> Result<u32, Error> {
+    let (width, height) = image::io::Reader::open(path)?
+        .with_guessed_format()?
+        .into_dimensions()?;
+    let orientation = File::open(path)
+        .map_err(Error::from)
+        .and_then(|file| exif::read_header(BufReader::new(file)))
+        .ok()
+        .and_then(|header| exif::read_orientation(&header));
+    match orientation {
+        Some(5..=8) => Ok(height),
+        _ => Ok(width),
+    }
+}
+
+/// Writes the thumbnails, the full resolution photos without metadata, scaled down JPEG and WebP
+/// versions in the given widths, and Deep Zoom tile pyramids of main board photos.
+///
+/// Scaled down versions wider than the original are not generated, so the generated widths are
+/// stored in each photo.
+fn process_photos<M, P>(
+    submissions: &mut [LegacySubmission<M, P>],
+    widths: &[u32],
+) -> Result<(), Error>
 where
     M: Sync + Send,
     P: Sync + Send + LegacyPhotos,
 {
     submissions
-        .par_iter()
+        .par_iter_mut()
         .map(|submission| {
             let target_dir = Path::new("build/static").join(&submission.code);
             fs::create_dir_all(&target_dir)?;
             if let Some(front) = submission.photos.front() {
                 let ref_meta = Path::new(&front.path).metadata()?;
-                for width in [80, 50] {
-                    let target = target_dir.join(format!(
-                        "{slug}_thumbnail_{width}.jpg",
-                        slug = submission.slug
-                    ));
-                    if is_outdated(&ref_meta, &target) {
-                        convert_photo(&front.path, &target, width)?;
+                let targets = [80, 50]
+                    .into_iter()
+                    .map(|width| {
+                        let name = format!("{slug}_thumbnail_{width}.jpg", slug = submission.slug);
+                        (width, target_dir.join(name))
+                    })
+                    .filter(|(_, target)| is_outdated(&ref_meta, target))
+                    .collect::<Vec<_>>();
+                if !targets.is_empty() {
+                    let img = decode_photo(&fs::read(&front.path)?)?;
+                    for (width, target) in targets {
+                        convert_photo(&img, &target, width, ImageOutputFormat::Jpeg(80))?;
                         set_file_mtime(&target, FileTime::from_last_modification_time(&ref_meta))?;
                         debug!("Wrote thumbnail {target}", target = target.display());
                     }
                 }
             }
-            for photo in submission.photos.photos() {
+            let main_board_photos = P::infos()
+                .into_iter()
+                .filter(|info| info.kind == PhotoKind::MainBoard)
+                .filter_map(|info| (info.getter)(&submission.photos).map(|p| p.name.clone()))
+                .collect::<Vec<_>>();
+            for photo in submission.photos.photos_mut() {
                 let ref_meta = Path::new(&photo.path).metadata()?;
+                let original_width = photo_width(Path::new(&photo.path))
+                    .with_context(|| format!("Failed to process {}", photo.path))?;
+                photo.widths = widths
+                    .iter()
+                    .copied()
+                    .filter(|&width| width < original_width)
+                    .collect();
+                photo.deep_zoom = main_board_photos.contains(&photo.name);
+
                 let target = target_dir.join(format!(
                     "{slug}_{name}",
                     slug = submission.slug,
                     name = photo.name
                 ));
-                if is_outdated(&ref_meta, &target) {
-                    fs::copy(&photo.path, &target)?;
+                let copy = Some(target).filter(|target| is_outdated(&ref_meta, target));
+                let targets = photo
+                    .widths
+                    .iter()
+                    .flat_map(|&width| {
+                        [
+                            ("jpg", ImageOutputFormat::Jpeg(80)),
+                            ("webp", ImageOutputFormat::WebP),
+                        ]
+                        .map(|(extension, format)| {
+                            let name = photo.scaled_name(width, extension);
+                            let name = format!("{slug}_{name}", slug = submission.slug);
+                            (width, format, target_dir.join(name))
+                        })
+                    })
+                    .filter(|(_, _, target)| is_outdated(&ref_meta, target))
+                    .collect::<Vec<_>>();
+                // The descriptor is written after all tiles, so it marks the whole pyramid
+                let dzi = photo
+                    .deep_zoom
+                    .then(|| {
+                        let name = photo.deep_zoom_name();
+                        target_dir.join(format!("{slug}_{name}", slug = submission.slug))
+                    })
+                    .filter(|dzi| is_outdated(&ref_meta, dzi));
+                if copy.is_none() && targets.is_empty() && dzi.is_none() {
+                    continue;
+                }
+                // The whole file is only read if something needs to be written
+                let data = fs::read(&photo.path)?;
+                if let Some(target) = copy {
+                    let stripped = exif::strip_metadata(&data)
+                        .with_context(|| format!("Failed to process {}", photo.path))?;
+                    fs::write(&target, &stripped.data)?;
                     set_file_mtime(&target, FileTime::from_last_modification_time(&ref_meta))?;
                     debug!("Copied photo {target}", target = target.display());
+                    if !stripped.removed.is_empty() {
+                        let removed = stripped
+                            .removed
+                            .iter()
+                            .map(|removed| removed.to_string())
+                            .collect::<Vec<_>>();
+                        info!(
+                            "Removed {removed} from {target}",
+                            removed = removed.join(", "),
+                            target = target.display()
+                        );
+                    }
+                }
+                if !targets.is_empty() || dzi.is_some() {
+                    let img = decode_photo(&data)?;
+                    for (width, format, target) in targets {
+                        convert_photo(&img, &target, width, format)?;
+                        set_file_mtime(&target, FileTime::from_last_modification_time(&ref_meta))?;
+                        debug!("Wrote scaled photo {target}", target = target.display());
+                    }
+                    if let Some(dzi) = dzi {
+                        deep_zoom::write_pyramid(&img, &dzi)?;
+                        set_file_mtime(&dzi, FileTime::from_last_modification_time(&ref_meta))?;
+                        debug!("Wrote tile pyramid {dzi}", dzi = dzi.display());
+                    }
                 }
             }
             Ok(())
@@ -163,7 +272,21 @@ where
         .collect::<Result<(), Error>>()
 }
 
-fn main() -> Result<(), Error> {
+#[derive(Parser, Debug)]
+#[command(about = "Builds the site into the build directory")]
+struct Args {
+    /// Lowest problem severity (warning, error, fatal) that makes the build fail
+    #[arg(long, default_value = "error")]
+    fail_on: Severity,
+    /// Comma-separated widths of the scaled down versions of submission photos
+    #[arg(long, value_delimiter = ',', default_value = "320,640,1280,2048")]
+    photo_widths: Vec<u32>,
+}
+
+fn main() -> Result<ExitCode, Error> {
+    let mut args = Args::parse();
+    args.photo_widths.sort_unstable();
+    args.photo_widths.dedup();
     let _ = TermLogger::init(
         LevelFilter::Info,
         simplelog::Config::default(),
@@ -174,46 +297,69 @@ fn main() -> Result<(), Error> {
     let mut data = SiteData::default();
     create_dir_all("build/static/export/consoles")?;
 
+    info!("Checking submissions");
+
+    // Submissions with fatal problems are left out of the database
+    let (mut report, db) = lint_and_load(Path::new("."));
+
     info!("Processing submissions");
 
-    let cfgs = gbhwdb_backend::config::cartridge::load_cfgs("config/games.json")?;
+    data.cartridges = process_cartridge_submissions(&db)?;
+    data.dmg = process_console_submissions(&db)?;
+    data.sgb = process_console_submissions(&db)?;
+    data.mgb = process_console_submissions(&db)?;
+    data.mgl = process_console_submissions(&db)?;
+    data.sgb2 = process_console_submissions(&db)?;
+    data.cgb = process_console_submissions(&db)?;
+    data.agb = process_console_submissions(&db)?;
+    data.ags = process_console_submissions(&db)?;
+    data.gbs = process_console_submissions(&db)?;
+    data.oxy = process_console_submissions(&db)?;
+    data.cfgs = db.cfgs.clone();
+
+    info!("Writing JSON export");
+
+    json_export::write_json_export(&db, "build/static/export/json")?;
+
+    info!("Writing SQLite export");
 
-    data.cartridges = process_cartridge_submissions(&cfgs)?;
-    data.dmg = process_dmg_submissions()?;
-    data.sgb = process_sgb_submissions()?;
-    data.mgb = process_mgb_submissions()?;
-    data.mgl = process_mgl_submissions()?;
-    data.sgb2 = process_sgb2_submissions()?;
-    data.cgb = process_cgb_submissions()?;
-    data.agb = process_agb_submissions()?;
-    data.ags = process_ags_submissions()?;
-    data.gbs = process_gbs_submissions()?;
-    data.oxy = process_oxy_submissions()?;
-    data.cfgs = cfgs;
+    sqlite_export::write_sqlite_export(&db, "build/static/export/gbhwdb.sqlite")?;
+
+    info!("Writing chip CSV export");
+
+    let csv = BufWriter::new(File::create("build/static/export/chips.csv")?);
+    write_chip_csv(csv, json_export::SITE_URL, &db)?;
 
     info!("Processing photos");
 
-    process_photos(&data.cartridges)?;
-    process_photos(&data.dmg)?;
-    process_photos(&data.sgb)?;
-    process_photos(&data.mgb)?;
-    process_photos(&data.mgl)?;
-    process_photos(&data.sgb2)?;
-    process_photos(&data.cgb)?;
-    process_photos(&data.agb)?;
-    process_photos(&data.ags)?;
-    process_photos(&data.gbs)?;
-    process_photos(&data.oxy)?;
+    process_photos(&mut data.cartridges, &args.photo_widths)?;
+    process_photos(&mut data.dmg, &args.photo_widths)?;
+    process_photos(&mut data.sgb, &args.photo_widths)?;
+    process_photos(&mut data.mgb, &args.photo_widths)?;
+    process_photos(&mut data.mgl, &args.photo_widths)?;
+    process_photos(&mut data.sgb2, &args.photo_widths)?;
+    process_photos(&mut data.cgb, &args.photo_widths)?;
+    process_photos(&mut data.agb, &args.photo_widths)?;
+    process_photos(&mut data.ags, &args.photo_widths)?;
+    process_photos(&mut data.gbs, &args.photo_widths)?;
+    process_photos(&mut data.oxy, &args.photo_widths)?;
 
     info!("Generating site");
 
     let mut site = build_site();
-    site.generate_all(&data, "build")?;
+    site.generate_all(&data, "build", &mut report)?;
     build_css()?;
     copy_static_files()?;
 
     info!("Site generation finished");
-    Ok(())
+
+    write_build_report(&report, "build/report.json")?;
+    print_build_report(&report);
+    if report.exceeds(args.fail_on) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
 }
 
 fn write_console_submission_csv<M, P>(
@@ -229,85 +375,89 @@ where
     write_submission_csv(csv, "https://gbhwdb.gekkio/consoles", submissions)
 }
 
-fn process_cartridge_submissions(
-    cfgs: &BTreeMap<String, GameConfig>,
-) -> Result<Vec<LegacyCartridgeSubmission>, Error> {
+fn process_cartridge_submissions(db: &Database) -> Result<Vec<LegacyCartridgeSubmission>, Error> {
     use legacy::cartridge::*;
-    let walker = WalkDir::new("data/cartridges").min_depth(3).max_depth(3);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let cartridge: Cartridge = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(cartridge.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            let cfg = cfgs.get(&cartridge.code).unwrap();
-
-            let layout = BoardLayout::from_label(&cartridge.board.label).unwrap_or_else(|| {
-                panic!(
-                    "Failed to find board layout for board {}",
-                    cartridge.board.label
-                )
-            });
-            assert!(cfg.layouts.contains(&layout));
-
-            if let Some(year) = cartridge.board.year {
-                assert!(year >= 1989 && year < 2010);
-            }
-
-            if let Some(sha256) = cartridge.dump.as_ref().map(|dump| dump.sha256) {
-                match cfg.sha256 {
-                    None => warn!(
-                        "Submission has SHA256 but config doesn't: {}",
-                        cartridge.code
-                    ),
-                    Some(cfg_sha) if cfg_sha == sha256 => (),
-                    _ => panic!("SHA256 mismatch: {}", cartridge.code),
-                }
-            }
+    let cfgs = &db.cfgs;
+    let loaded_submissions = db
+        .submissions
+        .iter()
+        .filter(|submission| submission.category == Category::Cartridge)
+        .filter_map(|submission| Some((submission, submission.data::<Cartridge>()?)))
+        .collect::<Vec<_>>();
+    let mut cartridges_by_code = HashMap::<&str, Vec<&Cartridge>>::new();
+    for &(_, cartridge) in &loaded_submissions {
+        cartridges_by_code
+            .entry(&cartridge.code)
+            .or_default()
+            .push(cartridge);
+    }
+    let release_years = cartridges_by_code
+        .into_iter()
+        .filter_map(|(code, cartridges)| {
+            let cfg = cfgs.get(code)?;
+            Some((code.to_owned(), repro::release_year(cartridges, cfg)?))
+        })
+        .collect::<HashMap<_, _>>();
 
-            let mut board = LegacyBoard {
-                layout,
-                kind: cartridge.board.label.clone(),
-                circled_letters: cartridge.board.circled_letters.clone(),
-                extra_label: cartridge.board.extra_label.clone(),
-                year: cartridge.board.year.map(|year| year as u16),
-                month: cartridge.board.month,
-                u1: None,
-                u2: None,
-                u3: None,
-                u4: None,
-                u5: None,
-                u6: None,
-                u7: None,
-                x1: None,
-            };
-            add_legacy_chips(layout, cartridge.board, &mut board);
-            let metadata = LegacyMetadata {
-                cfg: cfg.clone(),
-                code: cartridge.shell.code,
-                stamp: cartridge.shell.stamp,
-                board,
-                dump: cartridge.dump,
-            };
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.pcb_front = get_photo(root, "02_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "03_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: cartridge.code,
-                title: format!("Entry #{}", cartridge.index),
-                slug: cartridge.slug,
-                sort_group: None,
-                contributor: cartridge.contributor,
-                metadata,
-                photos,
-            });
-        }
+    let mut submissions = Vec::new();
+    for (loaded, cartridge) in loaded_submissions {
+        debug!("{}/{}", loaded.code, loaded.slug);
+        let photo = |name| loaded.photo(name).map(to_legacy_photo);
+        let photos = LegacyDefaultPhotos {
+            front: photo("01_front.jpg"),
+            back: None,
+            pcb_front: photo("02_pcb_front.jpg"),
+            pcb_back: photo("03_pcb_back.jpg"),
+        };
+        // Problems in the data have already been reported by lint, and submissions with fatal
+        // problems have been skipped
+        let cfg = match cfgs.get(&cartridge.code) {
+            Some(cfg) => cfg,
+            None => continue,
+        };
+        let layout = match BoardLayout::resolve(&cartridge.board.label, cfg) {
+            Some(layout) => layout,
+            None => continue,
+        };
+        let release_year = release_years.get(&cartridge.code).copied();
+        let repro = repro::analyze(cartridge, cfg, release_year);
+
+        let mut board = LegacyBoard {
+            layout,
+            mapper: None,
+            kind: cartridge.board.label.clone(),
+            circled_letters: cartridge.board.circled_letters.clone(),
+            extra_label: cartridge.board.extra_label.clone(),
+            year: cartridge.board.year.map(|year| year as u16),
+            month: cartridge.board.month,
+            u1: None,
+            u2: None,
+            u3: None,
+            u4: None,
+            u5: None,
+            u6: None,
+            u7: None,
+            x1: None,
+            bt1: None,
+        };
+        add_legacy_chips(cfg, layout, &cartridge.board, &mut board);
+        let metadata = LegacyMetadata {
+            cfg: cfg.clone(),
+            code: cartridge.shell.code.clone(),
+            stamp: cartridge.shell.stamp.clone(),
+            board,
+            dump: cartridge.dump.clone(),
+            repro,
+        };
+        submissions.push(LegacySubmission {
+            code: cartridge.code.clone(),
+            title: format!("Entry #{}", cartridge.index),
+            slug: cartridge.slug.clone(),
+            sort_group: None,
+            contributor: cartridge.contributor.clone(),
+            metadata,
+            photos,
+        });
     }
     submissions.sort_by_key(|submission| (submission.code.clone(), submission.slug.clone()));
     let csv = BufWriter::new(File::create("build/static/export/cartridges.csv")?);
@@ -315,1015 +465,50 @@ fn process_cartridge_submissions(
     Ok(submissions)
 }
 
-fn process_dmg_submissions() -> Result<Vec<LegacyDmgSubmission>, Error> {
-    use gbhwdb_backend::input::dmg::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/DMG").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: DmgConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let cpu = console.mainboard.u1.as_ref().map(|chip| {
-                to_legacy_chip(None, chip, parser::gen1_soc::gen1_soc()).unwrap_or_else(|| {
-                    LegacyChip {
-                        kind: Some("blob".to_string()),
-                        ..LegacyChip::default()
-                    }
-                })
-            });
-            let year_hint = cpu.as_ref().map(|cpu| cpu.year.unwrap_or(1996));
-
-            let work_ram = console.mainboard.u2.as_ref().map(|chip| {
-                to_legacy_chip(year_hint, chip, parser::ram::ram()).unwrap_or_else(|| LegacyChip {
-                    kind: Some("blob".to_string()),
-                    ..LegacyChip::default()
-                })
-            });
-            let video_ram = console.mainboard.u3.as_ref().map(|chip| {
-                to_legacy_chip(year_hint, chip, parser::ram::ram()).unwrap_or_else(|| LegacyChip {
-                    kind: Some("blob".to_string()),
-                    ..LegacyChip::default()
-                })
-            });
-            let amplifier = console.mainboard.u4.as_ref().map(|chip| {
-                to_legacy_chip(year_hint, chip, parser::dmg_amp::dmg_amp()).unwrap_or_else(|| {
-                    LegacyChip {
-                        kind: Some("blob".to_string()),
-                        ..LegacyChip::default()
-                    }
-                })
-            });
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.x1,
-                parser::crystal_4mihz::crystal_4mihz(),
-            );
-
-            let mainboard = LegacyDmgMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                extra_label: console.mainboard.extra_label.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                cpu,
-                work_ram,
-                video_ram,
-                amplifier,
-                crystal,
-            };
-
-            let lcd_board = console.lcd_board.as_ref().map(|board| {
-                let regulator = map_legacy_chip(year_hint, &board.chip, parser::dmg_reg::dmg_reg());
-                let lcd_panel = board
-                    .screen
-                    .as_ref()
-                    .and_then(|screen| to_legacy_lcd_panel(year_hint, screen));
-
-                LegacyDmgLcdBoard {
-                    kind: board.label.clone(),
-                    circled_letters: board.circled_letters.clone(),
-                    stamp: board.stamp.clone(),
-                    year: board.year,
-                    month: board.month,
-                    lcd_panel,
-                    regulator,
-                }
-            });
-
-            let power_board = console
-                .power_board
-                .as_ref()
-                .map(|board| LegacyDmgPowerBoard {
-                    kind: board.kind.clone(),
-                    label: (if board.kind == "D" {
-                        "DC CONV2 DMG"
-                    } else {
-                        "DC CONV DMG"
-                    })
-                    .to_owned(),
-                    year: board.year,
-                    month: board.month,
-                });
-
-            let jack_board = console.jack_board.as_ref().map(|board| LegacyDmgJackBoard {
-                kind: board.kind.clone(),
-                extra_label: board.extra_label.clone(),
-            });
-
-            let mainboard_stamp = console
-                .mainboard
-                .stamp
-                .as_ref()
-                .filter(|_| !console.mainboard.outlier)
-                .map(|stamp| {
-                    gbhwdb_backend::parser::dmg_stamp::dmg_stamp()
-                        .parse(&stamp)
-                        .unwrap_or_else(|_| panic!("{}", stamp))
-                });
-            let lcd_board_stamp = console
-                .lcd_board
-                .as_ref()
-                .and_then(|board| board.stamp.as_ref().filter(|_| !board.outlier))
-                .map(|stamp| {
-                    gbhwdb_backend::parser::dmg_stamp::dmg_stamp()
-                        .parse(&stamp)
-                        .unwrap_or_else(|_| panic!("{}", stamp))
-                });
-            let stamp = mainboard_stamp.or(lcd_board_stamp);
-
-            let metadata = LegacyDmgMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                year: stamp
-                    .as_ref()
-                    .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
-                month: stamp.as_ref().and_then(|stamp| stamp.month),
-                mainboard,
-                lcd_board,
-                power_board,
-                jack_board,
-            };
-
-            let has_outliers = console.shell.outlier
-                || console.mainboard.outlier
-                || console
-                    .lcd_board
-                    .as_ref()
-                    .map(|board| {
-                        board.outlier
-                            || board
-                                .screen
-                                .as_ref()
-                                .map(|screen| screen.outlier)
-                                .unwrap_or(false)
-                    })
-                    .unwrap_or(false)
-                || console
-                    .power_board
-                    .as_ref()
-                    .map(|board| board.outlier)
-                    .unwrap_or(false)
-                || console
-                    .jack_board
-                    .as_ref()
-                    .map(|board| board.outlier)
-                    .unwrap_or(false);
-            let mut photos = LegacyDmgPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.mainboard_front = get_photo(root, "03_mainboard_front.jpg");
-            photos.mainboard_back = get_photo(root, "04_mainboard_back.jpg");
-            photos.lcd_board_front = get_photo(root, "05_lcd_board_front.jpg");
-            photos.lcd_board_back = get_photo(root, "06_lcd_board_back.jpg");
-            photos.power_board_front = get_photo(root, "07_power_board_front.jpg");
-            photos.power_board_back = get_photo(root, "08_power_board_back.jpg");
-            photos.jack_board_front = get_photo(root, "09_jack_board_front.jpg");
-            photos.jack_board_back = get_photo(root, "10_jack_board_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "dmg".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: Some(
-                    (match (console.shell.serial.is_some(), has_outliers) {
-                        (true, false) => "A",
-                        (false, false) => "B",
-                        (true, true) => "C",
-                        (false, true) => "D",
-                    })
-                    .to_owned(),
-                ),
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("dmg", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_sgb_submissions() -> Result<Vec<LegacySgbSubmission>, Error> {
-    use gbhwdb_backend::input::sgb::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/SGB").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: SgbConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-
-            let year_hint = console.mainboard.year;
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::gen1_soc::gen1_soc(),
-            );
-            let icd2 = map_legacy_chip(year_hint, &console.mainboard.u2, parser::icd2::icd2());
-            let work_ram = map_legacy_chip(year_hint, &console.mainboard.u3, parser::ram::ram());
-            let video_ram = map_legacy_chip(year_hint, &console.mainboard.u4, parser::ram::ram());
-            let rom = map_legacy_chip(year_hint, &console.mainboard.u5, parser::sgb_rom::sgb_rom());
-            let cic = map_legacy_chip(year_hint, &console.mainboard.u6, parser::cic::cic());
-            let mainboard = LegacySgbMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                letter_at_top_right: console.mainboard.letter_at_top_right.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                cpu,
-                icd2,
-                work_ram,
-                video_ram,
-                rom,
-                cic,
-            };
-
-            let metadata = LegacySgbMetadata {
-                stamp: console.shell.stamp,
-                mainboard,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "sgb".to_string(),
-                title: format!("Unit #{}", console.index),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("sgb", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_mgb_submissions() -> Result<Vec<LegacyMgbSubmission>, Error> {
-    use gbhwdb_backend::input::mgb::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/MGB").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: MgbConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let year_hint = console.mainboard.year;
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::gen2_soc::gen2_soc(),
-            );
-            let work_ram = map_legacy_chip(year_hint, &console.mainboard.u2, parser::ram::ram());
-            let amplifier =
-                map_legacy_chip(year_hint, &console.mainboard.u3, parser::mgb_amp::mgb_amp());
-            let regulator =
-                map_legacy_chip(year_hint, &console.mainboard.u4, parser::dmg_reg::dmg_reg());
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.x1,
-                parser::crystal_4mihz::crystal_4mihz(),
-            );
-            let mainboard = LegacyMgbMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                number_pair: console.mainboard.number_pair.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                jun: console.mainboard.jun,
-                cpu,
-                work_ram,
-                amplifier,
-                regulator,
-                crystal,
-            };
-            let lcd_panel = to_legacy_lcd_panel(year_hint, &console.screen);
-
-            let stamp = console.mainboard.stamp.as_ref().map(|stamp| {
-                gbhwdb_backend::parser::dmg_stamp::dmg_stamp()
-                    .parse(&stamp)
-                    .unwrap_or_else(|_| panic!("{}", stamp))
-            });
-
-            let metadata = LegacyMgbMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                year: stamp
-                    .as_ref()
-                    .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
-                month: stamp.as_ref().and_then(|stamp| stamp.month),
-                mainboard,
-                lcd_panel,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "mgb".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("mgb", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_mgl_submissions() -> Result<Vec<LegacyMglSubmission>, Error> {
-    use gbhwdb_backend::input::mgl::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/MGL").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: MglConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let year_hint = console.mainboard.year;
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::gen2_soc::gen2_soc(),
-            );
-            let work_ram = map_legacy_chip(year_hint, &console.mainboard.u2, parser::ram::ram());
-            let amplifier =
-                map_legacy_chip(year_hint, &console.mainboard.u3, parser::mgb_amp::mgb_amp());
-            let regulator =
-                map_legacy_chip(year_hint, &console.mainboard.u4, parser::dmg_reg::dmg_reg());
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.x1,
-                parser::crystal_4mihz::crystal_4mihz(),
-            );
-            let t1 = map_legacy_chip(
-                year_hint,
-                &console.mainboard.t1,
-                parser::mgl_transformer::mgl_transformer(),
-            );
-            let mainboard = LegacyMglMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                number_pair: console.mainboard.number_pair.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                jun: console.mainboard.jun,
-                cpu,
-                work_ram,
-                amplifier,
-                regulator,
-                crystal,
-                t1,
-            };
-            let lcd_panel = to_legacy_lcd_panel(year_hint, &console.screen);
-
-            let stamp = console.mainboard.stamp.as_ref().map(|stamp| {
-                gbhwdb_backend::parser::cgb_stamp::cgb_stamp()
-                    .parse(&stamp)
-                    .unwrap_or_else(|_| panic!("{}", stamp))
-            });
-
-            let metadata = LegacyMglMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                year: stamp
-                    .as_ref()
-                    .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
-                week: stamp.as_ref().and_then(|stamp| stamp.week),
-                mainboard,
-                lcd_panel,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "mgl".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("mgl", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_sgb2_submissions() -> Result<Vec<LegacySgb2Submission>, Error> {
-    use gbhwdb_backend::input::sgb2::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/SGB2").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: Sgb2Console = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-
-            let year_hint = console.mainboard.year;
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::gen2_soc::gen2_soc(),
-            );
-            let icd2 = map_legacy_chip(year_hint, &console.mainboard.u2, parser::icd2::icd2());
-            let work_ram = map_legacy_chip(year_hint, &console.mainboard.u3, parser::ram::ram());
-            let rom = map_legacy_chip(year_hint, &console.mainboard.u4, parser::sgb_rom::sgb_rom());
-            let cic = map_legacy_chip(year_hint, &console.mainboard.u5, parser::cic::cic());
-            let coil = map_legacy_chip(year_hint, &console.mainboard.coil1, parser::coil::coil());
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.xtal1,
-                parser::crystal_20mihz::crystal_20mihz(),
-            );
-            let mainboard = LegacySgb2Mainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                letter_at_top_right: console.mainboard.letter_at_top_right.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                cpu,
-                icd2,
-                work_ram,
-                rom,
-                cic,
-                coil,
-                crystal,
-            };
-
-            let metadata = LegacySgb2Metadata {
-                stamp: console.shell.stamp,
-                mainboard,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "sgb2".to_string(),
-                title: format!("Unit #{}", console.index),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("sgb2", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_cgb_submissions() -> Result<Vec<LegacyCgbSubmission>, Error> {
-    use gbhwdb_backend::input::cgb::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/CGB").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: CgbConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let year_hint = console.mainboard.year.or(Some(1998));
-            let cpu = map_legacy_chip(year_hint, &console.mainboard.u1, parser::cgb_soc::cgb_soc());
-            let work_ram = map_legacy_chip(year_hint, &console.mainboard.u2, parser::ram::ram());
-            let amplifier =
-                map_legacy_chip(year_hint, &console.mainboard.u3, parser::mgb_amp::mgb_amp());
-            let regulator =
-                map_legacy_chip(year_hint, &console.mainboard.u4, parser::cgb_reg::cgb_reg());
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.x1,
-                parser::crystal_8mihz::crystal_8mihz(),
-            );
-            let mainboard = LegacyCgbMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                number_pair: console.mainboard.number_pair.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                jun: console.mainboard.jun,
-                cpu,
-                work_ram,
-                amplifier,
-                regulator,
-                crystal,
-            };
-
-            let (old_stamp, new_stamp) = match &console.mainboard.stamp {
-                Some(stamp) => {
-                    if stamp.starts_with(&['6', '7', '8', '9'][..]) {
-                        (
-                            Some(
-                                gbhwdb_backend::parser::dmg_stamp::dmg_stamp()
-                                    .parse(&stamp)
-                                    .unwrap_or_else(|_| panic!("{}", stamp)),
-                            ),
-                            None,
-                        )
-                    } else {
-                        (
-                            None,
-                            Some(
-                                gbhwdb_backend::parser::cgb_stamp::cgb_stamp()
-                                    .parse(&stamp)
-                                    .unwrap_or_else(|_| panic!("{}", stamp)),
-                            ),
-                        )
-                    }
-                }
-                None => (None, None),
-            };
-            let stamp_year = new_stamp
-                .as_ref()
-                .and_then(|stamp| stamp.year)
-                .or(old_stamp.as_ref().and_then(|stamp| stamp.year));
-
-            let metadata = LegacyCgbMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                year: to_legacy_year(year_hint, stamp_year),
-                month: old_stamp.as_ref().and_then(|stamp| stamp.month),
-                week: new_stamp.as_ref().and_then(|stamp| stamp.week),
-                mainboard,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "cgb".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("cgb", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_agb_submissions() -> Result<Vec<LegacyAgbSubmission>, Error> {
-    use gbhwdb_backend::input::agb::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/AGB").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: AgbConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let year_hint = console.mainboard.year.or(Some(2001));
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::agb_soc_qfp_128::agb_soc_qfp_128(),
-            );
-            let work_ram = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u2,
-                parser::sram_tsop1_48::sram_tsop1_48(),
-            );
-            let regulator =
-                map_legacy_chip(year_hint, &console.mainboard.u3, parser::agb_reg::agb_reg());
-            let u4 = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u4,
-                parser::agb_pmic::agb_pmic(),
-            );
-            let amplifier =
-                map_legacy_chip(year_hint, &console.mainboard.u6, parser::agb_amp::agb_amp());
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.x1,
-                parser::crystal_4mihz::crystal_4mihz(),
-            );
-            let mainboard = LegacyAgbMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                number_pair: console.mainboard.number_pair.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                cpu,
-                work_ram,
-                amplifier,
-                regulator,
-                crystal,
-                u4,
-            };
-
-            let stamp = console.mainboard.stamp.as_ref().map(|stamp| {
-                gbhwdb_backend::parser::cgb_stamp::cgb_stamp()
-                    .parse(&stamp)
-                    .unwrap_or_else(|_| panic!("{}", stamp))
-            });
-
-            let metadata = LegacyAgbMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                year: stamp
-                    .as_ref()
-                    .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
-                week: stamp.as_ref().and_then(|stamp| stamp.week),
-                mainboard,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "agb".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("agb", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_ags_submissions() -> Result<Vec<LegacyAgsSubmission>, Error> {
-    use gbhwdb_backend::input::ags::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/AGS").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: AgsConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let year_hint = console.mainboard.year.or(Some(2003));
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::agb_soc_qfp_156::agb_soc_qfp_156(),
-            );
-            let work_ram = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u2,
-                parser::sram_tsop1_48::sram_tsop1_48(),
-            );
-            let amplifier = match console.mainboard.label.as_str() {
-                // FIXME: Not really an amplifier
-                "C/AGS-CPU-30" | "C/AGT-CPU-01" => map_legacy_chip(
-                    year_hint,
-                    &console.mainboard.u3,
-                    parser::ags_pmic_new::ags_pmic_new(),
-                ),
-                _ => map_legacy_chip(year_hint, &console.mainboard.u3, parser::agb_amp::agb_amp()),
-            };
-            let u4 = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u4,
-                parser::ags_pmic_old::ags_pmic_old(),
-            );
-            let u5 = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u5,
-                parser::ags_charge_ctrl::ags_charge_ctrl(),
-            );
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.x1,
-                parser::crystal_4mihz::crystal_4mihz(),
-            );
-            let mainboard = LegacyAgsMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                number_pair: console.mainboard.number_pair.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                cpu,
-                work_ram,
-                amplifier,
-                u4,
-                u5,
-                crystal,
-            };
-
-            let metadata = LegacyAgsMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                mainboard,
-            };
-
-            let mut photos = LegacyAgsPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.top = get_photo(root, "02_top.jpg");
-            photos.back = get_photo(root, "03_back.jpg");
-            photos.pcb_front = get_photo(root, "04_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "05_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "ags".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("ags", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_gbs_submissions() -> Result<Vec<LegacyGbsSubmission>, Error> {
-    use gbhwdb_backend::input::gbs::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/GBS").min_depth(2).max_depth(2);
-    let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: GbsConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-
-            let year_hint = console.mainboard.year.or(Some(2003));
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u2,
-                parser::agb_soc_qfp_128::agb_soc_qfp_128(),
-            );
-            let work_ram = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u3,
-                parser::sram_tsop1_48::sram_tsop1_48(),
-            );
-            let u4 = map_legacy_chip(year_hint, &console.mainboard.u4, parser::gbs_dol::gbs_dol());
-            let u5 = map_legacy_chip(year_hint, &console.mainboard.u5, parser::gbs_reg::gbs_reg());
-            let u6 = map_legacy_chip(year_hint, &console.mainboard.u6, parser::gbs_reg::gbs_reg());
-            let crystal = map_legacy_chip(
-                year_hint,
-                &console.mainboard.y1,
-                parser::crystal_32mihz::crystal_32mihz(),
-            );
-            let mainboard = LegacyGbsMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                number_pair: console.mainboard.number_pair.clone(),
-                stamp: console.mainboard.stamp.clone(),
-                stamp_front: console.mainboard.stamp_front.clone(),
-                stamp_back: console.mainboard.stamp_back.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                cpu,
-                work_ram,
-                crystal,
-                u4,
-                u5,
-                u6,
-            };
-
-            let stamp = console.mainboard.stamp.as_ref().map(|stamp| {
-                gbhwdb_backend::parser::cgb_stamp::cgb_stamp()
-                    .parse(&stamp)
-                    .unwrap_or_else(|_| panic!("{}", stamp))
-            });
-
-            let metadata = LegacyGbsMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                year: stamp
-                    .as_ref()
-                    .and_then(|stamp| to_legacy_year(year_hint, stamp.year)),
-                week: stamp.as_ref().and_then(|stamp| stamp.week),
-                mainboard,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "gbs".to_string(),
-                title: format!("Unit #{}", console.index),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
-    }
-    submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("gbs", &submissions)?;
-    Ok(submissions)
-}
-
-fn process_oxy_submissions() -> Result<Vec<LegacyOxySubmission>, Error> {
-    use gbhwdb_backend::input::oxy::*;
-    use legacy::console::*;
-    let walker = WalkDir::new("data/consoles/OXY").min_depth(2).max_depth(2);
+fn process_console_submissions<M, P>(db: &Database) -> Result<Vec<LegacySubmission<M, P>>, Error>
+where
+    M: FromConsoleSubmission + ToCsv,
+    P: LegacyConsolePhotos,
+{
     let mut submissions = Vec::new();
-    for entry in walker.into_iter().filter_entry(is_metadata_file) {
-        let entry = entry?;
-        if let Some(root) = entry.path().parent() {
-            debug!("{}", entry.path().display());
-            let file = File::open(&entry.path())?;
-            let console: OxyConsole = serde_json::from_reader(file)?;
-            assert_eq!(
-                Some(console.slug.as_str()),
-                root.file_name().and_then(|name| name.to_str())
-            );
-            if let Some(serial) = &console.shell.serial {
-                assert_eq!(&console.slug, serial);
-            }
-
-            let year_hint = console.mainboard.year.or(Some(2005));
-            let cpu = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u1,
-                parser::agb_soc_bga::agb_soc_bga(),
-            );
-            let u2 = map_legacy_chip(
-                year_hint,
-                &console.mainboard.u2,
-                parser::oxy_pmic::oxy_pmic(),
-            );
-            let u4 = map_legacy_chip(year_hint, &console.mainboard.u4, parser::oxy_u4::oxy_u4());
-            let u5 = map_legacy_chip(year_hint, &console.mainboard.u5, parser::oxy_u5::oxy_u5());
-            let mainboard = LegacyOxyMainboard {
-                kind: console.mainboard.label.clone(),
-                circled_letters: console.mainboard.circled_letters.clone(),
-                year: console.mainboard.year,
-                month: console.mainboard.month,
-                cpu,
-                u2,
-                u4,
-                u5,
-            };
-
-            let metadata = LegacyOxyMetadata {
-                color: console.shell.color.map(|c| format!("{:?}", c)),
-                release_code: console.shell.release_code.clone(),
-                mainboard,
-            };
-
-            let mut photos = LegacyDefaultPhotos::default();
-            photos.front = get_photo(root, "01_front.jpg");
-            photos.back = get_photo(root, "02_back.jpg");
-            photos.pcb_front = get_photo(root, "03_pcb_front.jpg");
-            photos.pcb_back = get_photo(root, "04_pcb_back.jpg");
-            submissions.push(LegacySubmission {
-                code: "oxy".to_string(),
-                title: console
-                    .shell
-                    .serial
-                    .clone()
-                    .unwrap_or_else(|| format!("Unit #{}", console.index.unwrap())),
-                slug: console.slug,
-                sort_group: None,
-                contributor: console.contributor,
-                metadata,
-                photos,
-            });
-        }
+    let category = Category::Console(M::CONSOLE);
+    for loaded in db
+        .submissions
+        .iter()
+        .filter(|submission| submission.category == category)
+    {
+        debug!("{}/{}", loaded.code, loaded.slug);
+        let photos = P::from_files(
+            <M::Submission as ConsoleSubmission>::PHOTOS
+                .files()
+                .iter()
+                .map(|name| loaded.photo(name).map(to_legacy_photo))
+                .collect(),
+        );
+        let console = match loaded.data::<M::Submission>() {
+            Some(console) => console,
+            None => continue,
+        };
+        let chips = LegacyBoardChips::from_submission(console.year_hint(), console);
+        submissions.push(LegacySubmission {
+            code: M::CONSOLE.id().to_owned(),
+            title: console.title(),
+            slug: console.slug().to_owned(),
+            sort_group: M::sort_group(&console),
+            contributor: console.contributor().to_owned(),
+            metadata: M::from_submission(console, chips),
+            photos,
+        });
     }
     submissions.sort_by_key(|submission| (submission.sort_group.clone(), submission.slug.clone()));
-    write_console_submission_csv("oxy", &submissions)?;
+    write_console_submission_csv(M::CONSOLE.id(), &submissions)?;
     Ok(submissions)
 }
 
 fn copy_static_files() -> Result<(), Error> {
-    static PATTERNS: [&str; 8] = [
+    static PATTERNS: [&str; 9] = [
         "site/static/**/*.html",
+        "site/static/**/*.js",
         "site/static/**/*.txt",
         "site/static/**/*.ico",
         "site/static/**/*.jpg",
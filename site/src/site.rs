@@ -4,24 +4,24 @@
 
 use anyhow::Error;
 use gbhwdb_backend::{
-    config::cartridge::{BoardLayout, ChipRole, ChipRoleConfig},
+    config::mainboard::mainboard_revisions,
+    lint::{Problem, Report, Severity},
     Console,
 };
 use itertools::Itertools;
-use log::error;
 use percy_dom::{View, VirtualNode};
 use std::{
     borrow::Cow,
     collections::HashMap,
     fs::{self, create_dir_all},
     path::{Path, PathBuf},
-    sync::OnceLock,
 };
 use time::OffsetDateTime;
 
 use crate::{
-    legacy::LegacySubmission,
+    legacy::{console::LegacyConsoleMetadata, LegacyPhotos, LegacySubmission},
     template::{
+        battery_stats::BatteryStats,
         cartridge_page::CartridgePage,
         cartridges::Cartridges,
         console_page::ConsolePage,
@@ -30,16 +30,16 @@ use crate::{
         dmg_submission_list::DmgSubmissionList,
         game::Game,
         home::Home,
-        mapper::{Mapper, MapperCfg},
+        mainboard_revision::MainboardRevisionPage,
+        mapper::{Mapper, MapperPage},
         markdown::Markdown,
         markdown_page::MarkdownPage,
         page,
+        unlicensed_cartridges::UnlicensedCartridges,
     },
     SiteData,
 };
 
-static MAPPER_CFGS: OnceLock<Vec<MapperCfg>> = OnceLock::new();
-
 pub fn build_site() -> Site {
     let mut site = Site::new();
     site.add_page(["index"], |data| {
@@ -181,195 +181,80 @@ pub fn build_site() -> Site {
                 create_pages(console, &data.oxy, |s| ConsolePage::new(s).render())
             }),
         });
+        fn create_mainboard_pages<M: LegacyConsoleMetadata, P: LegacyPhotos>(
+            console: Console,
+            submissions: &[LegacySubmission<M, P>],
+        ) -> Vec<(SitePath, Page)> {
+            mainboard_revisions(console)
+                .map(|revision| {
+                    let path = SitePath(vec![
+                        Cow::Borrowed("consoles"),
+                        Cow::Borrowed(console.id()),
+                        Cow::Borrowed("mainboards"),
+                        Cow::Owned(revision.slug()),
+                    ]);
+                    let submissions = submissions
+                        .iter()
+                        .filter(|submission| submission.metadata.mainboard().kind == revision.label)
+                        .collect();
+                    let page = Page {
+                        title: format!("{}: {}", console.code(), revision.label).into(),
+                        section: SiteSection::Consoles(Some(console)),
+                        content: MainboardRevisionPage {
+                            revision,
+                            submissions,
+                        }
+                        .render(),
+                    };
+                    (path, page)
+                })
+                .collect()
+        }
+        site.page_sets.push(match console {
+            Console::Dmg => Box::new(move |data| create_mainboard_pages(console, &data.dmg)),
+            Console::Sgb => Box::new(move |data| create_mainboard_pages(console, &data.sgb)),
+            Console::Mgb => Box::new(move |data| create_mainboard_pages(console, &data.mgb)),
+            Console::Mgl => Box::new(move |data| create_mainboard_pages(console, &data.mgl)),
+            Console::Sgb2 => Box::new(move |data| create_mainboard_pages(console, &data.sgb2)),
+            Console::Cgb => Box::new(move |data| create_mainboard_pages(console, &data.cgb)),
+            Console::Agb => Box::new(move |data| create_mainboard_pages(console, &data.agb)),
+            Console::Ags => Box::new(move |data| create_mainboard_pages(console, &data.ags)),
+            Console::Gbs => Box::new(move |data| create_mainboard_pages(console, &data.gbs)),
+            Console::Oxy => Box::new(move |data| create_mainboard_pages(console, &data.oxy)),
+        });
     }
-    let mapper_cfgs = MAPPER_CFGS.get_or_init(|| {
-        vec![
-            MapperCfg {
-                id: "no-mapper",
-                name: "No mapper",
-                chips: &[ChipRole::Rom],
-                match_fn: Box::new(|layout, _| layout == BoardLayout::Rom),
-            },
-            MapperCfg {
-                id: "mbc1",
-                name: "MBC1",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind.starts_with("MBC1"))
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mbc2",
-                name: "MBC2",
-                chips: &[ChipRole::Rom, ChipRole::Mapper, ChipRole::SupervisorReset],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind.starts_with("MBC2"))
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mbc3",
-                name: "MBC3",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                    ChipRole::Crystal,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind != "MBC30" && chip_kind.starts_with("MBC3"))
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mbc30",
-                name: "MBC30",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                    ChipRole::Crystal,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind == "MBC30")
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mbc5",
-                name: "MBC5",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind.starts_with("MBC5"))
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mbc6",
-                name: "MBC6",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind == "MBC6")
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mbc7",
-                name: "MBC7",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Eeprom,
-                    ChipRole::Accelerometer,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind == "MBC7")
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "mmm01",
-                name: "MMM01",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind == "MMM01")
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "huc1",
-                name: "HuC-1",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind.starts_with("HuC-1"))
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "huc3",
-                name: "HuC-3",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Ram,
-                    ChipRole::SupervisorReset,
-                    ChipRole::HexInverter,
-                    ChipRole::Crystal,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind.starts_with("HuC-3"))
-                        .unwrap_or(false)
-                }),
-            },
-            MapperCfg {
-                id: "tama5",
-                name: "TAMA5",
-                chips: &[
-                    ChipRole::Rom,
-                    ChipRole::Mapper,
-                    ChipRole::Mcu,
-                    ChipRole::Rtc,
-                    ChipRole::SupervisorReset,
-                    ChipRole::Crystal,
-                ],
-                match_fn: Box::new(|_, chip| {
-                    chip.and_then(|chip| chip.kind.as_ref())
-                        .map(|chip_kind| chip_kind == "TAMA5")
-                        .unwrap_or(false)
-                }),
-            },
-        ]
-    });
     site.add_page(["cartridges", "index"], move |data| {
         Ok(Page {
             title: "Game Boy cartridges".into(),
             section: SiteSection::Cartridges,
             content: Cartridges {
-                mapper_cfgs,
                 cfgs: &data.cfgs,
                 submissions: &data.cartridges,
             }
             .render(),
         })
     });
+    site.add_page(["cartridges", "unlicensed"], move |data| {
+        Ok(Page {
+            title: "Unlicensed Game Boy cartridges".into(),
+            section: SiteSection::Cartridges,
+            content: UnlicensedCartridges {
+                cfgs: &data.cfgs,
+                submissions: &data.cartridges,
+            }
+            .render(),
+        })
+    });
+    site.add_page(["cartridges", "batteries"], move |data| {
+        Ok(Page {
+            title: "Game Boy cartridge batteries".into(),
+            section: SiteSection::Cartridges,
+            content: BatteryStats {
+                submissions: &data.cartridges,
+            }
+            .render(),
+        })
+    });
     site.page_sets.push(Box::new(move |data| {
         data.cartridges
             .iter()
@@ -425,27 +310,12 @@ pub fn build_site() -> Site {
     site.page_sets.push(Box::new(move |data| {
         data.cartridges
             .iter()
-            .map(|submission| {
-                let layout = submission.metadata.board.layout;
-                let chips = ChipRoleConfig::from(layout);
-                let mapper = chips
-                    .iter()
-                    .find(|&(_, role)| role == ChipRole::Mapper)
-                    .and_then(|(designator, _)| submission.metadata.board[designator].as_ref());
-                let key = mapper_cfgs
-                    .iter()
-                    .position(|cfg| (cfg.match_fn)(layout, mapper));
-                (key, submission)
-            })
-            .sorted_by_key(|&(key, _)| key)
-            .group_by(|&(key, _)| key)
+            .filter_map(|submission| MapperPage::of(submission).map(|page| (page, submission)))
+            .into_group_map()
             .into_iter()
-            .filter_map(|(cfg_idx, group)| {
-                cfg_idx.map(|idx| (idx, group.map(|(_, submission)| submission)))
-            })
-            .map(|(cfg_idx, group)| {
-                let cfg = &mapper_cfgs[cfg_idx];
+            .map(|(mapper_page, group)| {
                 let submissions = group
+                    .into_iter()
                     .sorted_by_key(|submission| {
                         (
                             &submission.metadata.cfg.name,
@@ -453,12 +323,15 @@ pub fn build_site() -> Site {
                         )
                     })
                     .collect::<Vec<_>>();
-                let path = SitePath(vec![Cow::Borrowed("cartridges"), Cow::Borrowed(cfg.id)]);
+                let path = SitePath(vec![
+                    Cow::Borrowed("cartridges"),
+                    Cow::Borrowed(mapper_page.id()),
+                ]);
                 let page = Page {
-                    title: Cow::Borrowed(cfg.name),
+                    title: Cow::Borrowed(mapper_page.name()),
                     section: SiteSection::Cartridges,
                     content: Mapper {
-                        cfg: &cfg,
+                        page: mapper_page,
                         submissions,
                     }
                     .render(),
@@ -542,14 +415,26 @@ impl Site {
     ) {
         self.pages.insert(path.into(), Box::new(generator));
     }
+    /// Generates all pages, recording pages that fail to render as errors in the report
     pub fn generate_all(
         &mut self,
         data: &SiteData,
         target_dir: impl AsRef<Path>,
+        report: &mut Report,
     ) -> Result<(), Error> {
         let target_dir = target_dir.as_ref();
-        for (path, generator) in &self.pages {
-            match generator(data).and_then(|page| page.generate()) {
+        let pages = self
+            .pages
+            .iter()
+            .map(|(path, generator)| (path.clone(), generator(data)))
+            .chain(
+                self.page_sets
+                    .iter()
+                    .flat_map(|page_set| page_set(data))
+                    .map(|(path, page)| (path, Ok(page))),
+            );
+        for (path, page) in pages {
+            match page.and_then(|page| page.generate()) {
                 Ok(content) => {
                     let target_file = path.join(target_dir);
                     if let Some(parent) = target_file.parent() {
@@ -557,18 +442,12 @@ impl Site {
                     }
                     fs::write(target_file, content.as_bytes())?;
                 }
-                Err(err) => {
-                    error!("{}", err)
-                }
-            }
-        }
-        for page_set in &self.page_sets {
-            for (path, page) in page_set(data) {
-                let target_file = path.join(target_dir);
-                if let Some(parent) = target_file.parent() {
-                    create_dir_all(parent)?;
-                }
-                fs::write(target_file, page.generate()?)?;
+                Err(err) => report.problems.push(Problem {
+                    severity: Severity::Error,
+                    path: path.join(target_dir),
+                    pointer: String::new(),
+                    message: format!("failed to generate page: {err}"),
+                }),
             }
         }
         Ok(())
@@ -0,0 +1,199 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use gbhwdb_backend::db::{
+    export::{export, ExportSubmission},
+    Category, Database,
+};
+use rusqlite::{params, Connection, Transaction};
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, remove_file},
+    path::Path,
+};
+
+use crate::json_export::SITE_URL;
+
+const SCHEMA: &str = "
+CREATE TABLE games (
+    code TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    platform TEXT NOT NULL,
+    publisher TEXT,
+    rom_verified INTEGER NOT NULL
+);
+CREATE TABLE contributors (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE boards (
+    id INTEGER PRIMARY KEY,
+    category TEXT NOT NULL,
+    name TEXT NOT NULL,
+    UNIQUE (category, name)
+);
+CREATE TABLE submissions (
+    id INTEGER PRIMARY KEY,
+    category TEXT NOT NULL,
+    code TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    title TEXT NOT NULL,
+    url TEXT NOT NULL,
+    outlier INTEGER NOT NULL,
+    contributor_id INTEGER NOT NULL REFERENCES contributors (id),
+    board_id INTEGER NOT NULL REFERENCES boards (id),
+    game_code TEXT REFERENCES games (code),
+    UNIQUE (category, code, slug)
+);
+CREATE TABLE chips (
+    id INTEGER PRIMARY KEY,
+    submission_id INTEGER NOT NULL REFERENCES submissions (id),
+    designator TEXT NOT NULL,
+    role TEXT NOT NULL,
+    label TEXT,
+    outlier INTEGER NOT NULL,
+    kind TEXT,
+    manufacturer TEXT,
+    year INTEGER,
+    month INTEGER,
+    week INTEGER,
+    rom_code TEXT,
+    date_from TEXT,
+    date_to TEXT,
+    UNIQUE (submission_id, designator)
+);
+CREATE TABLE photos (
+    submission_id INTEGER NOT NULL REFERENCES submissions (id),
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    PRIMARY KEY (submission_id, name)
+);
+CREATE INDEX chips_kind ON chips (kind);
+CREATE INDEX chips_manufacturer_year ON chips (manufacturer, year);
+";
+
+/// Writes the whole database into a single SQLite file, replacing any previous file.
+///
+/// `category` is `cartridge` or a console id (e.g. `dmg`), and `game_code` links cartridge
+/// submissions to `games`.
+pub fn write_sqlite_export(db: &Database, path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    if path.exists() {
+        remove_file(path)?;
+    }
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+    let tx = conn.transaction()?;
+    insert_games(&tx, db)?;
+    let export = export(db, SITE_URL);
+    let mut ids = Ids::default();
+    for (_, file) in &export.files {
+        for submission in &file.submissions {
+            insert_submission(&tx, db, &mut ids, file.category, submission)?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn insert_games(tx: &Transaction, db: &Database) -> Result<(), Error> {
+    let mut stmt = tx.prepare(
+        "INSERT INTO games (code, name, platform, publisher, rom_verified) VALUES (?, ?, ?, ?, ?)",
+    )?;
+    for (code, cfg) in &db.cfgs {
+        stmt.execute(params![
+            code,
+            cfg.name,
+            cfg.platform.to_string(),
+            cfg.publisher,
+            cfg.rom_verified
+        ])?;
+    }
+    Ok(())
+}
+
+/// Row ids of contributors and boards inserted so far
+#[derive(Default)]
+struct Ids<'a> {
+    contributors: HashMap<&'a str, i64>,
+    boards: HashMap<(&'a str, &'a str), i64>,
+}
+
+fn insert_submission<'a>(
+    tx: &Transaction,
+    db: &Database,
+    ids: &mut Ids<'a>,
+    category: &'a str,
+    submission: &ExportSubmission<'a>,
+) -> Result<(), Error> {
+    let contributor_id = match ids.contributors.get(submission.contributor) {
+        Some(&id) => id,
+        None => {
+            tx.prepare_cached("INSERT INTO contributors (name) VALUES (?)")?
+                .execute([submission.contributor])?;
+            let id = tx.last_insert_rowid();
+            ids.contributors.insert(submission.contributor, id);
+            id
+        }
+    };
+    let board_id = match ids.boards.get(&(category, submission.board)) {
+        Some(&id) => id,
+        None => {
+            tx.prepare_cached("INSERT INTO boards (category, name) VALUES (?, ?)")?
+                .execute([category, submission.board])?;
+            let id = tx.last_insert_rowid();
+            ids.boards.insert((category, submission.board), id);
+            id
+        }
+    };
+    let game_code = (category == Category::Cartridge.id())
+        .then_some(submission.code)
+        .filter(|code| db.cfgs.contains_key(*code));
+    tx.prepare_cached(
+        "INSERT INTO submissions (category, code, slug, title, url, outlier, contributor_id, board_id, game_code) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )?
+    .execute(params![
+        category,
+        submission.code,
+        submission.slug,
+        submission.title,
+        submission.url,
+        submission.outlier,
+        contributor_id,
+        board_id,
+        game_code
+    ])?;
+    let submission_id = tx.last_insert_rowid();
+
+    let mut stmt = tx.prepare_cached(
+        "INSERT INTO chips (submission_id, designator, role, label, outlier, kind, manufacturer, year, month, week, rom_code, date_from, date_to) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )?;
+    for chip in &submission.chips {
+        stmt.execute(params![
+            submission_id,
+            chip.designator,
+            chip.role,
+            chip.label,
+            chip.outlier,
+            chip.kind,
+            chip.manufacturer,
+            chip.year,
+            chip.month.map(u8::from),
+            chip.week.map(u8::from),
+            chip.rom_code,
+            chip.date.as_ref().map(|date| &date.from),
+            chip.date.as_ref().map(|date| &date.to)
+        ])?;
+    }
+    let mut stmt =
+        tx.prepare_cached("INSERT INTO photos (submission_id, name, url) VALUES (?, ?, ?)")?;
+    for photo in &submission.photos {
+        stmt.execute(params![submission_id, photo.name, photo.url])?;
+    }
+    Ok(())
+}
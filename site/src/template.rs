@@ -7,6 +7,7 @@ use percy_dom::{html, IterableNodes, View, VirtualNode};
 use crate::site::SiteSection;
 use crate::template::{site_footer::SiteFooter, site_header::SiteHeader};
 
+pub mod battery_stats;
 pub mod cartridge_page;
 pub mod cartridges;
 pub mod console_page;
@@ -18,12 +19,15 @@ pub mod home;
 pub mod listing_chip;
 pub mod listing_entry_cell;
 pub mod listing_photos_cell;
+pub mod mainboard_revision;
 pub mod mapper;
 pub mod markdown;
 pub mod markdown_page;
 pub mod raw_html;
+pub mod responsive_photo;
 pub mod site_footer;
 pub mod site_header;
+pub mod unlicensed_cartridges;
 
 pub fn page(title: &str, section: SiteSection, content: VirtualNode) -> String {
     let content = html! {
@@ -48,6 +52,7 @@ pub fn page(title: &str, section: SiteSection, content: VirtualNode) -> String {
           <div class="site-main__content">{content}</div>
         </main>
         <SiteFooter />
+        <script src="/deep-zoom.js"></script>
       </body>
     </html>
     }
@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use percy_dom::{html, IterableNodes, View, VirtualNode};
+use std::collections::BTreeMap;
+
+use crate::legacy::{HasDateCode, LegacyCartridgeSubmission};
+
+/// Board manufacture years are grouped into eras of this many years
+const ERA_LENGTH: u16 = 5;
+
+pub struct BatteryStats<'a> {
+    pub submissions: &'a [LegacyCartridgeSubmission],
+}
+
+impl<'a> View for BatteryStats<'a> {
+    fn render(&self) -> VirtualNode {
+        let mut vendors = BTreeMap::new();
+        let mut eras: BTreeMap<Option<u16>, BTreeMap<&str, usize>> = BTreeMap::new();
+        for submission in self.submissions {
+            let board = &submission.metadata.board;
+            let battery = match board.bt1.as_ref() {
+                Some(battery) => battery,
+                None => continue,
+            };
+            let vendor = battery.manufacturer.as_deref().unwrap_or("Unknown");
+            *vendors.entry(vendor).or_insert(0) += 1;
+            // Years before the first era are implausible, so they are counted as unknown
+            let era = board
+                .date_code()
+                .year
+                .and_then(|year| Some(year - year.checked_sub(1989)? % ERA_LENGTH));
+            *eras.entry(era).or_default().entry(vendor).or_insert(0) += 1;
+        }
+        html! {
+            <article>
+                <h2>{"Cartridge battery vendors by board era"}</h2>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{"Board era"}</th>
+                            { vendors.keys().map(|&vendor| html! {
+                                <th>{vendor}</th>
+                            }).collect::<Vec<_>>() }
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { eras.iter().map(|(&era, counts)| html! {
+                            <tr>
+                                <td>{era.map(|year| format!("{}-{}", year, year + ERA_LENGTH - 1)).unwrap_or_else(|| "Unknown".to_owned())}</td>
+                                { vendors.keys().map(|vendor| html! {
+                                    <td>{counts.get(vendor).copied().unwrap_or(0).to_string()}</td>
+                                }).collect::<Vec<_>>() }
+                            </tr>
+                        }).collect::<Vec<_>>() }
+                    </tbody>
+                    <tfoot>
+                        <tr>
+                            <td>{"Total"}</td>
+                            { vendors.values().map(|count| html! {
+                                <td>{count.to_string()}</td>
+                            }).collect::<Vec<_>>() }
+                        </tr>
+                    </tfoot>
+                </table>
+            </article>
+        }
+    }
+}
@@ -7,6 +7,7 @@ use percy_dom::{html, IterableNodes, View, VirtualNode};
 use std::convert::identity;
 use time::{format_description::FormatItem, macros::format_description};
 
+use super::responsive_photo::ResponsivePhoto;
 use crate::legacy::{HasDateCode, LegacyCartridgeSubmission, LegacyChip, LegacyPhoto};
 
 pub struct CartridgePage<'a> {
@@ -18,17 +19,12 @@ impl<'a> CartridgePage<'a> {
         CartridgePage { submission }
     }
     pub fn render_photo(&self, photo: &LegacyPhoto) -> VirtualNode {
-        let url = format!(
-            "/static/{code}/{slug}_{name}",
-            code = self.submission.code,
-            slug = self.submission.slug,
-            name = photo.name
-        );
-        html! {
-            <a href={&url}>
-                <img src={&url}>
-            </a>
+        ResponsivePhoto {
+            code: &self.submission.code,
+            slug: &self.submission.slug,
+            photo,
         }
+        .render()
     }
 }
 
@@ -116,6 +112,17 @@ impl<'a> View for CartridgePage<'a> {
                         .collect::<Vec<_>>()
                     }
                 </table>
+                { (!metadata.repro.findings.is_empty()).then(|| html! {
+                    <div>
+                        <h3>{"Reproduction check"}</h3>
+                        <p>{format!("Reproduction likelihood score: {}/100", metadata.repro.score())}</p>
+                        <ul>
+                            { metadata.repro.findings.iter().map(|finding| html! {
+                                <li>{finding.to_string()}</li>
+                            }).collect::<Vec<_>>() }
+                        </ul>
+                    </div>
+                }) }
                 { metadata.dump.as_ref().map(|dump| html! {
                     <div>
                         <h3>{"ROM dump"}</h3>
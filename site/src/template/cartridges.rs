@@ -2,16 +2,18 @@
 //
 // SPDX-License-Identifier: MIT
 
-use gbhwdb_backend::config::cartridge::{ChipRole, ChipRoleConfig, GameConfig, GamePlatform};
+use gbhwdb_backend::{
+    config::cartridge::{ChipRole, ChipRoleConfig, GameConfig, GamePlatform},
+    db::export::SCHEMA_VERSION,
+};
 use itertools::Itertools;
 use percy_dom::{html, IterableNodes, View, VirtualNode};
 use std::{borrow::Cow, collections::BTreeMap};
 
-use super::mapper::MapperCfg;
+use super::mapper::MapperPage;
 use crate::legacy::LegacyCartridgeSubmission;
 
 pub struct Cartridges<'a> {
-    pub mapper_cfgs: &'a [MapperCfg],
     pub cfgs: &'a BTreeMap<String, GameConfig>,
     pub submissions: &'a [LegacyCartridgeSubmission],
 }
@@ -29,17 +31,28 @@ impl<'a> View for Cartridges<'a> {
             per_game.push((cfg, group.collect::<Vec<_>>()));
         }
         per_game.sort_by_key(|(cfg, _)| &cfg.name);
+        let mapper_pages = self
+            .submissions
+            .iter()
+            .filter_map(MapperPage::of)
+            .unique()
+            .sorted()
+            .collect::<Vec<_>>();
         html! {
             <article>
                 <h2>{"Game Boy cartridges"}</h2>
                 <h3>{"Cartridges by mapper"}</h3>
                 <ul class="cartridges__mapper-list">
-                { self.mapper_cfgs.iter().map(|cfg| html! {
+                { mapper_pages.iter().map(|page| html! {
                     <li>
-                        <a href={format!("/cartridges/{}.html", cfg.id)}>{cfg.name}</a>
+                        <a href={format!("/cartridges/{}.html", page.id())}>{page.name()}</a>
                     </li>
                 }).collect::<Vec<_>>() }
                 </ul>
+                <h3>{"Unlicensed cartridges"}</h3>
+                <a href="/cartridges/unlicensed.html">{"Unlicensed and third-party cartridges"}</a>
+                <h3>{"Statistics"}</h3>
+                <a href="/cartridges/batteries.html">{"Battery vendors by board era"}</a>
                 <h3>{"Cartridges by game"}</h3>
                 <table>
                     <thead>
@@ -60,7 +73,7 @@ impl<'a> View for Cartridges<'a> {
                     </tbody>
                     <tbody>
                         { per_game.iter()
-                            .filter(|(cfg, _)| cfg.platform == GamePlatform::Gb)
+                            .filter(|(cfg, _)| cfg.platform == GamePlatform::Gb && !cfg.is_unlicensed())
                             .map(|(cfg, submissions)| render_game(cfg, submissions))
                             .collect::<Vec<_>>()
                         }
@@ -72,7 +85,7 @@ impl<'a> View for Cartridges<'a> {
                     </tbody>
                     <tbody>
                         { per_game.iter()
-                            .filter(|(cfg, _)| cfg.platform == GamePlatform::Gbc)
+                            .filter(|(cfg, _)| cfg.platform == GamePlatform::Gbc && !cfg.is_unlicensed())
                             .map(|(cfg, submissions)| render_game(cfg, submissions))
                             .collect::<Vec<_>>()
                         }
@@ -80,6 +93,12 @@ impl<'a> View for Cartridges<'a> {
                 </table>
                 <h3>{"Data dumps"}</h3>
                 <a href="/static/export/cartridges.csv">{"UTF-8 encoded CSV"}</a>
+                {" | "}
+                <a href="/static/export/json/cartridges.json">{format!("JSON (schema version {SCHEMA_VERSION})")}</a>
+                {" | "}
+                <a href="/static/export/gbhwdb.sqlite">{"SQLite database of all submissions"}</a>
+                {" | "}
+                <a href="/static/export/chips.csv">{"CSV of all chips, one per row"}</a>
             </article>
         }
     }
@@ -99,14 +118,7 @@ fn render_game(cfg: &GameConfig, submissions: &[&LegacyCartridgeSubmission]) ->
     let board_types = submissions
         .iter()
         .map(|submission| Cow::Borrowed(submission.metadata.board.kind.as_ref()));
-    let mappers = submissions.iter().filter_map(|submission| {
-        let roles = ChipRoleConfig::from(submission.metadata.board.layout);
-        let chip = roles
-            .iter()
-            .find(|&(_, role)| role == ChipRole::Mapper)
-            .and_then(|(designator, _)| submission.metadata.board[designator].as_ref());
-        chip.and_then(|chip| chip.kind.as_deref().map(Cow::Borrowed))
-    });
+    let mappers = mappers(submissions);
     html! {
         <tr>
             <td class="submission-list-item">
@@ -122,7 +134,20 @@ fn render_game(cfg: &GameConfig, submissions: &[&LegacyCartridgeSubmission]) ->
     }
 }
 
-fn multiline<'a>(lines: impl Iterator<Item = Cow<'a, str>>) -> IterableNodes {
+pub fn mappers<'a>(
+    submissions: &'a [&'a LegacyCartridgeSubmission],
+) -> impl Iterator<Item = Cow<'a, str>> {
+    submissions.iter().filter_map(|submission| {
+        let roles = ChipRoleConfig::from(submission.metadata.board.layout);
+        let chip = roles
+            .iter()
+            .find(|&(_, role)| role == ChipRole::Mapper)
+            .and_then(|(designator, _)| submission.metadata.board[designator].as_ref());
+        chip.and_then(|chip| chip.kind.as_deref().map(Cow::Borrowed))
+    })
+}
+
+pub fn multiline<'a>(lines: impl Iterator<Item = Cow<'a, str>>) -> IterableNodes {
     let lines = lines.unique().sorted();
     lines
         .map(|line| {
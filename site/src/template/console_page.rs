@@ -4,6 +4,7 @@
 
 use percy_dom::{html, IterableNodes, View, VirtualNode};
 
+use super::responsive_photo::ResponsivePhoto;
 use crate::legacy::{
     console::{ChipInfo, LegacyConsoleMetadata},
     HasDateCode, LegacyChip, LegacyPhoto, LegacyPhotos, LegacySubmission, PhotoInfo, PhotoKind,
@@ -31,17 +32,12 @@ impl<'a, M: LegacyConsoleMetadata, P: LegacyPhotos> ConsolePage<'a, M, P> {
         (photo.getter)(&self.submission.photos).map(|photo| self.render_photo(photo))
     }
     pub fn render_photo(&self, photo: &LegacyPhoto) -> VirtualNode {
-        let url = format!(
-            "/static/{console}/{slug}_{name}",
-            console = M::CONSOLE.id(),
-            slug = self.submission.slug,
-            name = photo.name
-        );
-        html! {
-            <a href={&url}>
-                <img src={&url}>
-            </a>
+        ResponsivePhoto {
+            code: M::CONSOLE.id(),
+            slug: &self.submission.slug,
+            photo,
         }
+        .render()
     }
 }
 
@@ -107,7 +103,12 @@ impl<'a, M: LegacyConsoleMetadata, P: LegacyPhotos> View for ConsolePage<'a, M,
                 </div>
                 <dl>
                     <dt>{"Board type"}</dt>
-                    <dd>{mainboard.kind}</dd>
+                    <dd>{ match metadata.mainboard_revision() {
+                        Some(revision) => html! {
+                            <a href={format!("/consoles/{}/mainboards/{}.html", M::CONSOLE.id(), revision.slug())}>{mainboard.kind}</a>
+                        },
+                        None => mainboard.kind.into(),
+                    } }</dd>
                     { mainboard.date_code.calendar().into_iter().flat_map(|date| {
                         [
                             html!{ <dt>{"Manufacture date"}</dt> },
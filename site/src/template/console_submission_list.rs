@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: MIT
 
+use gbhwdb_backend::db::export::SCHEMA_VERSION;
 use percy_dom::{html, IterableNodes, View, VirtualNode};
 
 use super::{listing_entry_cell::ListingEntryCell, listing_photos_cell::ListingPhotosCell};
@@ -76,6 +77,12 @@ impl<'a, M: LegacyConsoleMetadata, P: LegacyPhotos> View for ConsoleSubmissionLi
                 </table>
                 <h3>{"Data dumps"}</h3>
                 <a href={format!("/static/export/consoles/{id}.csv", id=console.id())}>{"UTF-8 encoded CSV"}</a>
+                {" | "}
+                <a href={format!("/static/export/json/consoles/{id}.json", id=console.id())}>{format!("JSON (schema version {SCHEMA_VERSION})")}</a>
+                {" | "}
+                <a href="/static/export/gbhwdb.sqlite">{"SQLite database of all submissions"}</a>
+                {" | "}
+                <a href="/static/export/chips.csv">{"CSV of all chips, one per row"}</a>
             </article>
         }
     }
@@ -124,7 +131,12 @@ impl<'a, M: LegacyConsoleMetadata, P: LegacyPhotos> View for Submission<'a, M, P
                     </td>
                 }) }
                 <td>
-                    <div>{metadata.mainboard().kind}</div>
+                    <div>{ match metadata.mainboard_revision() {
+                        Some(revision) => html! {
+                            <a href={format!("/consoles/{}/mainboards/{}.html", M::CONSOLE.id(), revision.slug())}>{metadata.mainboard().kind}</a>
+                        },
+                        None => metadata.mainboard().kind.into(),
+                    } }</div>
                     {metadata.mainboard().date_code.calendar_short().map(|date_code| {
                         html! {
                             <div>{format!("{date_code}")}</div>
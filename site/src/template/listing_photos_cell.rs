@@ -14,10 +14,13 @@ impl<'a, M, P> ListingPhotosCell<'a, M, P> {
     fn render_photo(&self, label: &'static str, photo: &LegacyPhoto) -> VirtualNode {
         let code = &self.submission.code;
         let slug = &self.submission.slug;
+        let preview = photo.preview_name();
         let name = &photo.name;
         html! {
             <div>
-                <a href={format!("/static/{code}/{slug}_{name}")}>{label}</a>
+                <a href={format!("/static/{code}/{slug}_{preview}")}>{label}</a>
+                {" "}
+                <a href={format!("/static/{code}/{slug}_{name}")} title="Full resolution">{"(full)"}</a>
             </div>
         }
     }
@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use gbhwdb_backend::config::{console::console_board_chips, mainboard::MainboardRevision};
+use percy_dom::{html, IterableNodes, View, VirtualNode};
+
+use super::{
+    listing_chip::ListingChip, listing_entry_cell::ListingEntryCell,
+    listing_photos_cell::ListingPhotosCell,
+};
+use crate::legacy::{
+    console::{ChipInfo, LegacyConsoleMetadata},
+    LegacyPhotos, LegacySubmission,
+};
+
+/// Lists the expected chips of a mainboard revision, and all units that have that mainboard
+pub struct MainboardRevisionPage<'a, M, P> {
+    pub revision: &'static MainboardRevision,
+    pub submissions: Vec<&'a LegacySubmission<M, P>>,
+}
+
+impl<'a, M: LegacyConsoleMetadata, P: LegacyPhotos> View for MainboardRevisionPage<'a, M, P> {
+    fn render(&self) -> VirtualNode {
+        let console = M::CONSOLE;
+        let board_chips = console_board_chips(console, self.revision.label);
+        let chips = board_chips
+            .iter()
+            .map(ChipInfo::<M>::from_config)
+            .collect::<Vec<_>>();
+        html! {
+            <article>
+                <h2>{format!("{} ({}): {}", console.name(), console.code(), self.revision.label)}</h2>
+                <h3>{"Expected chips"}</h3>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{"Chip"}</th>
+                            <th>{"Expected"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { board_chips.iter().filter_map(|chip| {
+                            let rule = self.revision.rule(chip.role)?;
+                            let expected = if rule.kinds.is_empty() {
+                                "Not populated".to_owned()
+                            } else {
+                                rule.kinds.join(", ")
+                            };
+                            Some(html! {
+                                <tr>
+                                    <td>{format!("{} ({})", chip.role.display(), chip.designator.as_str())}</td>
+                                    <td>{expected}</td>
+                                </tr>
+                            })
+                        }).collect::<Vec<_>>() }
+                    </tbody>
+                </table>
+                <h3>{format!("Units ({})", self.submissions.len())}</h3>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{"Submission"}</th>
+                            { chips.iter().map(|chip| html! {
+                                <th>{format!("{} ({})", chip.label, chip.designator)}</th>
+                            }).collect::<Vec<_>>() }
+                            <th>{"Deviations"}</th>
+                            <th>{"Photos"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { self.submissions.iter().map(|&submission| {
+                            render_submission(&chips, submission)
+                        }).collect::<Vec<_>>() }
+                    </tbody>
+                </table>
+            </article>
+        }
+    }
+}
+
+fn render_submission<M: LegacyConsoleMetadata, P: LegacyPhotos>(
+    chips: &[ChipInfo<M>],
+    submission: &LegacySubmission<M, P>,
+) -> VirtualNode {
+    let metadata = &submission.metadata;
+    html! {
+        <tr>
+            { ListingEntryCell {
+                url_prefix: "/consoles",
+                primary_text: &submission.title,
+                secondary_texts: &[],
+                submission,
+            }.render() }
+            { chips.iter().map(|chip| {
+                ListingChip {
+                    chip: (chip.getter)(metadata),
+                    hide_type: chip.hide_type,
+                }.render()
+            }).collect::<Vec<_>>() }
+            <td>
+                { metadata.mainboard_deviations().iter().map(|deviation| html! {
+                    <div>{deviation.to_string()}</div>
+                }).collect::<Vec<_>>() }
+            </td>
+            { ListingPhotosCell {
+                submission,
+            }.render() }
+        </tr>
+    }
+}
@@ -2,8 +2,13 @@
 //
 // SPDX-License-Identifier: MIT
 
-use gbhwdb_backend::config::cartridge::{BoardLayout, ChipRole, ChipRoleConfig};
+use gbhwdb_backend::{
+    config::cartridge::{BoardLayout, ChipRole, ChipRoleConfig},
+    parser::{MapperFamily, MapperType},
+};
+use itertools::Itertools;
 use percy_dom::{html, IterableNodes, View, VirtualNode};
+use std::collections::BTreeMap;
 
 use super::{
     listing_chip::ListingChip, listing_entry_cell::ListingEntryCell,
@@ -11,49 +16,142 @@ use super::{
 };
 use crate::legacy::{HasDateCode, LegacyCartridgeSubmission, LegacyChip};
 
-pub struct MapperCfg {
-    pub id: &'static str,
-    pub name: &'static str,
-    pub chips: &'static [ChipRole],
-    pub match_fn: Box<dyn Fn(BoardLayout, Option<&LegacyChip>) -> bool + Send + Sync>,
+/// A "cartridges by mapper" page. Every mapper family seen in the submissions gets its own page
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MapperPage {
+    NoMapper,
+    Mapper(MapperFamily),
+}
+
+impl MapperPage {
+    pub fn of(submission: &LegacyCartridgeSubmission) -> Option<MapperPage> {
+        let board = &submission.metadata.board;
+        match (board.layout, board.mapper) {
+            (_, Some(mapper)) => Some(MapperPage::Mapper(mapper.family())),
+            (BoardLayout::Rom | BoardLayout::UnlRom, None) => Some(MapperPage::NoMapper),
+            _ => None,
+        }
+    }
+    pub fn id(&self) -> &'static str {
+        match self {
+            MapperPage::NoMapper => "no-mapper",
+            MapperPage::Mapper(family) => family.id(),
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapperPage::NoMapper => "No mapper",
+            MapperPage::Mapper(family) => family.name(),
+        }
+    }
 }
 
 pub struct Mapper<'a> {
-    pub cfg: &'a MapperCfg,
+    pub page: MapperPage,
     pub submissions: Vec<&'a LegacyCartridgeSubmission>,
 }
 
 impl<'a> View for Mapper<'a> {
     fn render(&self) -> VirtualNode {
+        let roles = self
+            .submissions
+            .iter()
+            .flat_map(|submission| {
+                ChipRoleConfig::from(submission.metadata.board.layout)
+                    .iter()
+                    .map(|(_, role)| role)
+                    .collect::<Vec<_>>()
+            })
+            .unique()
+            .collect::<Vec<_>>();
+        let versions = self
+            .submissions
+            .iter()
+            .map(|&submission| (submission.metadata.board.mapper, submission))
+            .into_group_map()
+            .into_iter()
+            .sorted_by_key(|&(version, _)| version)
+            .collect::<Vec<_>>();
         return html! {
             <article>
-                <h2>{format!("Cartridges by mapper: {}", self.cfg.name)}</h2>
-                <table>
-                    <thead>
-                        <tr>
-                            <th>{"Entry"}</th>
-                            <th>{"Release"}</th>
-                            <th>{"Board"}</th>
-                            { self.cfg.chips.iter().map(|role| html! {
-                                <th>{role.display()}</th>
-                            }).collect::<Vec<_>>() }
-                            <th>{"Photos"}</th>
-                        </tr>
-                    </thead>
-                    <tbody>
-                        { self.submissions.iter().map(|&submission| {
-                            render_submission(self.cfg, submission)
-                        }).collect::<Vec<_>>() }
-                    </tbody>
-                </table>
+                <h2>{format!("Cartridges by mapper: {}", self.page.name())}</h2>
+                { versions.iter().map(|(version, submissions)| {
+                    render_version(*version, &roles, submissions)
+                }).collect::<Vec<_>>() }
             </article>
         };
     }
 }
 
-fn render_submission(cfg: &MapperCfg, submission: &LegacyCartridgeSubmission) -> VirtualNode {
+fn render_version(
+    version: Option<MapperType>,
+    roles: &[ChipRole],
+    submissions: &[&LegacyCartridgeSubmission],
+) -> VirtualNode {
+    let mapper_chips = submissions
+        .iter()
+        .filter_map(|submission| find_chip(submission, ChipRole::Mapper))
+        .collect::<Vec<_>>();
+    let years = mapper_chips
+        .iter()
+        .filter_map(|chip| chip.date_code().year)
+        .minmax()
+        .into_option();
+    let mut manufacturers = BTreeMap::new();
+    for chip in &mapper_chips {
+        let manufacturer = chip.manufacturer.as_deref().unwrap_or("Unknown");
+        *manufacturers.entry(manufacturer).or_insert(0) += 1;
+    }
+    html! {
+        <section>
+            <h3>{version.map(|version| version.display_name()).unwrap_or("No mapper")}</h3>
+            <dl>
+                <dt>{"Submissions"}</dt>
+                <dd>{submissions.len().to_string()}</dd>
+                { years.into_iter().flat_map(|(from, to)| {
+                    [
+                        html!{ <dt>{"Date range"}</dt> },
+                        html!{ <dd>{if from == to { from.to_string() } else { format!("{from}-{to}") }}</dd> },
+                    ]
+                }).collect::<Vec<_>>() }
+                { (!manufacturers.is_empty()).then(|| html! {
+                    <dt>{"Manufacturers"}</dt>
+                }) }
+                { manufacturers.iter().map(|(manufacturer, count)| html! {
+                    <dd>{format!("{manufacturer}: {count}")}</dd>
+                }).collect::<Vec<_>>() }
+            </dl>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{"Entry"}</th>
+                        <th>{"Release"}</th>
+                        <th>{"Board"}</th>
+                        { roles.iter().map(|role| html! {
+                            <th>{role.display()}</th>
+                        }).collect::<Vec<_>>() }
+                        <th>{"Photos"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { submissions.iter().map(|&submission| {
+                        render_submission(roles, submission)
+                    }).collect::<Vec<_>>() }
+                </tbody>
+            </table>
+        </section>
+    }
+}
+
+fn find_chip(submission: &LegacyCartridgeSubmission, role: ChipRole) -> Option<&LegacyChip> {
+    ChipRoleConfig::from(submission.metadata.board.layout)
+        .iter()
+        .find(|&(_, candidate)| candidate == role)
+        .and_then(|(designator, _)| submission.metadata.board[designator].as_ref())
+}
+
+fn render_submission(roles: &[ChipRole], submission: &LegacyCartridgeSubmission) -> VirtualNode {
     let metadata = &submission.metadata;
-    let chips = ChipRoleConfig::from(submission.metadata.board.layout);
     html! {
         <tr>
             { ListingEntryCell {
@@ -67,11 +165,9 @@ fn render_submission(cfg: &MapperCfg, submission: &LegacyCartridgeSubmission) ->
                 <div>{&metadata.board.kind}</div>
                 <div>{metadata.board.date_code().calendar().unwrap_or_default()}</div>
             </td>
-            { cfg.chips.iter().map(|&role| {
-                let chip = chips.iter().find(|&(_, candidate)| candidate == role)
-                    .and_then(|(designator, _)| submission.metadata.board[designator].as_ref());
+            { roles.iter().map(|&role| {
                 ListingChip {
-                    chip,
+                    chip: find_chip(submission, role),
                     hide_type: false,
                 }.render()
             }).collect::<Vec<_>>() }
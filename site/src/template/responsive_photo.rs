@@ -0,0 +1,107 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use percy_dom::{html, AttributeValue, VElement, View, VirtualNode};
+
+use crate::legacy::LegacyPhoto;
+
+/// Photos are shown two per row, so they take at most half of the widest page layout
+const SIZES: &str = "(min-width: 1440px) 720px, 50vw";
+
+/// Photo rendered as a `<picture>` with WebP and JPEG versions in all generated widths, a
+/// separate link to the full resolution original, and a zoom button for photos with a Deep Zoom
+/// tile pyramid
+pub struct ResponsivePhoto<'a> {
+    /// Console id or game code
+    pub code: &'a str,
+    pub slug: &'a str,
+    pub photo: &'a LegacyPhoto,
+}
+
+impl<'a> ResponsivePhoto<'a> {
+    fn url(&self, name: &str) -> String {
+        format!(
+            "/static/{code}/{slug}_{name}",
+            code = self.code,
+            slug = self.slug
+        )
+    }
+    fn srcset(&self, extension: &str) -> String {
+        self.photo
+            .widths
+            .iter()
+            .map(|&width| {
+                let url = self.url(&self.photo.scaled_name(width, extension));
+                format!("{url} {width}w")
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+    fn render_picture(&self) -> VirtualNode {
+        let mut img = VElement::new("img");
+        img.attrs.insert(
+            "src".into(),
+            AttributeValue::String(self.url(&self.photo.preview_name())),
+        );
+        img.attrs
+            .insert("loading".into(), AttributeValue::String("lazy".into()));
+        if self.photo.widths.is_empty() {
+            return img.into();
+        }
+        img.attrs
+            .insert("srcset".into(), AttributeValue::String(self.srcset("jpg")));
+        img.attrs
+            .insert("sizes".into(), AttributeValue::String(SIZES.into()));
+
+        let mut webp = VElement::new("source");
+        webp.attrs
+            .insert("type".into(), AttributeValue::String("image/webp".into()));
+        webp.attrs
+            .insert("srcset".into(), AttributeValue::String(self.srcset("webp")));
+        webp.attrs
+            .insert("sizes".into(), AttributeValue::String(SIZES.into()));
+
+        let mut picture = VElement::new("picture");
+        picture.children.push(webp.into());
+        picture.children.push(img.into());
+        picture.into()
+    }
+    /// Renders a button that opens the photo in the pan-and-zoom viewer (`deep-zoom.js`), if the
+    /// photo has a tile pyramid
+    fn render_zoom(&self) -> Option<VirtualNode> {
+        if !self.photo.deep_zoom {
+            return None;
+        }
+        let mut button = VElement::new("button");
+        button
+            .attrs
+            .insert("type".into(), AttributeValue::String("button".into()));
+        button.attrs.insert(
+            "class".into(),
+            AttributeValue::String("responsive-photo__zoom".into()),
+        );
+        button.attrs.insert(
+            "data-dzi".into(),
+            AttributeValue::String(self.url(&self.photo.deep_zoom_name())),
+        );
+        button.children.push(VirtualNode::text("Zoom"));
+        Some(button.into())
+    }
+}
+
+impl<'a> View for ResponsivePhoto<'a> {
+    fn render(&self) -> VirtualNode {
+        html! {
+            <div class="responsive-photo">
+                <a href={self.url(&self.photo.preview_name())}>
+                    { self.render_picture() }
+                </a>
+                <a class="responsive-photo__original" href={self.url(&self.photo.name)}>
+                    {"Full resolution"}
+                </a>
+                { self.render_zoom().into_iter() }
+            </div>
+        }
+    }
+}
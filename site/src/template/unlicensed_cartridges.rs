@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use gbhwdb_backend::config::cartridge::GameConfig;
+use itertools::Itertools;
+use percy_dom::{html, IterableNodes, View, VirtualNode};
+use std::{borrow::Cow, collections::BTreeMap};
+
+use super::cartridges::{mappers, multiline};
+use crate::legacy::LegacyCartridgeSubmission;
+
+pub struct UnlicensedCartridges<'a> {
+    pub cfgs: &'a BTreeMap<String, GameConfig>,
+    pub submissions: &'a [LegacyCartridgeSubmission],
+}
+
+impl<'a> View for UnlicensedCartridges<'a> {
+    fn render(&self) -> VirtualNode {
+        let mut per_game = Vec::new();
+        for (code, group) in &self
+            .submissions
+            .iter()
+            .filter(|submission| submission.metadata.cfg.is_unlicensed())
+            .sorted_by_key(|submission| &submission.code)
+            .group_by(|submission| &submission.code)
+        {
+            let cfg = &self.cfgs[code];
+            per_game.push((cfg, group.collect::<Vec<_>>()));
+        }
+        per_game.sort_by_key(|(cfg, _)| (&cfg.publisher, &cfg.name));
+        html! {
+            <article>
+                <h2>{"Unlicensed and third-party cartridges"}</h2>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{"Title"}</th>
+                            <th>{"Publisher"}</th>
+                            <th>{"Code"}</th>
+                            <th>{"Platform"}</th>
+                            <th>{"Year(s)"}</th>
+                            <th>{"Board type(s)"}</th>
+                            <th>{"Mapper(s)"}</th>
+                            <th>{"Submissions"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { per_game.iter()
+                            .map(|(cfg, submissions)| render_game(cfg, submissions))
+                            .collect::<Vec<_>>()
+                        }
+                    </tbody>
+                </table>
+            </article>
+        }
+    }
+}
+
+fn render_game(cfg: &GameConfig, submissions: &[&LegacyCartridgeSubmission]) -> VirtualNode {
+    let years = submissions.iter().filter_map(|submission| {
+        submission
+            .metadata
+            .board
+            .year
+            .map(|year| Cow::Owned(year.to_string()))
+    });
+    let board_types = submissions
+        .iter()
+        .map(|submission| Cow::Borrowed(submission.metadata.board.kind.as_ref()));
+    html! {
+        <tr>
+            <td class="submission-list-item">
+                <a class="submission-list-item__link" href={format!("/cartridges/{}", cfg.rom_id)}>{&cfg.name}</a>
+            </td>
+            <td>{cfg.publisher.as_deref().unwrap_or_default()}</td>
+            <td>{&cfg.rom_id}</td>
+            <td>{cfg.platform.to_string()}</td>
+            <td>{multiline(years)}</td>
+            <td>{multiline(board_types)}</td>
+            <td>{multiline(mappers(submissions))}</td>
+            <td>{submissions.len()}</td>
+        </tr>
+    }
+}
@@ -0,0 +1,272 @@
+// Pan-and-zoom viewer for Deep Zoom (DZI) tile pyramids.
+//
+// Buttons with a data-dzi attribute open the viewer. Only the tiles of the level that matches the
+// current zoom are loaded, and already loaded tiles of lower levels are drawn behind them while
+// they load.
+(function () {
+  'use strict';
+
+  var MAX_SCALE = 4;
+
+  function parseDescriptor(url, text) {
+    var doc = new DOMParser().parseFromString(text, 'application/xml');
+    var image = doc.getElementsByTagName('Image')[0];
+    var size = doc.getElementsByTagName('Size')[0];
+    if (!image || !size) {
+      throw new Error('Invalid Deep Zoom descriptor ' + url);
+    }
+    var width = parseInt(size.getAttribute('Width'), 10);
+    var height = parseInt(size.getAttribute('Height'), 10);
+    return {
+      tileBase: url.replace(/\.dzi$/, '_files/'),
+      format: image.getAttribute('Format'),
+      tileSize: parseInt(image.getAttribute('TileSize'), 10),
+      overlap: parseInt(image.getAttribute('Overlap'), 10),
+      width: width,
+      height: height,
+      maxLevel: Math.ceil(Math.log2(Math.max(width, height, 1))),
+    };
+  }
+
+  function Viewer(dzi) {
+    this.dzi = dzi;
+    this.tiles = new Map();
+    this.pointers = new Map();
+    this.frame = null;
+
+    this.root = document.createElement('div');
+    this.root.className = 'deep-zoom';
+    this.canvas = document.createElement('canvas');
+    this.canvas.className = 'deep-zoom__canvas';
+    var close = document.createElement('button');
+    close.type = 'button';
+    close.className = 'deep-zoom__close';
+    close.textContent = 'Close';
+    close.addEventListener('click', this.close.bind(this));
+    this.root.appendChild(this.canvas);
+    this.root.appendChild(close);
+    document.body.appendChild(this.root);
+
+    this.onKeyDown = this.onKeyDown.bind(this);
+    this.onResize = this.onResize.bind(this);
+    document.addEventListener('keydown', this.onKeyDown);
+    window.addEventListener('resize', this.onResize);
+    this.canvas.addEventListener('wheel', this.onWheel.bind(this), { passive: false });
+    this.canvas.addEventListener('dblclick', this.onDoubleClick.bind(this));
+    this.canvas.addEventListener('pointerdown', this.onPointerDown.bind(this));
+    this.canvas.addEventListener('pointermove', this.onPointerMove.bind(this));
+    this.canvas.addEventListener('pointerup', this.onPointerUp.bind(this));
+    this.canvas.addEventListener('pointercancel', this.onPointerUp.bind(this));
+
+    this.resizeCanvas();
+    this.fit();
+    close.focus();
+  }
+
+  Viewer.prototype.close = function () {
+    document.removeEventListener('keydown', this.onKeyDown);
+    window.removeEventListener('resize', this.onResize);
+    if (this.frame !== null) {
+      cancelAnimationFrame(this.frame);
+    }
+    this.root.remove();
+  };
+
+  Viewer.prototype.resizeCanvas = function () {
+    var ratio = window.devicePixelRatio || 1;
+    this.viewWidth = this.canvas.clientWidth;
+    this.viewHeight = this.canvas.clientHeight;
+    this.canvas.width = Math.round(this.viewWidth * ratio);
+    this.canvas.height = Math.round(this.viewHeight * ratio);
+  };
+
+  // Scales the whole photo to fit the viewer
+  Viewer.prototype.fit = function () {
+    var dzi = this.dzi;
+    this.minScale = Math.min(this.viewWidth / dzi.width, this.viewHeight / dzi.height, 1);
+    this.scale = this.minScale;
+    this.x = (this.viewWidth - dzi.width * this.scale) / 2;
+    this.y = (this.viewHeight - dzi.height * this.scale) / 2;
+    this.redraw();
+  };
+
+  // Zooms by the given factor, keeping the photo point under (viewX, viewY) in place
+  Viewer.prototype.zoom = function (factor, viewX, viewY) {
+    var scale = Math.min(Math.max(this.scale * factor, this.minScale), MAX_SCALE);
+    this.x = viewX - ((viewX - this.x) * scale) / this.scale;
+    this.y = viewY - ((viewY - this.y) * scale) / this.scale;
+    this.scale = scale;
+    this.redraw();
+  };
+
+  Viewer.prototype.redraw = function () {
+    if (this.frame === null) {
+      this.frame = requestAnimationFrame(
+        function () {
+          this.frame = null;
+          this.draw();
+        }.bind(this)
+      );
+    }
+  };
+
+  Viewer.prototype.tile = function (level, column, row, load) {
+    var key = level + '/' + column + '_' + row;
+    var tile = this.tiles.get(key);
+    if (!tile && load) {
+      tile = new Image();
+      tile.onload = this.redraw.bind(this);
+      tile.src = this.dzi.tileBase + key + '.' + this.dzi.format;
+      this.tiles.set(key, tile);
+    }
+    return tile && tile.complete && tile.naturalWidth > 0 ? tile : null;
+  };
+
+  Viewer.prototype.drawLevel = function (ctx, level, load) {
+    var dzi = this.dzi;
+    // Size of one level pixel in view pixels
+    var levelScale = this.scale * Math.pow(2, dzi.maxLevel - level);
+    var levelWidth = Math.ceil(dzi.width / Math.pow(2, dzi.maxLevel - level));
+    var levelHeight = Math.ceil(dzi.height / Math.pow(2, dzi.maxLevel - level));
+    var tileSize = dzi.tileSize;
+    var firstColumn = Math.max(Math.floor(-this.x / levelScale / tileSize), 0);
+    var firstRow = Math.max(Math.floor(-this.y / levelScale / tileSize), 0);
+    var lastColumn = Math.min(
+      Math.floor((this.viewWidth - this.x) / levelScale / tileSize),
+      Math.ceil(levelWidth / tileSize) - 1
+    );
+    var lastRow = Math.min(
+      Math.floor((this.viewHeight - this.y) / levelScale / tileSize),
+      Math.ceil(levelHeight / tileSize) - 1
+    );
+    for (var row = firstRow; row <= lastRow; row++) {
+      for (var column = firstColumn; column <= lastColumn; column++) {
+        var tile = this.tile(level, column, row, load);
+        if (tile) {
+          var left = column * tileSize - (column > 0 ? dzi.overlap : 0);
+          var top = row * tileSize - (row > 0 ? dzi.overlap : 0);
+          ctx.drawImage(
+            tile,
+            this.x + left * levelScale,
+            this.y + top * levelScale,
+            tile.naturalWidth * levelScale,
+            tile.naturalHeight * levelScale
+          );
+        }
+      }
+    }
+  };
+
+  Viewer.prototype.draw = function () {
+    var ratio = this.canvas.width / Math.max(this.viewWidth, 1);
+    var ctx = this.canvas.getContext('2d');
+    ctx.setTransform(1, 0, 0, 1, 0, 0);
+    ctx.clearRect(0, 0, this.canvas.width, this.canvas.height);
+    ctx.setTransform(ratio, 0, 0, ratio, 0, 0);
+    // The level whose pixels are closest to (but not smaller than) physical screen pixels
+    var level = this.dzi.maxLevel + Math.ceil(Math.log2(this.scale * ratio));
+    level = Math.min(Math.max(level, 0), this.dzi.maxLevel);
+    for (var lower = 0; lower < level; lower++) {
+      this.drawLevel(ctx, lower, false);
+    }
+    this.drawLevel(ctx, level, true);
+  };
+
+  Viewer.prototype.viewPosition = function (event) {
+    var rect = this.canvas.getBoundingClientRect();
+    return { x: event.clientX - rect.left, y: event.clientY - rect.top };
+  };
+
+  Viewer.prototype.onKeyDown = function (event) {
+    var centerX = this.viewWidth / 2;
+    var centerY = this.viewHeight / 2;
+    if (event.key === 'Escape') {
+      this.close();
+    } else if (event.key === '+' || event.key === '=') {
+      this.zoom(2, centerX, centerY);
+    } else if (event.key === '-') {
+      this.zoom(0.5, centerX, centerY);
+    } else if (event.key === '0') {
+      this.fit();
+    }
+  };
+
+  Viewer.prototype.onResize = function () {
+    this.resizeCanvas();
+    this.fit();
+  };
+
+  Viewer.prototype.onWheel = function (event) {
+    event.preventDefault();
+    var position = this.viewPosition(event);
+    this.zoom(Math.pow(2, -event.deltaY / 300), position.x, position.y);
+  };
+
+  Viewer.prototype.onDoubleClick = function (event) {
+    var position = this.viewPosition(event);
+    this.zoom(event.shiftKey ? 0.5 : 2, position.x, position.y);
+  };
+
+  Viewer.prototype.onPointerDown = function (event) {
+    this.canvas.setPointerCapture(event.pointerId);
+    this.pointers.set(event.pointerId, this.viewPosition(event));
+  };
+
+  Viewer.prototype.onPointerMove = function (event) {
+    var previous = this.pointers.get(event.pointerId);
+    if (!previous) {
+      return;
+    }
+    var position = this.viewPosition(event);
+    if (this.pointers.size === 1) {
+      this.x += position.x - previous.x;
+      this.y += position.y - previous.y;
+      this.redraw();
+    } else if (this.pointers.size === 2) {
+      // Pinch zoom around the midpoint of the two pointers
+      var other = null;
+      this.pointers.forEach(function (value, id) {
+        if (id !== event.pointerId) {
+          other = value;
+        }
+      });
+      var before = Math.hypot(previous.x - other.x, previous.y - other.y);
+      var after = Math.hypot(position.x - other.x, position.y - other.y);
+      if (before > 0) {
+        this.zoom(after / before, (position.x + other.x) / 2, (position.y + other.y) / 2);
+      }
+    }
+    this.pointers.set(event.pointerId, position);
+  };
+
+  Viewer.prototype.onPointerUp = function (event) {
+    this.pointers.delete(event.pointerId);
+  };
+
+  function open(url) {
+    fetch(url)
+      .then(function (response) {
+        if (!response.ok) {
+          throw new Error('Failed to load ' + url + ': ' + response.status);
+        }
+        return response.text();
+      })
+      .then(function (text) {
+        new Viewer(parseDescriptor(url, text));
+      })
+      .catch(function (err) {
+        console.error(err);
+      });
+  }
+
+  var buttons = document.querySelectorAll('button[data-dzi]');
+  if (buttons.length === 0 || !window.fetch || !window.DOMParser) {
+    return;
+  }
+  document.documentElement.classList.add('deep-zoom-enabled');
+  buttons.forEach(function (button) {
+    button.addEventListener('click', function () {
+      open(button.getAttribute('data-dzi'));
+    });
+  });
+})();
@@ -4,7 +4,7 @@
 
 use anyhow::{anyhow, Error};
 use cursive::{traits::*, view::Margins, views::*, Cursive, CursiveExt};
-use gbhwdb_backend::config::cartridge::{BoardLayout, GameConfig, GamePlatform};
+use gbhwdb_backend::config::cartridge::{write_cfgs, BoardLayout, GameConfig, GamePlatform};
 use gbhwdb_tools::{cursive::*, dat::DatFile};
 use glob::glob;
 use itertools::Itertools;
@@ -14,7 +14,7 @@ use std::{
     collections::{BTreeMap, HashSet},
     fmt,
     fs::File,
-    io::{BufReader, BufWriter},
+    io::BufReader,
     ops::Index,
     path::Path,
     rc::Rc,
@@ -115,13 +115,6 @@ fn load_cfgs<P: AsRef<Path>>(path: P) -> Result<BTreeMap<String, GameConfig>, Er
     Ok(cfgs)
 }
 
-fn write_cfgs<P: AsRef<Path>>(path: P, cfgs: &BTreeMap<String, GameConfig>) -> Result<(), Error> {
-    let file = File::create(path)?;
-    let file = BufWriter::new(file);
-    serde_json::to_writer_pretty(file, cfgs)?;
-    Ok(())
-}
-
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum Command {
     Sync,
@@ -474,6 +467,8 @@ fn add(siv: &mut Cursive, cfgs: &mut BTreeMap<String, GameConfig>, dats: &Dats)
             sha256,
             platform,
             layouts: vec![*layout],
+            publisher: None,
+            mapper: None,
         },
     );
 }
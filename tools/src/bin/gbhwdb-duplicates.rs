@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::{Parser, ValueEnum};
+use gbhwdb_backend::{
+    analysis::duplicates::{
+        find_duplicates, fingerprint_photos, Duplicate, DEFAULT_MAX_PHOTO_DISTANCE,
+    },
+    db::{Database, Submission},
+};
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Lists submissions that are likely duplicates of each other.
+///
+/// Consoles with the same serial number and submissions of the same game or console with identical
+/// boards and chip labels are always reported. Photos are compared only with --photos, because
+/// every photo has to be decoded.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Root directory of the repository
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    #[arg(short, long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+    /// Compare photos of all submissions
+    #[arg(long)]
+    photos: bool,
+    /// Largest perceptual hash distance (0-64) at which photos are considered near-identical
+    #[arg(long, default_value_t = DEFAULT_MAX_PHOTO_DISTANCE)]
+    max_distance: u32,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+#[derive(Serialize)]
+struct JsonSubmission<'a> {
+    category: &'static str,
+    code: &'a str,
+    slug: &'a str,
+    contributor: &'a str,
+}
+
+impl<'a> From<&'a Submission> for JsonSubmission<'a> {
+    fn from(submission: &'a Submission) -> Self {
+        JsonSubmission {
+            category: submission.category.id(),
+            code: &submission.code,
+            slug: &submission.slug,
+            contributor: &submission.contributor,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDuplicate<'a> {
+    submissions: [JsonSubmission<'a>; 2],
+    signals: Vec<String>,
+}
+
+impl<'a> From<&Duplicate<'a>> for JsonDuplicate<'a> {
+    fn from(duplicate: &Duplicate<'a>) -> Self {
+        JsonDuplicate {
+            submissions: [duplicate.a.into(), duplicate.b.into()],
+            signals: duplicate
+                .signals
+                .iter()
+                .map(|signal| signal.to_string())
+                .collect(),
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let db = Database::load(&args.root)?;
+
+    let photos = if args.photos {
+        let (photos, errors) = fingerprint_photos(&db.submissions);
+        for (path, err) in errors {
+            eprintln!("Failed to read {}: {err:#}", path.display());
+        }
+        let undecoded = photos
+            .iter()
+            .filter(|photo| photo.fingerprint.perceptual.is_none())
+            .count();
+        if undecoded > 0 {
+            eprintln!("{undecoded} photos couldn't be decoded and are only compared byte by byte");
+        }
+        photos
+    } else {
+        Vec::new()
+    };
+    let duplicates = find_duplicates(&db.submissions, &photos, args.max_distance);
+
+    let mut stdout = io::stdout().lock();
+    match args.format {
+        Format::Human => {
+            for duplicate in &duplicates {
+                let (a, b) = (duplicate.a, duplicate.b);
+                writeln!(
+                    stdout,
+                    "{} {}: {} ({}) and {} ({})",
+                    a.category.id(),
+                    a.code,
+                    a.slug,
+                    a.contributor,
+                    if a.code == b.code {
+                        b.slug.clone()
+                    } else {
+                        format!("{} {}", b.code, b.slug)
+                    },
+                    b.contributor
+                )?;
+                for signal in &duplicate.signals {
+                    writeln!(stdout, "  {signal}")?;
+                }
+            }
+            writeln!(stdout, "{} likely duplicates", duplicates.len())?;
+        }
+        Format::Json => {
+            let duplicates = duplicates
+                .iter()
+                .map(JsonDuplicate::from)
+                .collect::<Vec<_>>();
+            serde_json::to_writer_pretty(&mut stdout, &duplicates)?;
+            writeln!(stdout)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::{Parser, ValueEnum};
+use gbhwdb_backend::{config::cartridge::GamePlatform, db::Database};
+use gbhwdb_tools::dat::{dump_games, write_dat, DatHeader};
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::PathBuf,
+};
+
+/// Generates a Logiqx XML DAT file of all submitted cartridge dumps
+#[derive(Parser, Debug)]
+struct Args {
+    /// Root directory of the repository
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    /// Output file. Written to stdout if not given
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Only include games of the given platform
+    #[arg(long, value_enum)]
+    platform: Option<Platform>,
+    /// DAT version. Defaults to the date of the newest dump as YYYYMMDD
+    #[arg(long)]
+    version: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Platform {
+    Gb,
+    Gbc,
+    Gba,
+}
+
+impl From<Platform> for GamePlatform {
+    fn from(platform: Platform) -> Self {
+        match platform {
+            Platform::Gb => GamePlatform::Gb,
+            Platform::Gbc => GamePlatform::Gbc,
+            Platform::Gba => GamePlatform::Gba,
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let db = Database::load(&args.root)?;
+    let mut games = dump_games(&db)?;
+    if let Some(platform) = args.platform {
+        games.retain(|game| game.platform == platform.into());
+    }
+    let name = match args.platform.map(GamePlatform::from) {
+        Some(platform) => format!("Game Boy hardware database - {platform} cartridge dumps"),
+        None => "Game Boy hardware database - Cartridge dumps".to_owned(),
+    };
+    let version = args.version.unwrap_or_else(|| {
+        games
+            .iter()
+            .map(|game| game.last_dumped.replace('-', ""))
+            .max()
+            .unwrap_or_default()
+    });
+    let header = DatHeader {
+        description: name.clone(),
+        name,
+        version,
+        author: "Game Boy hardware database contributors".to_owned(),
+        homepage: "Game Boy hardware database".to_owned(),
+        url: "https://gbhwdb.gekkio.fi".to_owned(),
+    };
+    match args.output {
+        Some(path) => write_dat(BufWriter::new(File::create(path)?), &header, &games),
+        None => write_dat(io::stdout().lock(), &header, &games),
+    }
+}
@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::Parser;
+use gbhwdb_backend::format::format_all;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+/// Rewrites config/games.json and all metadata.json files in canonical form.
+///
+/// Files are migrated to the current schema_version, strings are trimmed, and whitespace in labels
+/// is collapsed. Files that would lose data in the round trip are reported and left untouched.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Root directory of the repository
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    /// List files that aren't canonical without rewriting them
+    #[arg(long)]
+    check: bool,
+}
+
+fn main() -> Result<ExitCode, Error> {
+    let args = Args::parse();
+    let (mut failed, mut changed) = (0, 0);
+    for result in format_all(&args.root)? {
+        match result {
+            Ok(file) if file.is_canonical() => (),
+            Ok(file) => {
+                changed += 1;
+                println!("{}", file.path.display());
+                if !args.check {
+                    fs::write(&file.path, &file.new)?;
+                }
+            }
+            Err(err) => {
+                failed += 1;
+                eprintln!("{err:#}");
+            }
+        }
+    }
+    if args.check {
+        eprintln!("{changed} files need formatting, {failed} files failed");
+    } else {
+        eprintln!("Formatted {changed} files, {failed} files failed");
+    }
+    if failed > 0 || (args.check && changed > 0) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::{Parser, ValueEnum};
+use gbhwdb_backend::lint::{lint, Severity};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+/// Validates all submission data and game configs without building the site.
+///
+/// Exits with a non-zero status if any problems at least as severe as --fail-on are found.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Root directory of the repository
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    #[arg(short, long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+    /// Lowest severity that causes a non-zero exit status: warning, error or fatal
+    #[arg(long, default_value = "error")]
+    fail_on: Severity,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+
+fn main() -> Result<ExitCode, Error> {
+    let args = Args::parse();
+    let report = lint(&args.root);
+
+    let mut stdout = io::stdout().lock();
+    match args.format {
+        Format::Human => {
+            for problem in &report.problems {
+                writeln!(stdout, "{problem}")?;
+            }
+            writeln!(
+                stdout,
+                "{} fatal, {} errors, {} warnings",
+                report.count(Severity::Fatal),
+                report.count(Severity::Error),
+                report.count(Severity::Warning)
+            )?;
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &report)?;
+            writeln!(stdout)?;
+        }
+    }
+    if report.exceeds(args.fail_on) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
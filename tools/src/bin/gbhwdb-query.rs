@@ -0,0 +1,240 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::{Parser, ValueEnum};
+use gbhwdb_backend::db::{
+    expr::{Expr, Field, Value},
+    Database, Submission, SubmissionChip,
+};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Queries submissions using the same parsers as the site.
+///
+/// Expressions compare fields with =, !=, <, <=, >, >= or ~ (contains), and can be combined with
+/// and, or, not and parentheses. Chip fields in one expression must all match the same chip.
+///
+/// Example: gbhwdb-query "console = DMG and chip.role = WRAM and chip.kind ~ LH5164 and chip.year < 1991"
+#[derive(Parser, Debug)]
+#[command(verbatim_doc_comment)]
+struct Args {
+    /// Filter expression. All submissions are included if not given
+    expr: Option<String>,
+    /// Root directory of the repository
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Output one row per matching chip instead of one per submission
+    #[arg(long)]
+    chips: bool,
+    /// Comma-separated list of output fields
+    #[arg(long, value_delimiter = ',')]
+    fields: Vec<Field>,
+    /// Count results grouped by the given comma-separated fields
+    #[arg(long, value_delimiter = ',')]
+    group_by: Vec<Field>,
+    /// List all available fields and exit
+    #[arg(long)]
+    list_fields: bool,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+const SUBMISSION_FIELDS: &[Field] = &[
+    Field::Code,
+    Field::Slug,
+    Field::Title,
+    Field::Contributor,
+    Field::Board,
+];
+
+const CHIP_FIELDS: &[Field] = &[
+    Field::Code,
+    Field::Slug,
+    Field::ChipDesignator,
+    Field::ChipRole,
+    Field::ChipKind,
+    Field::ChipManufacturer,
+    Field::ChipYear,
+    Field::ChipWeek,
+];
+
+type Row<'a> = (&'a Submission, Option<&'a SubmissionChip>);
+
+fn select_rows<'a>(db: &'a Database, expr: Option<&'a Expr>, per_chip: bool) -> Vec<Row<'a>> {
+    let mut rows = Vec::new();
+    for submission in &db.submissions {
+        match expr {
+            Some(expr) if per_chip => rows.extend(
+                expr.matching_chips(submission)
+                    .map(|chip| (submission, Some(chip))),
+            ),
+            Some(expr) => {
+                if expr.matches(submission) {
+                    rows.push((submission, None));
+                }
+            }
+            None if per_chip => {
+                rows.extend(submission.chips.iter().map(|chip| (submission, Some(chip))))
+            }
+            None => rows.push((submission, None)),
+        }
+    }
+    rows
+}
+
+/// Counts rows by the values of the given fields, largest groups first
+fn group_rows(rows: &[Row], fields: &[Field]) -> Vec<Vec<Value>> {
+    let mut counts: BTreeMap<Vec<Value>, u32> = BTreeMap::new();
+    for &(submission, chip) in rows {
+        let key = fields
+            .iter()
+            .map(|field| field.value(submission, chip))
+            .collect();
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut groups = counts.into_iter().collect::<Vec<_>>();
+    groups.sort_by(|(_, a), (_, b)| b.cmp(a));
+    groups
+        .into_iter()
+        .map(|(mut key, count)| {
+            key.push(Value::Number(count));
+            key
+        })
+        .collect()
+}
+
+fn write_table(mut w: impl Write, header: &[&str], records: &[Vec<Value>]) -> Result<(), Error> {
+    let records = records
+        .iter()
+        .map(|record| record.iter().map(|value| value.to_string()).collect())
+        .collect::<Vec<Vec<String>>>();
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            records
+                .iter()
+                .map(|record| record[idx].chars().count())
+                .fold(name.len(), usize::max)
+        })
+        .collect::<Vec<_>>();
+    let mut write_line = |cells: &[&str]| {
+        let mut line = String::new();
+        for (cell, width) in cells.iter().zip(&widths) {
+            line.push_str(&format!("{cell:width$}  "));
+        }
+        writeln!(w, "{}", line.trim_end())
+    };
+    write_line(header)?;
+    let separator = widths
+        .iter()
+        .map(|&width| "-".repeat(width))
+        .collect::<Vec<_>>();
+    write_line(&separator.iter().map(String::as_str).collect::<Vec<_>>())?;
+    for record in &records {
+        write_line(&record.iter().map(String::as_str).collect::<Vec<_>>())?;
+    }
+    Ok(())
+}
+
+fn write_csv(w: impl Write, header: &[&str], records: &[Vec<Value>]) -> Result<(), Error> {
+    let mut w = csv::Writer::from_writer(w);
+    w.write_record(header)?;
+    for record in records {
+        w.write_record(record.iter().map(|value| value.to_string()))?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+/// JSON object with the fields in output order
+struct JsonRecord<'a> {
+    header: &'a [&'a str],
+    values: &'a [Value],
+}
+
+impl<'a> Serialize for JsonRecord<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.header.len()))?;
+        for (name, value) in self.header.iter().zip(self.values) {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+fn write_json(mut w: impl Write, header: &[&str], records: &[Vec<Value>]) -> Result<(), Error> {
+    let records = records
+        .iter()
+        .map(|values| JsonRecord { header, values })
+        .collect::<Vec<_>>();
+    serde_json::to_writer_pretty(&mut w, &records)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    if args.list_fields {
+        for field in Field::ALL {
+            println!("{field}");
+        }
+        return Ok(());
+    }
+    let expr = args.expr.as_deref().map(Expr::parse).transpose()?;
+    let db = Database::load(&args.root)?;
+
+    let per_chip = args.chips
+        || args
+            .fields
+            .iter()
+            .chain(&args.group_by)
+            .any(|field| field.is_chip_field());
+    let rows = select_rows(&db, expr.as_ref(), per_chip);
+
+    let (header, records) = if !args.group_by.is_empty() {
+        let mut header = args
+            .group_by
+            .iter()
+            .map(|field| field.name())
+            .collect::<Vec<_>>();
+        header.push("count");
+        (header, group_rows(&rows, &args.group_by))
+    } else {
+        let fields = match args.fields.as_slice() {
+            [] if per_chip => CHIP_FIELDS,
+            [] => SUBMISSION_FIELDS,
+            fields => fields,
+        };
+        let records = rows
+            .iter()
+            .map(|&(submission, chip)| {
+                fields
+                    .iter()
+                    .map(|field| field.value(submission, chip))
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        (fields.iter().map(|field| field.name()).collect(), records)
+    };
+
+    let stdout = io::stdout().lock();
+    match args.format {
+        Format::Table => write_table(stdout, &header, &records),
+        Format::Csv => write_csv(stdout, &header, &records),
+        Format::Json => write_json(stdout, &header, &records),
+    }
+}
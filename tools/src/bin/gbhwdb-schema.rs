@@ -0,0 +1,33 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::Parser;
+use gbhwdb_backend::{
+    format::to_canonical_json,
+    schema::{submission_schemas, SCHEMA_DIR},
+};
+use std::{fs, path::PathBuf};
+
+/// Writes JSON Schemas of all metadata.json formats.
+///
+/// Submission files can refer to the schemas with a "$schema" key, e.g.
+/// "../../../../schemas/dmg.schema.json".
+#[derive(Parser, Debug)]
+struct Args {
+    /// Output directory
+    #[arg(short, long, default_value = SCHEMA_DIR)]
+    output: PathBuf,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    fs::create_dir_all(&args.output)?;
+    for (file, schema) in submission_schemas() {
+        let path = args.output.join(file);
+        fs::write(&path, to_canonical_json(&schema)?)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
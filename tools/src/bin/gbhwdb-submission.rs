@@ -6,11 +6,14 @@ use anyhow::Error;
 use cursive::{traits::*, views::*, Cursive, CursiveExt};
 use gbhwdb_backend::{
     config::cartridge::{BoardLayout, ChipRole, ChipRoleConfig, GameConfig},
+    format::to_canonical_json,
     input::{
         cartridge::{Cartridge, CartridgeBoard, CartridgeShell},
         Chip,
     },
+    migration::SCHEMA_VERSION,
     parser::{self, LabelParser},
+    schema::{schema_ref, CARTRIDGE_SCHEMA},
     time::Month,
 };
 use gbhwdb_tools::cursive::*;
@@ -50,7 +53,7 @@ fn main() -> Result<(), Error> {
         Ok(())
     } else {
         while let Some((root, cartridge)) = ask_submission(&mut siv, &cfgs, &contributor) {
-            let json = serde_json::to_string_pretty(&cartridge)?;
+            let json = to_canonical_json(&cartridge)?;
             siv.add_layer(
                 Dialog::new()
                     .title(root.display().to_string())
@@ -113,6 +116,8 @@ fn ask_submission(
                 return Some((
                     root,
                     Cartridge {
+                        schema: Some(schema_ref(CARTRIDGE_SCHEMA)),
+                        schema_version: SCHEMA_VERSION,
                         code,
                         slug,
                         contributor: contributor.to_owned(),
@@ -250,7 +255,8 @@ fn ask_board(siv: &mut Cursive) -> Option<CartridgeBoard> {
                     .child(chip_editor("u5", chips.u5))
                     .child(chip_editor("u6", chips.u6))
                     .child(chip_editor("u7", chips.u7))
-                    .child(chip_editor("x1", chips.x1)),
+                    .child(chip_editor("x1", chips.x1))
+                    .child(chip_editor("bt1", chips.bt1)),
             )
             .button("Ok", |s| s.quit())
             .fixed_width(150),
@@ -270,6 +276,7 @@ fn ask_board(siv: &mut Cursive) -> Option<CartridgeBoard> {
         u6: add_chip(siv, chips.u6, "u6"),
         u7: add_chip(siv, chips.u7, "u7"),
         x1: add_chip(siv, chips.x1, "x1"),
+        bt1: add_chip(siv, chips.bt1, "bt1"),
         outlier: false,
     });
     siv.pop_layer();
@@ -310,6 +317,9 @@ fn chip_editor(id: &str, role: Option<ChipRole>) -> LinearLayout {
             ChipRole::Flash => {
                 add_details_callback(&mut editor, &details_id, parser::flash::flash())
             }
+            ChipRole::Battery => {
+                add_details_callback(&mut editor, &details_id, parser::battery::battery_cr2025())
+            }
             ChipRole::Eeprom => {
                 add_details_callback(&mut editor, &details_id, parser::eeprom::eeprom())
             }
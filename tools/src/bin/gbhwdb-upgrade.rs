@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2017-2023 Joonas Javanainen <joonas.javanainen@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use anyhow::Error;
+use clap::Parser;
+use gbhwdb_backend::migration::{upgrade_all, SCHEMA_VERSION};
+use std::{fs, path::PathBuf};
+
+/// Upgrades all metadata.json files in the data directory to the current schema_version.
+///
+/// Older files can still be loaded, but upgrading them keeps the data consistent with the schemas.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Root directory of the repository
+    #[arg(long, default_value = ".")]
+    root: PathBuf,
+    /// Only show what would change
+    #[arg(long)]
+    dry_run: bool,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let upgrades = upgrade_all(&args.root)?;
+    let (mut total_added, mut total_removed) = (0, 0);
+    for upgrade in &upgrades {
+        let (added, removed) = upgrade.line_changes();
+        total_added += added;
+        total_removed += removed;
+        let migrations = upgrade
+            .migrations
+            .iter()
+            .map(|migration| migration.description)
+            .collect::<Vec<_>>();
+        println!(
+            "{}: {} -> {SCHEMA_VERSION} (+{added} -{removed}): {}",
+            upgrade.path.display(),
+            upgrade.from_version,
+            migrations.join(", ")
+        );
+        if !args.dry_run {
+            fs::write(&upgrade.path, &upgrade.new)?;
+        }
+    }
+    println!(
+        "{} {} files to schema_version {SCHEMA_VERSION} (+{total_added} -{total_removed} lines)",
+        if args.dry_run {
+            "Would upgrade"
+        } else {
+            "Upgraded"
+        },
+        upgrades.len()
+    );
+    Ok(())
+}
@@ -2,10 +2,16 @@
 //
 // SPDX-License-Identifier: MIT
 
-use anyhow::Error;
-use gbhwdb_backend::sha256::Sha256;
+use anyhow::{anyhow, Error};
+use gbhwdb_backend::{
+    config::cartridge::GamePlatform,
+    db::{Category, Database},
+    input::cartridge::CartridgeDump,
+    sha256::Sha256,
+};
 use retro_dat::{DatReader, Status};
-use std::{collections::HashMap, path::Path};
+use serde::Deserialize;
+use std::{borrow::Cow, collections::HashMap, io::Write, path::Path};
 
 #[derive(Clone, Debug)]
 pub struct DatFile {
@@ -56,3 +62,250 @@ pub fn from_path<P: AsRef<Path>>(path: P) -> Result<DatFile, Error> {
         games,
     })
 }
+
+/// Header of a generated DAT file
+#[derive(Clone, Debug)]
+pub struct DatHeader {
+    pub name: String,
+    pub description: String,
+    pub version: String,
+    pub author: String,
+    pub homepage: String,
+    pub url: String,
+}
+
+/// A game in a generated DAT file, with one ROM and the submissions it was dumped from
+#[derive(Clone, Debug)]
+pub struct DumpGame {
+    pub name: String,
+    pub platform: GamePlatform,
+    pub sha256: Sha256,
+    /// True if the hash matches a verified dump in the No-Intro DAT
+    pub verified: bool,
+    pub sources: Vec<String>,
+    /// Date of the newest dump as YYYY-MM-DD
+    pub last_dumped: String,
+}
+
+impl DumpGame {
+    pub fn rom_name(&self) -> String {
+        let extension = match self.platform {
+            GamePlatform::Gb => "gb",
+            GamePlatform::Gbc => "gbc",
+            GamePlatform::Gba => "gba",
+        };
+        format!("{}.{extension}", self.name)
+    }
+}
+
+/// Collects all cartridge dumps in the database, grouped by game and hash.
+///
+/// If a game has dumps with different hashes, the ones that don't match the No-Intro hash get
+/// `(Alt N)` added to their name.
+pub fn dump_games(db: &Database) -> Result<Vec<DumpGame>, Error> {
+    let mut games: Vec<(&str, DumpGame)> = Vec::new();
+    for submission in &db.submissions {
+        if submission.category != Category::Cartridge {
+            continue;
+        }
+        let dump = match submission.metadata.get("dump") {
+            Some(dump) => CartridgeDump::deserialize(dump)?,
+            None => continue,
+        };
+        let cfg = db
+            .cfgs
+            .get(&submission.code)
+            .ok_or_else(|| anyhow!("unknown game code {}", submission.code))?;
+        let rom = submission
+            .chips
+            .iter()
+            .find(|chip| chip.role == "ROM")
+            .and_then(|chip| chip.chip.label.as_deref())
+            .unwrap_or("unknown");
+        let source = format!(
+            "{}/{} by {}: board {}, ROM {rom}, dumped with {} on {}",
+            submission.code,
+            submission.slug,
+            submission.contributor,
+            submission.board,
+            dump.tool,
+            dump.date,
+        );
+        let existing = games
+            .iter_mut()
+            .find(|(code, game)| *code == submission.code && game.sha256 == dump.sha256);
+        match existing {
+            Some((_, game)) => {
+                game.sources.push(source);
+                game.last_dumped = game.last_dumped.clone().max(dump.date.to_string());
+            }
+            None => games.push((
+                &submission.code,
+                DumpGame {
+                    name: cfg.name.clone(),
+                    platform: cfg.platform,
+                    sha256: dump.sha256,
+                    verified: cfg.rom_verified && cfg.sha256 == Some(dump.sha256),
+                    sources: vec![source],
+                    last_dumped: dump.date.to_string(),
+                },
+            )),
+        }
+    }
+    // Dumps matching the No-Intro hash come first and keep the plain name
+    games.sort_by_key(|(code, game)| (*code, db.cfgs[*code].sha256 != Some(game.sha256)));
+    let mut alt = 0;
+    for idx in 1..games.len() {
+        let prev_code = games[idx - 1].0;
+        let (code, game) = &mut games[idx];
+        if *code == prev_code {
+            alt += 1;
+            game.name = format!("{} (Alt {alt})", game.name);
+        } else {
+            alt = 0;
+        }
+    }
+    let mut games = games.into_iter().map(|(_, game)| game).collect::<Vec<_>>();
+    games.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(games)
+}
+
+/// Writes a DAT file in the Logiqx XML format.
+///
+/// The DAT is SHA-256 only: submissions only record the SHA-256 of the dump, so ROM entries have
+/// no size or CRC32. The Logiqx DTD requires a size for every ROM, so the file has no DOCTYPE and
+/// isn't valid against the DTD. Dump sources are written as game comments.
+pub fn write_dat<W: Write>(mut w: W, header: &DatHeader, games: &[DumpGame]) -> Result<(), Error> {
+    writeln!(w, r#"<?xml version="1.0"?>"#)?;
+    writeln!(w, "<datafile>")?;
+    writeln!(w, "\t<header>")?;
+    for (tag, value) in [
+        ("name", &header.name),
+        ("description", &header.description),
+        ("version", &header.version),
+        ("author", &header.author),
+        ("homepage", &header.homepage),
+        ("url", &header.url),
+    ] {
+        writeln!(w, "\t\t<{tag}>{}</{tag}>", escape(value))?;
+    }
+    writeln!(w, "\t</header>")?;
+    for game in games {
+        let name = escape(&game.name);
+        writeln!(w, "\t<game name=\"{name}\">")?;
+        for source in &game.sources {
+            writeln!(w, "\t\t<comment>{}</comment>", escape(source))?;
+        }
+        writeln!(w, "\t\t<description>{name}</description>")?;
+        let status = if game.verified {
+            r#" status="verified""#
+        } else {
+            ""
+        };
+        writeln!(
+            w,
+            "\t\t<rom name=\"{}\" sha256=\"{}\"{status}/>",
+            escape(&game.rom_name()),
+            game.sha256
+        )?;
+        writeln!(w, "\t</game>")?;
+    }
+    writeln!(w, "</datafile>")?;
+    w.flush()?;
+    Ok(())
+}
+
+fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(ch),
+        }
+    }
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+fn test_database() -> Database {
+    use gbhwdb_backend::{config::cartridge::GameConfig, db::Submission};
+
+    let sha256 = |byte: &str| Sha256::parse(&byte.repeat(32)).unwrap();
+    let cfg = |name: &str, sha256| GameConfig {
+        rom_id: String::new(),
+        name: name.to_owned(),
+        rom_verified: true,
+        sha256: Some(sha256),
+        platform: GamePlatform::Gb,
+        layouts: Vec::new(),
+        publisher: None,
+        mapper: None,
+    };
+    let submission = |code: &str, slug: &str, sha256: Sha256, date: &str| Submission {
+        category: Category::Cartridge,
+        code: code.to_owned(),
+        slug: slug.to_owned(),
+        contributor: "test".to_owned(),
+        title: slug.to_owned(),
+        board: "DMG-AAA-01".to_owned(),
+        outlier: false,
+        chips: Vec::new(),
+        photos: Vec::new(),
+        metadata: serde_json::json!({
+            "dump": { "tool": "test", "date": date, "sha256": sha256.to_string() }
+        }),
+    };
+    Database {
+        cfgs: [
+            ("DMG-AA-0".to_owned(), cfg("Game A", sha256("aa"))),
+            ("DMG-BB-0".to_owned(), cfg("Game B", sha256("bb"))),
+        ]
+        .into_iter()
+        .collect(),
+        submissions: vec![
+            submission("DMG-AA-0", "test-1", sha256("a1"), "2020-01-01"),
+            submission("DMG-AA-0", "test-2", sha256("aa"), "2020-01-02"),
+            submission("DMG-AA-0", "test-3", sha256("aa"), "2021-05-01"),
+            submission("DMG-AA-0", "test-4", sha256("a2"), "2020-01-04"),
+            submission("DMG-BB-0", "test-5", sha256("b1"), "2020-01-05"),
+        ],
+    }
+}
+
+#[test]
+fn test_dump_games() {
+    let games = dump_games(&test_database()).unwrap();
+    let names = games
+        .iter()
+        .map(|game| game.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["Game A", "Game A (Alt 1)", "Game A (Alt 2)", "Game B"]
+    );
+    let game = &games[0];
+    assert_eq!(game.sha256.to_string(), "aa".repeat(32));
+    assert!(game.verified);
+    assert_eq!(game.sources.len(), 2);
+    assert_eq!(game.last_dumped, "2021-05-01");
+    assert_eq!(game.rom_name(), "Game A.gb");
+    assert!(!games[1].verified);
+    assert_eq!(games[3].sha256.to_string(), "b1".repeat(32));
+    assert!(!games[3].verified);
+}
+
+#[test]
+fn test_escape() {
+    assert!(matches!(escape("Game A"), Cow::Borrowed("Game A")));
+    assert_eq!(
+        escape(r#"Tom & Jerry <"Pokémon's">"#),
+        "Tom &amp; Jerry &lt;&quot;Pokémon&apos;s&quot;&gt;"
+    );
+}